@@ -1,4 +1,5 @@
 use crate::ctx::Ctx;
+use crate::model::store::migrator::split_sql_statements;
 use crate::model::user::{User, UserBmc};
 use crate::model::ModelManager;
 use sqlx::postgres::PgPoolOptions;
@@ -43,7 +44,12 @@ pub async fn init_dev_db() -> Result<(), Box<dyn std::error::Error>> {
         pexec(&root_db, &sql_recreate_db_file).await?;
     }
 
-    // -- Get SQL files.
+    // -- Init Model Layer and apply the schema migrations (sql/migrations).
+    let mm = ModelManager::new_without_migrate().await?;
+    mm.migrate_up().await?;
+    let ctx = Ctx::root_ctx();
+
+    // -- Get SQL files (dev seed data).
     let mut paths: Vec<PathBuf> = fs::read_dir(sql_dir)?
         .filter_map(|entry| entry.ok().map(|e| e.path()))
         .collect();
@@ -59,10 +65,6 @@ pub async fn init_dev_db() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // -- Init Model Layer
-    let mm = ModelManager::new().await?;
-    let ctx = Ctx::root_ctx();
-
     // -- Set demo1 pwd
     let demo1_user: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
         .await?
@@ -80,10 +82,7 @@ async fn pexec(db: &Db, file: &Path) -> Result<(), sqlx::Error> {
     // Read the file.
     let content = fs::read_to_string(file)?;
 
-    // FIXME: Make the split more sql proof.
-    let sqls: Vec<&str> = content.split(";").collect();
-
-    for sql in sqls {
+    for sql in split_sql_statements(&content) {
         sqlx::query(sql).execute(db).await?;
     }
 
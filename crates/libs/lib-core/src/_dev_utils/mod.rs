@@ -55,6 +55,7 @@ pub async fn seed_tasks(ctx: &Ctx, mm: &ModelManager, project_id: i64, titles: &
             TaskForCreate {
                 project_id,
                 title: title.to_string(),
+                ..Default::default()
             },
         )
         .await?;
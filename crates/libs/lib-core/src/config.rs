@@ -1,4 +1,4 @@
-use lib_utils::envs::get_env;
+use lib_utils::envs::{get_env, get_env_parse};
 use std::sync::OnceLock;
 
 pub fn core_config() -> &'static CoreConfig {
@@ -15,9 +15,13 @@ pub fn core_config() -> &'static CoreConfig {
 pub struct CoreConfig {
     // -- Db
     pub DB_URL: String,
+    pub DB_AUTO_MIGRATE: bool,
 
     // -- Web
     pub WEB_FOLDER: String,
+
+    // -- Attachments
+    pub ATTACHMENT_FOLDER: String,
 }
 
 impl CoreConfig {
@@ -25,9 +29,13 @@ impl CoreConfig {
         Ok(CoreConfig {
             // -- Db
             DB_URL: get_env("SERVICE_DB_URL")?,
+            DB_AUTO_MIGRATE: get_env_parse("SERVICE_DB_AUTO_MIGRATE")?,
 
             // -- Web
             WEB_FOLDER: get_env("SERVICE_WEB_FOLDER")?,
+
+            // -- Attachments
+            ATTACHMENT_FOLDER: get_env("SERVICE_ATTACHMENT_FOLDER")?,
         })
     }
 }
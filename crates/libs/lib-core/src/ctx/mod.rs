@@ -31,4 +31,8 @@ impl Ctx {
     pub fn user_id(&self) -> i64 {
         self.user_id
     }
+
+    pub fn is_root(&self) -> bool {
+        self.user_id == 0
+    }
 }
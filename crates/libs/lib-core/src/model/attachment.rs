@@ -0,0 +1,423 @@
+//! Task attachments, the files (e.g., logs, screenshots) attached to a task.
+//!
+//! Design:
+//!
+//! - The `attachment` row holds the file metadata, and the content is in the attachment
+//!   storage (see `model::storage`), under the generated `storage_key`.
+//! - The users who can update a task can attach files to it and delete its attachments.
+//!   The users who can read a task can read its attachments.
+//! - The size and sha256 are computed while the content is written to the storage.
+//!
+//! Note: The storage objects of the attachments deleted with their task (db cascade)
+//!       are not deleted.
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessLevel, CommonIden, DbBmc, SoftDeleteIden};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::storage::StorageReader;
+use crate::model::task::{Task, TaskBmc};
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue};
+use sea_query::{Condition, Expr, Iden, Query};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+use uuid::Uuid;
+
+// endregion:   --- Modules
+
+// region:      --- Attachment Types
+
+/// Max length of the `filename` and `content_type` (see the `attachment` table).
+const METADATA_MAX_LEN: usize = 256;
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub task_id: i64,
+
+    pub uploader_id: i64,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    /// Hex encoded sha256 of the content.
+    pub sha256: String,
+    #[serde(skip)]
+    pub storage_key: String,
+
+    // -- Timestamps
+    //    (creator and last modified user_id/time)
+    pub cid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub ctime: OffsetDateTime,
+    pub mid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub mtime: OffsetDateTime,
+}
+
+/// The metadata given by the uploader (the other metadata is computed).
+pub struct AttachmentForCreate {
+    pub task_id: i64,
+    pub filename: String,
+    pub content_type: Option<String>,
+}
+
+#[derive(Fields)]
+struct AttachmentForInsert {
+    task_id: i64,
+    uploader_id: i64,
+    filename: String,
+    content_type: String,
+    size: i64,
+    sha256: String,
+    storage_key: String,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+pub struct AttachmentFilter {
+    pub id: Option<OpValsInt64>,
+    pub task_id: Option<OpValsInt64>,
+    pub uploader_id: Option<OpValsInt64>,
+    pub filename: Option<OpValsString>,
+    pub content_type: Option<OpValsString>,
+
+    pub cid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub ctime: Option<OpValsValue>,
+    pub mid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum AttachmentIden {
+    TaskId,
+}
+
+// endregion:   --- Attachment Types
+
+// region:      --- AttachmentBmc
+
+pub struct AttachmentBmc;
+
+impl DbBmc for AttachmentBmc {
+    const TABLE: &'static str = "attachment";
+
+    /// The attachments of the (non deleted) tasks, with the task access level.
+    fn access_cond(ctx: &Ctx, level: AccessLevel) -> Option<Condition> {
+        let mut task_ids = Query::select();
+        task_ids
+            .column(CommonIden::Id)
+            .from(TaskBmc::table_ref())
+            .cond_where(TaskBmc::access_cond(ctx, level)?)
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+
+        Some(Condition::all().add(Expr::col(AttachmentIden::TaskId).in_subquery(task_ids)))
+    }
+
+    /// The attachments are deleted and restored with their task (and its project).
+    fn not_deleted_parent_cond() -> Option<Condition> {
+        let task_ids = TaskBmc::not_deleted_ids_query();
+
+        Some(Condition::all().add(Expr::col(AttachmentIden::TaskId).in_subquery(task_ids)))
+    }
+}
+
+impl AttachmentBmc {
+    /// Store the `reader` content, and create its attachment, uploaded by the ctx user.
+    pub async fn create(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        attachment_c: AttachmentForCreate,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<i64> {
+        let AttachmentForCreate {
+            task_id,
+            filename,
+            content_type,
+        } = attachment_c;
+
+        // -- Check access
+        //    (before storing the content)
+        let _task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+        base::ensure_access::<TaskBmc>(ctx, mm, AccessLevel::Write, task_id).await?;
+
+        // -- Store the content
+        let storage_key = Uuid::new_v4().to_string();
+        let mut reader = Sha256Reader::new(reader);
+        let size = mm.storage().put(&storage_key, &mut reader).await?;
+
+        // -- Create the attachment
+        let attachment_i = AttachmentForInsert {
+            task_id,
+            uploader_id: ctx.user_id(),
+            filename: sanitize_filename(&filename),
+            content_type: sanitize_content_type(content_type.as_deref()),
+            size: size as i64,
+            sha256: reader.hex_digest(),
+            storage_key: storage_key.clone(),
+        };
+        let res = base::create::<Self, _>(ctx, mm, attachment_i).await;
+        if res.is_err() {
+            let _ = mm.storage().delete(&storage_key).await;
+        }
+
+        res
+    }
+
+    pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Attachment> {
+        base::get::<Self, _>(ctx, mm, id).await
+    }
+
+    /// Returns the attachment with the reader of its content.
+    pub async fn open(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+    ) -> Result<(Attachment, StorageReader)> {
+        let attachment = Self::get(ctx, mm, id).await?;
+        let reader = mm.storage().get(&attachment.storage_key).await?;
+
+        Ok((attachment, reader))
+    }
+
+    /// List the attachments of the task.
+    ///
+    /// default order: `ctime` (the oldest first)
+    pub async fn list_for_task(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Attachment>> {
+        let _task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+
+        let filter = AttachmentFilter {
+            task_id: Some(task_id.into()),
+            ..Default::default()
+        };
+        let mut list_options = list_options.unwrap_or_default();
+        if list_options.order_bys.is_none() {
+            list_options.order_bys = Some("ctime".into());
+        }
+
+        base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), Some(list_options)).await
+    }
+
+    /// Delete the attachment, and its content once the transaction is committed.
+    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        let attachment = Self::get(ctx, mm, id).await?;
+        mm.in_txn(|mm| async move {
+            base::delete::<Self>(ctx, &mm, id).await?;
+            mm.delete_storage_after_commit(attachment.storage_key).await
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- AttachmentBmc
+
+// region:      --- Attachment Utils
+
+/// Keeps the file name of the (client) path, without the quote and control chars.
+fn sanitize_filename(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(METADATA_MAX_LEN)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        "file".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn sanitize_content_type(content_type: Option<&str>) -> String {
+    match content_type.map(str::trim) {
+        Some(content_type)
+            if !content_type.is_empty()
+                && content_type.len() <= METADATA_MAX_LEN
+                && !content_type.chars().any(|c| c.is_control()) =>
+        {
+            content_type.to_string()
+        }
+        _ => DEFAULT_CONTENT_TYPE.to_string(),
+    }
+}
+
+/// Computes the sha256 of the content read through it.
+struct Sha256Reader<'r> {
+    inner: &'r mut (dyn AsyncRead + Send + Unpin),
+    hasher: Sha256,
+}
+
+impl<'r> Sha256Reader<'r> {
+    fn new(inner: &'r mut (dyn AsyncRead + Send + Unpin)) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    fn hex_digest(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+impl AsyncRead for Sha256Reader<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled_len = buf.filled().len();
+        ready!(Pin::new(&mut *self.inner).poll_read(cx, buf))?;
+        self.hasher.update(&buf.filled()[filled_len..]);
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+// endregion:   --- Attachment Utils
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::project::ProjectBmc;
+    use crate::model::project_member::{ProjectMemberBmc, ProjectRole};
+    use crate::model::user::{User, UserBmc};
+    use crate::model::Error;
+    use anyhow::{Context, Result};
+    use serial_test::serial;
+    use tokio::io::AsyncReadExt;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_attachment_create_open_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_attachment_create_open_ok project").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["bug 01"])
+            .await?
+            .remove(0);
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let demo1_ctx = Ctx::new(demo1.id)?;
+        ProjectMemberBmc::add(&ctx, &mm, fx_project_id, demo1.id, ProjectRole::Viewer).await?;
+        let fx_content = b"line 1\nline 2\n";
+        let fx_attachment_c = || AttachmentForCreate {
+            task_id: fx_task.id,
+            filename: "C:\\logs\\app.log".to_string(),
+            content_type: Some("text/plain".to_string()),
+        };
+
+        // -- Exec
+        let id = AttachmentBmc::create(&ctx, &mm, fx_attachment_c(), &mut &fx_content[..]).await?;
+        let res =
+            AttachmentBmc::create(&demo1_ctx, &mm, fx_attachment_c(), &mut &fx_content[..]).await;
+
+        // -- Check
+        assert!(
+            matches!(res, Err(Error::AccessDenied { entity: "task", .. })),
+            "viewer should not attach files"
+        );
+        let (attachment, mut reader) = AttachmentBmc::open(&demo1_ctx, &mm, id).await?;
+        assert_eq!(attachment.filename, "app.log");
+        assert_eq!(attachment.size, fx_content.len() as i64);
+        assert_eq!(
+            attachment.sha256,
+            format!("{:x}", Sha256::digest(fx_content))
+        );
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content).await?;
+        assert_eq!(content, fx_content);
+
+        AttachmentBmc::delete(&ctx, &mm, id).await?;
+        let res = mm.storage().get(&attachment.storage_key).await;
+        assert!(res.is_err(), "content should be deleted");
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_attachment_delete_rollback_keeps_content_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id = _dev_utils::seed_project(
+            &ctx,
+            &mm,
+            "test_attachment_delete_rollback_keeps_content_ok project",
+        )
+        .await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["bug 01"])
+            .await?
+            .remove(0);
+        let fx_attachment_c = AttachmentForCreate {
+            task_id: fx_task.id,
+            filename: "app.log".to_string(),
+            content_type: None,
+        };
+        let fx_content = b"line 1\n";
+        let id = AttachmentBmc::create(&ctx, &mm, fx_attachment_c, &mut &fx_content[..]).await?;
+
+        // -- Exec
+        let res: Result<()> = mm
+            .in_txn(|mm| {
+                let ctx = ctx.clone();
+                async move {
+                    AttachmentBmc::delete(&ctx, &mm, id).await?;
+                    Err(anyhow::anyhow!("fail after the delete"))
+                }
+            })
+            .await;
+
+        // -- Check
+        assert!(res.is_err());
+        let (_, mut reader) = AttachmentBmc::open(&ctx, &mm, id).await?;
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content).await?;
+        assert_eq!(
+            content, fx_content,
+            "rolled back delete should keep the content"
+        );
+
+        // -- Clean
+        AttachmentBmc::delete(&ctx, &mm, id).await?;
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
@@ -0,0 +1,426 @@
+//! Batch mutations (`create_many`, `update_many`, `delete_many`).
+//!
+//! Design:
+//!
+//! - Each batch is a single multi-row statement, executed in a `Dbx` transaction
+//!   with its change log rows (one per mutated entity row).
+//! - The targeted rows are locked (`FOR UPDATE`) with their before snapshots, and the
+//!   mutation is then applied to those exact rows.
+//! - The result has one `BatchItemResult` per item (created/targeted entity), in order.
+//!   For an id list target, the ids not found (or soft deleted) are reported as `NotFound`,
+//!   and the ids not accessible to the ctx user as `AccessDenied`, rather than failing
+//!   the whole batch.
+
+use super::crud_fns::{inaccessible_ids, not_deleted_cond, scope_cond, update_access_level};
+use crate::ctx::Ctx;
+use crate::model::base::{
+    prep_fields_for_create, prep_fields_for_soft_delete, prep_fields_for_update, AccessLevel,
+    CommonIden, DbBmc, BATCH_SIZE_MAX,
+};
+use crate::model::change_log::{ChangeLogBmc, ChangeOp};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use modql::field::{Fields, HasFields};
+use modql::filter::FilterGroups;
+use sea_query::{Condition, Expr, PostgresQueryBuilder, Query, SimpleExpr};
+use sea_query_binder::SqlxBinder;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// region:      --- Batch Types
+
+/// The rows targeted by `update_many` or `delete_many`.
+#[derive(Debug)]
+pub enum BatchTarget<F> {
+    Ids(Vec<i64>),
+    Filter(F),
+}
+
+impl<F> BatchTarget<F> {
+    /// Returns the target from exactly one of an id list or a filter
+    /// (e.g., from rpc params), otherwise `Error::BatchTargetInvalid`.
+    pub fn from_ids_or_filter(ids: Option<Vec<i64>>, filter: Option<F>) -> Result<Self> {
+        match (ids, filter) {
+            (Some(ids), None) => Ok(Self::Ids(ids)),
+            (None, Some(filter)) => Ok(Self::Filter(filter)),
+            _ => Err(Error::BatchTargetInvalid),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Created,
+    Updated,
+    Deleted,
+    NotFound,
+    AccessDenied,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemResult {
+    pub id: i64,
+    pub status: BatchItemStatus,
+}
+
+// endregion:   --- Batch Types
+
+/// Create all the entities with one multi-row insert.
+/// Returns the created ids, in the `data` order.
+///
+/// Note: Columns set by some items only get their default value for the other items.
+pub async fn create_many<MC, E>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    data: Vec<E>,
+) -> Result<Vec<BatchItemResult>>
+where
+    MC: DbBmc,
+    E: HasFields,
+{
+    ensure_batch_size(data.len())?;
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+    let user_id = ctx.user_id();
+
+    // -- Extract fields (name / sea-query value expressions) of each item
+    let items: Vec<Fields> = data
+        .into_iter()
+        .map(|item| {
+            let mut fields = item.not_none_fields();
+            prep_fields_for_create::<MC>(&mut fields, user_id);
+            fields
+        })
+        .collect();
+
+    // -- Build query
+    //    (the columns are the union of the items columns)
+    let mut columns = Vec::new();
+    let mut rows: Vec<HashMap<String, SimpleExpr>> = Vec::new();
+    for fields in items {
+        let mut row = HashMap::new();
+        for field in fields.into_vec() {
+            let name = field.iden.to_string();
+            if !columns.iter().any(|(column_name, _)| *column_name == name) {
+                columns.push((name.clone(), field.iden));
+            }
+            row.insert(name, field.value);
+        }
+        rows.push(row);
+    }
+
+    let mut query = Query::insert();
+    query
+        .into_table(MC::table_ref())
+        .columns(columns.iter().map(|(_, iden)| iden.clone()))
+        .returning(Query::returning().columns([CommonIden::Id]));
+    for mut row in rows {
+        let values = columns
+            .iter()
+            .map(|(name, _)| row.remove(name).unwrap_or_else(|| Expr::cust("DEFAULT")));
+        query.values(values)?;
+    }
+
+    // In a transaction (the change log is written with the mutation)
+    let ids = mm
+        .in_txn(|mm| async move {
+            // -- Exec Query
+            //    (Postgres returns the rows of a multi-row insert in the values order)
+            let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+            let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+            let ids: Vec<i64> = mm
+                .dbx()
+                .fetch_all(sqlx_query)
+                .await?
+                .into_iter()
+                .map(|(id,)| id)
+                .collect();
+
+            // -- Check access
+            //    Note: The inserts are rolled back with the transaction.
+            if let Some(id) = inaccessible_ids::<MC>(ctx, &mm, AccessLevel::Write, &ids)
+                .await?
+                .first()
+            {
+                return Err(Error::AccessDenied {
+                    entity: MC::TABLE,
+                    id: *id,
+                });
+            }
+
+            // -- Log changes
+            let changes = ids.iter().map(|id| (*id, None)).collect();
+            log_changes::<MC>(ctx, &mm, ChangeOp::Create, changes).await?;
+
+            Ok(ids)
+        })
+        .await?;
+
+    Ok(ids
+        .into_iter()
+        .map(|id| BatchItemResult {
+            id,
+            status: BatchItemStatus::Created,
+        })
+        .collect())
+}
+
+/// Update all the targeted entities with the same `data`, in one statement.
+pub async fn update_many<MC, E, F>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    target: BatchTarget<F>,
+    data: E,
+) -> Result<Vec<BatchItemResult>>
+where
+    MC: DbBmc,
+    E: HasFields,
+    F: Into<FilterGroups>,
+{
+    update_many_fields::<MC, F>(ctx, mm, target, data.not_none_fields()).await
+}
+
+/// Update all the targeted entities with the `fields` (e.g., with per row `CASE` expressions),
+/// as for `update_many`.
+pub(crate) async fn update_many_fields<MC, F>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    target: BatchTarget<F>,
+    fields: Fields,
+) -> Result<Vec<BatchItemResult>>
+where
+    MC: DbBmc,
+    F: Into<FilterGroups>,
+{
+    // -- Prep Fields
+    let fields = fields.into_vec();
+    let level = update_access_level::<MC>(&fields);
+    let mut fields = Fields::new(fields);
+    prep_fields_for_update::<MC>(&mut fields, ctx.user_id());
+
+    exec_many::<MC, F>(ctx, mm, target, level, Some(fields), ChangeOp::Update).await
+}
+
+/// Delete all the targeted entities, in one statement
+/// (soft delete or hard delete, as for `base::delete`).
+pub async fn delete_many<MC, F>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    target: BatchTarget<F>,
+) -> Result<Vec<BatchItemResult>>
+where
+    MC: DbBmc,
+    F: Into<FilterGroups>,
+{
+    // -- Soft delete (mark the rows as deleted)
+    if MC::has_soft_delete() {
+        let mut fields = Fields::new(Vec::new());
+        prep_fields_for_soft_delete::<MC>(&mut fields, ctx.user_id());
+        let level = MC::delete_access_level();
+        return exec_many::<MC, F>(ctx, mm, target, level, Some(fields), ChangeOp::Delete).await;
+    }
+
+    // -- Hard delete (logged as a purge, as for `base::delete`)
+    let level = MC::delete_access_level();
+    exec_many::<MC, F>(ctx, mm, target, level, None, ChangeOp::Purge).await
+}
+
+/// Update all the rows matching `cond` with the `fields` (e.g., column expressions),
+/// with their timestamps, version, and change log, as for `update_many`.
+/// Returns the updated ids.
+///
+/// NOTE: For the model internal updates (e.g., the cascade of a definition delete),
+///       so without access check nor batch size limit, and including the soft deleted rows.
+pub(crate) async fn update_by_cond<MC>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    cond: Condition,
+    mut fields: Fields,
+) -> Result<Vec<i64>>
+where
+    MC: DbBmc,
+{
+    prep_fields_for_update::<MC>(&mut fields, ctx.user_id());
+
+    // In a transaction (the change log is written with the mutation)
+    mm.in_txn(|mm| async move {
+        // -- Lock the target rows
+        let befores = ChangeLogBmc::snapshots::<MC>(&mm, cond).await?;
+        let ids: Vec<i64> = befores.iter().map(|(id, _)| *id).collect();
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+
+        // -- Build & Exec query
+        let (sql, values) = Query::update()
+            .table(MC::table_ref())
+            .values(fields.for_sea_update())
+            .and_where(Expr::col(CommonIden::Id).is_in(ids.clone()))
+            .build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_with(&sql, values);
+        mm.dbx().execute(sqlx_query).await?;
+
+        // -- Log changes
+        let changes = befores
+            .into_iter()
+            .map(|(id, before)| (id, Some(before)))
+            .collect();
+        log_changes::<MC>(ctx, &mm, ChangeOp::Update, changes).await?;
+
+        Ok(ids)
+    })
+    .await
+}
+
+// region:      --- Support
+
+/// Apply the update `fields` to the target rows accessible at `level` (or delete them when None).
+async fn exec_many<MC, F>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    target: BatchTarget<F>,
+    level: AccessLevel,
+    fields: Option<Fields>,
+    op: ChangeOp,
+) -> Result<Vec<BatchItemResult>>
+where
+    MC: DbBmc,
+    F: Into<FilterGroups>,
+{
+    // -- Target condition
+    let mut denied_ids = Vec::new();
+    let (target_ids, mut cond) = match target {
+        BatchTarget::Ids(ids) => {
+            ensure_batch_size(ids.len())?;
+            if ids.is_empty() {
+                return Ok(Vec::new());
+            }
+            denied_ids = inaccessible_ids::<MC>(ctx, mm, level, &ids).await?;
+            let cond = Condition::all().add(
+                Expr::col(CommonIden::Id)
+                    .is_in(ids.iter().filter(|id| !denied_ids.contains(id)).copied()),
+            );
+            (Some(ids), cond)
+        }
+        BatchTarget::Filter(filter) => {
+            let filters: FilterGroups = filter.into();
+            // A missing (or empty) filter would target all the rows.
+            if filters.groups().is_empty()
+                || filters
+                    .groups()
+                    .iter()
+                    .any(|group| group.nodes().is_empty())
+            {
+                return Err(Error::BatchFilterEmpty { entity: MC::TABLE });
+            }
+            let cond = scope_cond::<MC>(ctx, level, Some(filters.try_into()?));
+            (None, Condition::all().add_option(cond))
+        }
+    };
+    cond = cond.add_option(not_deleted_cond::<MC>());
+
+    // In a transaction (the change log is written with the mutation)
+    let ids = mm
+        .in_txn(|mm| async move {
+            // -- Lock the target rows
+            let befores = ChangeLogBmc::snapshots::<MC>(&mm, cond).await?;
+            let ids: Vec<i64> = befores.iter().map(|(id, _)| *id).collect();
+            ensure_batch_size(ids.len())?;
+
+            // -- Build & Exec query
+            if !ids.is_empty() {
+                let id_cond = Expr::col(CommonIden::Id).is_in(ids.clone());
+                let (sql, values) = match fields {
+                    Some(fields) => Query::update()
+                        .table(MC::table_ref())
+                        .values(fields.for_sea_update())
+                        .and_where(id_cond)
+                        .build_sqlx(PostgresQueryBuilder),
+                    None => Query::delete()
+                        .from_table(MC::table_ref())
+                        .and_where(id_cond)
+                        .build_sqlx(PostgresQueryBuilder),
+                };
+                let sqlx_query = sqlx::query_with(&sql, values);
+                mm.dbx().execute(sqlx_query).await?;
+            }
+
+            // -- Log changes
+            let changes = befores
+                .into_iter()
+                .map(|(id, before)| (id, Some(before)))
+                .collect();
+            log_changes::<MC>(ctx, &mm, op, changes).await?;
+
+            Ok::<_, Error>(ids)
+        })
+        .await?;
+
+    // -- Per item results
+    let status = match op {
+        ChangeOp::Update => BatchItemStatus::Updated,
+        _ => BatchItemStatus::Deleted,
+    };
+    let results = target_ids
+        .unwrap_or_else(|| ids.clone())
+        .into_iter()
+        .map(|id| BatchItemResult {
+            id,
+            status: if ids.contains(&id) {
+                status
+            } else if denied_ids.contains(&id) {
+                BatchItemStatus::AccessDenied
+            } else {
+                BatchItemStatus::NotFound
+            },
+        })
+        .collect();
+
+    Ok(results)
+}
+
+/// Log the changes of the mutated rows (`(id, before)`).
+///
+/// NOTE: Must be called in the mutation transaction (see `ModelManager::in_txn`).
+async fn log_changes<MC>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    op: ChangeOp,
+    changes: Vec<(i64, Option<Value>)>,
+) -> Result<()>
+where
+    MC: DbBmc,
+{
+    let ids: Vec<i64> = changes.iter().map(|(id, _)| *id).collect();
+    let mut afters: HashMap<i64, Value> = if ids.is_empty() {
+        HashMap::new()
+    } else {
+        let cond = Condition::all().add(Expr::col(CommonIden::Id).is_in(ids));
+        ChangeLogBmc::snapshots::<MC>(mm, cond)
+            .await?
+            .into_iter()
+            .collect()
+    };
+
+    let changes = changes
+        .into_iter()
+        .map(|(id, before)| (id, before, afters.remove(&id)))
+        .collect();
+    ChangeLogBmc::log_changes::<MC>(ctx, mm, op, changes).await
+}
+
+fn ensure_batch_size(size: usize) -> Result<()> {
+    if size as i64 > BATCH_SIZE_MAX {
+        Err(Error::BatchSizeOverMax {
+            max: BATCH_SIZE_MAX,
+            actual: size as i64,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// endregion:   --- Support
@@ -1,16 +1,23 @@
 use crate::ctx::Ctx;
+use crate::model::base::list_cursor::{ListCursor, ListPage};
 use crate::model::base::{
-    prep_fields_for_create, prep_fields_for_update, CommonIden, DbBmc, LIST_LIMIT_DEFAULT,
+    prep_fields_for_create, prep_fields_for_restore, prep_fields_for_soft_delete,
+    prep_fields_for_update, AccessLevel, CommonIden, DbBmc, SoftDeleteIden, LIST_LIMIT_DEFAULT,
     LIST_LIMIT_MAX,
 };
+use crate::model::change_log::{ChangeLogBmc, ChangeOp};
 use crate::model::ModelManager;
 use crate::model::{Error, Result};
-use modql::field::HasFields;
-use modql::filter::{FilterGroups, ListOptions};
-use sea_query::{Condition, Expr, PostgresQueryBuilder, Query};
+use modql::field::{Field, Fields, HasFields};
+use modql::filter::{FilterGroups, ListOptions, OrderBy};
+use sea_query::{
+    Alias, Asterisk, Condition, Expr, Func, Iden, NullOrdering, Order, PostgresQueryBuilder, Query,
+    SelectStatement,
+};
 use sea_query_binder::SqlxBinder;
+use serde_json::{json, Value};
 use sqlx::postgres::PgRow;
-use sqlx::FromRow;
+use sqlx::{FromRow, Row};
 
 pub async fn create<MC, E>(ctx: &Ctx, mm: &ModelManager, data: E) -> Result<i64>
 where
@@ -32,22 +39,34 @@ where
         .values(sea_values)?
         .returning(Query::returning().columns([CommonIden::Id]));
 
-    // -- Exec Query
-    let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-    let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
-    // NOTE: For now, we will use the _txn for all create.
-    //       We could have a with_txn as function argument if perf is an issue (it should not be)
-    let (id,) = mm.dbx().fetch_one(sqlx_query).await?;
+    // In a transaction (the change log is written with the mutation)
+    mm.in_txn(|mm| async move {
+        // -- Exec Query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let (id,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+        // -- Check access
+        //    (e.g., a task created in a project not accessible to the user)
+        //    Note: The insert is rolled back with the transaction.
+        ensure_access::<MC>(ctx, &mm, AccessLevel::Write, id).await?;
+
+        // -- Log change
+        log_change::<MC>(ctx, &mm, id, ChangeOp::Create, 1, None).await?;
 
-    Ok(id)
+        Ok(id)
+    })
+    .await
 }
 
-pub async fn get<MC, E>(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
+pub async fn get<MC, E>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
 where
     MC: DbBmc,
     E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
     E: HasFields,
 {
+    ensure_access::<MC>(ctx, mm, AccessLevel::Read, id).await?;
+
     // -- Build query
     let mut query = Query::select();
     query
@@ -55,6 +74,10 @@ where
         .columns(E::field_column_refs())
         .and_where(Expr::col(CommonIden::Id).eq(id));
 
+    if let Some(not_deleted) = not_deleted_cond::<MC>() {
+        query.cond_where(not_deleted);
+    }
+
     // -- Exec query
     let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
     let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
@@ -71,7 +94,7 @@ where
 }
 
 pub async fn list<MC, E, F>(
-    _ctx: &Ctx,
+    ctx: &Ctx,
     mm: &ModelManager,
     filter: Option<F>,
     list_options: Option<ListOptions>,
@@ -82,19 +105,207 @@ where
     E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
     E: HasFields,
 {
+    let cond = scope_cond::<MC>(ctx, AccessLevel::Read, into_condition(filter)?);
+    list_by_cond::<MC, E>(mm, cond, list_options).await
+}
+
+/// Count the entities matching the filter (excluding the soft deleted ones).
+pub async fn count<MC, F>(ctx: &Ctx, mm: &ModelManager, filter: Option<F>) -> Result<i64>
+where
+    MC: DbBmc,
+    F: Into<FilterGroups>,
+{
+    let cond = scope_cond::<MC>(ctx, AccessLevel::Read, into_condition(filter)?);
+    count_by_cond::<MC>(mm, cond).await
+}
+
+/// List a page of entities with keyset (cursor) pagination.
+///
+/// - The page is sorted by the `list_options` order by (at most one, `id` by default),
+///   with `id` as the tie-breaker.
+/// - `cursor` is the `next_cursor` of the previous page (None for the first page),
+///   and cannot be combined with a `list_options` offset (`Error::ListCursorWithOffset`).
+/// - `next_cursor` is None when there are no more entities.
+///
+/// - `with_total` adds the `total` count of the entities matching the filter.
+///
+/// Note: With more than one order by, there is no cursor pagination (`next_cursor` is always None).
+/// Note: The rows with a null sort key come last (asc) or first (desc), as in PostgreSQL.
+pub async fn list_page<MC, E, F>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    filter: Option<F>,
+    list_options: Option<ListOptions>,
+    cursor: Option<String>,
+    with_total: bool,
+) -> Result<ListPage<E>>
+where
+    MC: DbBmc,
+    F: Into<FilterGroups>,
+    E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+    E: HasFields,
+{
+    let list_options = compute_list_options(list_options)?;
+    let cond = scope_cond::<MC>(ctx, AccessLevel::Read, into_condition(filter)?);
+    // Note: Negative == 0 (as for `ListOptions::apply_to_sea_query`)
+    let limit = list_options.limit.unwrap_or(LIST_LIMIT_DEFAULT).max(0);
+    let offset = list_options.offset.unwrap_or(0).max(0);
+    // The cursor replaces the offset (both would skip the rows before the cursor twice).
+    if cursor.is_some() && offset > 0 {
+        return Err(Error::ListCursorWithOffset);
+    }
+
+    // -- Count the total (the cursor does not apply)
+    let total = if with_total {
+        Some(count_by_cond::<MC>(mm, cond.clone()).await?)
+    } else {
+        None
+    };
+
+    // -- Extract the sort key
+    let order_bys = list_options
+        .order_bys
+        .clone()
+        .map(|order_bys| order_bys.order_bys())
+        .unwrap_or_default();
+    if order_bys.len() > 1 {
+        if cursor.is_some() {
+            return Err(Error::ListCursorWithMultiOrderBys);
+        }
+        let entities = list_by_cond::<MC, E>(mm, cond, Some(list_options)).await?;
+        return Ok(ListPage {
+            entities,
+            next_cursor: None,
+            total,
+            limit,
+            offset,
+        });
+    }
+    let (order_by, desc) = match order_bys.into_iter().next() {
+        Some(OrderBy::Asc(col)) => (col, false),
+        Some(OrderBy::Desc(col)) => (col, true),
+        None => ("id".to_string(), false),
+    };
+    // Note: The column name gets in custom SQL below, so it must be a known field.
+    if !E::field_names().contains(&order_by.as_str()) {
+        return Err(Error::ListOrderByUnknown { order_by });
+    }
+    let order = if desc { Order::Desc } else { Order::Asc };
+
     // -- Build the query
     let mut query = Query::select();
-    query.from(MC::table_ref()).columns(E::field_column_refs());
+    query
+        .from(MC::table_ref())
+        .columns(E::field_column_refs())
+        .expr_as(
+            Expr::cust(format!(r#"to_jsonb("{order_by}")"#)),
+            Alias::new(CURSOR_KEY_COLUMN),
+        )
+        .expr_as(Expr::col(CommonIden::Id), Alias::new(CURSOR_ID_COLUMN));
 
-    // condition from filter
-    if let Some(filter) = filter {
-        let filters: FilterGroups = filter.into();
-        let cond: Condition = filters.try_into()?;
+    if let Some(cond) = cond {
         query.cond_where(cond);
     }
-    // list options
-    let list_options = compute_list_options(list_options)?;
-    list_options.apply_to_sea_query(&mut query);
+
+    if let Some(not_deleted) = not_deleted_cond::<MC>() {
+        query.cond_where(not_deleted);
+    }
+
+    // Start after the cursor, comparing `(sort key, id)` row values.
+    // (`jsonb_populate_record` gives back the cursor key in the column type)
+    // Note: The null sort keys are ordered as the greatest (last in asc, first in desc),
+    //       and are compared explicitly (a row value comparison with a null is null).
+    if let Some(cursor) = cursor {
+        let cursor_value = ListCursor::decode(&cursor)?;
+        if cursor_value.order_by != order_by || cursor_value.desc != desc {
+            return Err(Error::ListCursorInvalid { cursor });
+        }
+        let op = if desc { "<" } else { ">" };
+        let row_after = format!(
+            r#"("{order_by}", "id") {op} (SELECT r."{order_by}", r."id" FROM jsonb_populate_record(NULL::"{table}", $1) r)"#,
+            table = MC::TABLE
+        );
+        let record = json!({ order_by.as_str(): cursor_value.key, "id": cursor_value.id });
+        let after = match (cursor_value.key.is_null(), desc) {
+            (false, false) => Expr::cust_with_values(
+                format!(r#"({row_after} OR "{order_by}" IS NULL)"#),
+                [record],
+            ),
+            (false, true) => Expr::cust_with_values(row_after, [record]),
+            (true, false) => Expr::cust_with_values(
+                format!(r#""{order_by}" IS NULL AND "id" > $1"#),
+                [cursor_value.id],
+            ),
+            (true, true) => Expr::cust_with_values(
+                format!(r#"(("{order_by}" IS NULL AND "id" < $1) OR "{order_by}" IS NOT NULL)"#),
+                [cursor_value.id],
+            ),
+        };
+        query.and_where(after);
+    }
+
+    let nulls = if desc {
+        NullOrdering::First
+    } else {
+        NullOrdering::Last
+    };
+    query
+        .order_by_with_nulls(Alias::new(&order_by), order.clone(), nulls)
+        .order_by(CommonIden::Id, order);
+    query.offset(offset as u64);
+    // One more than the limit, to know if there is a next page.
+    query.limit(limit as u64 + 1);
+
+    // -- Execute the query
+    let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+    let sqlx_query = sqlx::query_as_with::<_, CursorRow<E>, _>(&sql, values);
+    let mut rows = mm.dbx().fetch_all(sqlx_query).await?;
+
+    // -- Build the page
+    let has_more = rows.len() > limit as usize;
+    rows.truncate(limit as usize);
+    let next_cursor = match rows.last() {
+        Some(last) if has_more => Some(
+            ListCursor {
+                order_by,
+                desc,
+                key: last.key.clone(),
+                id: last.id,
+            }
+            .encode(),
+        ),
+        _ => None,
+    };
+    let entities = rows.into_iter().map(|row| row.entity).collect();
+
+    Ok(ListPage {
+        entities,
+        next_cursor,
+        total,
+        limit,
+        offset,
+    })
+}
+
+/// List the soft deleted entities (see `DbBmc::has_soft_delete`).
+pub async fn list_deleted<MC, E, F>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    filter: Option<F>,
+    list_options: Option<ListOptions>,
+) -> Result<Vec<E>>
+where
+    MC: DbBmc,
+    F: Into<FilterGroups>,
+    E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+    E: HasFields,
+{
+    ensure_soft_delete::<MC>()?;
+
+    // -- Build the query
+    let cond = scope_cond::<MC>(ctx, AccessLevel::Read, into_condition(filter)?);
+    let mut query = new_list_query::<MC, E>(cond, list_options)?;
+    query.and_where(Expr::col(SoftDeleteIden::DeletedAt).is_not_null());
 
     // -- Execute the query
     let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
@@ -105,13 +316,52 @@ where
     Ok(entities)
 }
 
+/// Update the not `None` fields of `data`
+/// (a `Some(NullableValue(None))` field sets its column to NULL, see `NullableValue`).
 pub async fn update<MC, E>(ctx: &Ctx, mm: &ModelManager, id: i64, data: E) -> Result<()>
 where
     MC: DbBmc,
     E: HasFields,
 {
+    update_with_version::<MC, E>(ctx, mm, id, data, None).await
+}
+
+/// Update the entity, only if its current `version` is `expected_version` (when given),
+/// otherwise returns `Error::VersionConflict` (see `DbBmc::has_version`).
+pub async fn update_with_version<MC, E>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    id: i64,
+    data: E,
+    expected_version: Option<i64>,
+) -> Result<()>
+where
+    MC: DbBmc,
+    E: HasFields,
+{
+    update_fields_with_version::<MC>(ctx, mm, id, data.not_none_fields(), expected_version).await
+}
+
+/// Update the entity with the `fields` (e.g., with column expressions),
+/// as for `update_with_version`.
+pub(crate) async fn update_fields_with_version<MC>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    id: i64,
+    fields: Fields,
+    expected_version: Option<i64>,
+) -> Result<()>
+where
+    MC: DbBmc,
+{
+    if expected_version.is_some() {
+        ensure_version::<MC>()?;
+    }
+
     // -- Prep Fields
-    let mut fields = data.not_none_fields();
+    let fields = fields.into_vec();
+    ensure_access::<MC>(ctx, mm, update_access_level::<MC>(&fields), id).await?;
+    let mut fields = Fields::new(fields);
     prep_fields_for_update::<MC>(&mut fields, ctx.user_id());
 
     // -- Build query
@@ -122,46 +372,138 @@ where
         .values(fields)
         .and_where(Expr::col(CommonIden::Id).eq(id));
 
-    // -- Execute query
-    let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-    let sqlx_query = sqlx::query_with(&sql, values);
-    let count = mm.dbx().execute(sqlx_query).await?;
+    if let Some(not_deleted) = not_deleted_cond::<MC>() {
+        query.cond_where(not_deleted);
+    }
 
-    // -- Check result
-    if count == 0 {
-        Err(Error::EntityNotFound {
-            entity: MC::TABLE,
-            id,
-        })
-    } else {
-        Ok(())
+    // In a transaction (the change log is written with the mutation)
+    mm.in_txn(|mm| async move {
+        let before = ChangeLogBmc::snapshot::<MC>(&mm, id).await?;
+
+        // -- Check version
+        //    (the snapshot row is locked until the end of the transaction)
+        if let Some(expected_version) = expected_version {
+            let current = before
+                .as_ref()
+                .and_then(|row| row.get("version"))
+                .and_then(|version| version.as_i64());
+            if let Some(current) = current.filter(|current| *current != expected_version) {
+                return Err(Error::VersionConflict {
+                    entity: MC::TABLE,
+                    id,
+                    current,
+                });
+            }
+        }
+
+        // -- Execute query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_with(&sql, values);
+        let count = mm.dbx().execute(sqlx_query).await?;
+
+        // -- Log change
+        log_change::<MC>(ctx, &mm, id, ChangeOp::Update, count, before).await
+    })
+    .await
+}
+
+pub async fn delete<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+where
+    MC: DbBmc,
+{
+    // -- Soft delete (mark the row as deleted)
+    if MC::has_soft_delete() {
+        ensure_access::<MC>(ctx, mm, MC::delete_access_level(), id).await?;
+
+        let mut fields = Fields::new(Vec::new());
+        prep_fields_for_soft_delete::<MC>(&mut fields, ctx.user_id());
+
+        let mut query = Query::update();
+        query
+            .table(MC::table_ref())
+            .values(fields.for_sea_update())
+            .and_where(Expr::col(CommonIden::Id).eq(id))
+            .cond_where(Condition::all().add_option(not_deleted_cond::<MC>()));
+
+        // In a transaction (the change log is written with the mutation)
+        return mm
+            .in_txn(|mm| async move {
+                let before = ChangeLogBmc::snapshot::<MC>(&mm, id).await?;
+
+                let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+                let sqlx_query = sqlx::query_with(&sql, values);
+                let count = mm.dbx().execute(sqlx_query).await?;
+
+                log_change::<MC>(ctx, &mm, id, ChangeOp::Delete, count, before).await
+            })
+            .await;
     }
+
+    // -- Hard delete
+    purge::<MC>(ctx, mm, id).await
 }
 
-pub async fn delete<MC>(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+/// Restore a soft deleted entity (see `DbBmc::has_soft_delete`).
+pub async fn restore<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
 where
     MC: DbBmc,
 {
+    ensure_soft_delete::<MC>()?;
+    ensure_access::<MC>(ctx, mm, MC::delete_access_level(), id).await?;
+
+    // -- Prep Fields
+    let mut fields = Fields::new(Vec::new());
+    prep_fields_for_restore::<MC>(&mut fields, ctx.user_id());
+
+    // -- Build query
+    let mut query = Query::update();
+    query
+        .table(MC::table_ref())
+        .values(fields.for_sea_update())
+        .and_where(Expr::col(CommonIden::Id).eq(id))
+        .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_not_null());
+
+    // In a transaction (the change log is written with the mutation)
+    mm.in_txn(|mm| async move {
+        let before = ChangeLogBmc::snapshot::<MC>(&mm, id).await?;
+
+        // -- Execute query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_with(&sql, values);
+        let count = mm.dbx().execute(sqlx_query).await?;
+
+        // -- Log change
+        log_change::<MC>(ctx, &mm, id, ChangeOp::Restore, count, before).await
+    })
+    .await
+}
+
+/// Permanently delete the row, whether it is soft deleted or not.
+pub async fn purge<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+where
+    MC: DbBmc,
+{
+    ensure_access::<MC>(ctx, mm, MC::delete_access_level(), id).await?;
+
     // -- Build query
     let mut query = Query::delete();
     query
         .from_table(MC::table_ref())
         .and_where(Expr::col(CommonIden::Id).eq(id));
 
-    // -- Execute query
-    let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-    let sqlx_query = sqlx::query_with(&sql, values);
-    let count = mm.dbx().execute(sqlx_query).await?;
+    // In a transaction (the change log is written with the mutation)
+    mm.in_txn(|mm| async move {
+        let before = ChangeLogBmc::snapshot::<MC>(&mm, id).await?;
 
-    // -- Check result
-    if count == 0 {
-        Err(Error::EntityNotFound {
-            entity: MC::TABLE,
-            id,
-        })
-    } else {
-        Ok(())
-    }
+        // -- Execute query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_with(&sql, values);
+        let count = mm.dbx().execute(sqlx_query).await?;
+
+        // -- Log change
+        log_change::<MC>(ctx, &mm, id, ChangeOp::Purge, count, before).await
+    })
+    .await
 }
 
 pub fn compute_list_options(list_options: Option<ListOptions>) -> Result<ListOptions> {
@@ -190,3 +532,289 @@ pub fn compute_list_options(list_options: Option<ListOptions>) -> Result<ListOpt
         })
     }
 }
+
+// region:      --- Support
+
+const CURSOR_KEY_COLUMN: &str = "_cursor_key";
+const CURSOR_ID_COLUMN: &str = "_cursor_id";
+
+/// An entity row of `list_page`, with its sort key and id for the next cursor.
+struct CursorRow<E> {
+    entity: E,
+    key: Value,
+    id: i64,
+}
+
+impl<'r, E> FromRow<'r, PgRow> for CursorRow<E>
+where
+    E: FromRow<'r, PgRow>,
+{
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            entity: E::from_row(row)?,
+            // Note: A null sort key is a null `to_jsonb` (not a JSON null).
+            key: row
+                .try_get::<Option<Value>, _>(CURSOR_KEY_COLUMN)?
+                .unwrap_or_default(),
+            id: row.try_get(CURSOR_ID_COLUMN)?,
+        })
+    }
+}
+
+fn into_condition<F>(filter: Option<F>) -> Result<Option<Condition>>
+where
+    F: Into<FilterGroups>,
+{
+    let cond = match filter {
+        Some(filter) => {
+            let filters: FilterGroups = filter.into();
+            Some(filters.try_into()?)
+        }
+        None => None,
+    };
+
+    Ok(cond)
+}
+
+fn new_list_query<MC, E>(
+    cond: Option<Condition>,
+    list_options: Option<ListOptions>,
+) -> Result<SelectStatement>
+where
+    MC: DbBmc,
+    E: HasFields,
+{
+    let mut query = Query::select();
+    query.from(MC::table_ref()).columns(E::field_column_refs());
+
+    // condition from filter
+    if let Some(cond) = cond {
+        query.cond_where(cond);
+    }
+    // list options
+    let list_options = compute_list_options(list_options)?;
+    list_options.apply_to_sea_query(&mut query);
+
+    Ok(query)
+}
+
+/// List the (non soft deleted) entities matching the condition.
+async fn list_by_cond<MC, E>(
+    mm: &ModelManager,
+    cond: Option<Condition>,
+    list_options: Option<ListOptions>,
+) -> Result<Vec<E>>
+where
+    MC: DbBmc,
+    E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+    E: HasFields,
+{
+    // -- Build the query
+    let mut query = new_list_query::<MC, E>(cond, list_options)?;
+
+    if let Some(not_deleted) = not_deleted_cond::<MC>() {
+        query.cond_where(not_deleted);
+    }
+
+    // -- Execute the query
+    let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+
+    let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+    let entities = mm.dbx().fetch_all(sqlx_query).await?;
+
+    Ok(entities)
+}
+
+/// Count the (non soft deleted) entities matching the condition.
+async fn count_by_cond<MC>(mm: &ModelManager, cond: Option<Condition>) -> Result<i64>
+where
+    MC: DbBmc,
+{
+    // -- Build the query
+    let mut query = Query::select();
+    query
+        .from(MC::table_ref())
+        .expr(Func::count(Expr::col(Asterisk)));
+
+    if let Some(cond) = cond {
+        query.cond_where(cond);
+    }
+
+    if let Some(not_deleted) = not_deleted_cond::<MC>() {
+        query.cond_where(not_deleted);
+    }
+
+    // -- Execute the query
+    let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+    let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+    let (count,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+    Ok(count)
+}
+
+/// Returns the condition restricting the rows accessible to `ctx` at `level`
+/// (see `DbBmc::access_cond`). None when all the rows are accessible (e.g., root ctx).
+fn access_cond<MC>(ctx: &Ctx, level: AccessLevel) -> Option<Condition>
+where
+    MC: DbBmc,
+{
+    if ctx.is_root() {
+        None
+    } else {
+        MC::access_cond(ctx, level)
+    }
+}
+
+/// Returns the condition excluding the soft deleted rows, and the rows of a soft deleted parent
+/// (see `DbBmc::has_soft_delete` and `DbBmc::not_deleted_parent_cond`).
+/// None when the entity has neither.
+pub(crate) fn not_deleted_cond<MC>() -> Option<Condition>
+where
+    MC: DbBmc,
+{
+    let deleted_at_cond = MC::has_soft_delete()
+        .then(|| Condition::all().add(Expr::col(SoftDeleteIden::DeletedAt).is_null()));
+
+    match (deleted_at_cond, MC::not_deleted_parent_cond()) {
+        (Some(deleted_at_cond), Some(parent_cond)) => {
+            Some(Condition::all().add(deleted_at_cond).add(parent_cond))
+        }
+        (deleted_at_cond, None) => deleted_at_cond,
+        (None, parent_cond) => parent_cond,
+    }
+}
+
+/// Returns the access level required to update the `fields`
+/// (an update of the `owner_id` is an ownership transfer).
+pub(super) fn update_access_level<MC>(fields: &[Field]) -> AccessLevel
+where
+    MC: DbBmc,
+{
+    let owner_id = CommonIden::OwnerId.to_string();
+    if MC::has_owner_id() && fields.iter().any(|field| field.iden.to_string() == owner_id) {
+        AccessLevel::Owner
+    } else {
+        AccessLevel::Write
+    }
+}
+
+/// Returns the `cond` restricted to the rows accessible to `ctx` at `level`.
+pub(crate) fn scope_cond<MC>(
+    ctx: &Ctx,
+    level: AccessLevel,
+    cond: Option<Condition>,
+) -> Option<Condition>
+where
+    MC: DbBmc,
+{
+    match (cond, access_cond::<MC>(ctx, level)) {
+        (Some(cond), Some(access)) => Some(Condition::all().add(cond).add(access)),
+        (cond, None) => cond,
+        (None, access) => access,
+    }
+}
+
+/// Returns the ids (among `ids`) of the existing rows not accessible to `ctx` at `level`.
+pub(super) async fn inaccessible_ids<MC>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    level: AccessLevel,
+    ids: &[i64],
+) -> Result<Vec<i64>>
+where
+    MC: DbBmc,
+{
+    let Some(access) = access_cond::<MC>(ctx, level) else {
+        return Ok(Vec::new());
+    };
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // -- Build the query
+    let mut query = Query::select();
+    query
+        .from(MC::table_ref())
+        .column(CommonIden::Id)
+        .and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()))
+        .cond_where(Condition::all().add(access).not())
+        .order_by(CommonIden::Id, Order::Asc);
+
+    // -- Execute the query
+    let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+    let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+    let rows = mm.dbx().fetch_all(sqlx_query).await?;
+
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+/// Returns `Error::AccessDenied` if the row `id` exists but is not accessible to `ctx` at `level`.
+/// (a missing row is left to the caller, e.g., `Error::EntityNotFound`)
+pub async fn ensure_access<MC>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    level: AccessLevel,
+    id: i64,
+) -> Result<()>
+where
+    MC: DbBmc,
+{
+    if inaccessible_ids::<MC>(ctx, mm, level, &[id]).await?.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::AccessDenied {
+            entity: MC::TABLE,
+            id,
+        })
+    }
+}
+
+fn ensure_soft_delete<MC>() -> Result<()>
+where
+    MC: DbBmc,
+{
+    if MC::has_soft_delete() {
+        Ok(())
+    } else {
+        Err(Error::SoftDeleteNotSupported { entity: MC::TABLE })
+    }
+}
+
+fn ensure_version<MC>() -> Result<()>
+where
+    MC: DbBmc,
+{
+    if MC::has_version() {
+        Ok(())
+    } else {
+        Err(Error::VersionNotSupported { entity: MC::TABLE })
+    }
+}
+
+/// Log the change of the mutated row (`count` rows affected).
+/// Returns `Error::EntityNotFound` when no row was affected.
+///
+/// NOTE: Must be called in the mutation transaction (see `ModelManager::in_txn`).
+pub(crate) async fn log_change<MC>(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    id: i64,
+    op: ChangeOp,
+    count: u64,
+    before: Option<Value>,
+) -> Result<()>
+where
+    MC: DbBmc,
+{
+    if count == 0 {
+        return Err(Error::EntityNotFound {
+            entity: MC::TABLE,
+            id,
+        });
+    }
+
+    let after = ChangeLogBmc::snapshot::<MC>(mm, id).await?;
+    ChangeLogBmc::log_change::<MC>(ctx, mm, id, op, before, after).await
+}
+
+// endregion:   --- Support
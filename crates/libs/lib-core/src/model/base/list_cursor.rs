@@ -0,0 +1,84 @@
+use crate::model::{Error, Result};
+use lib_utils::b64::{b64u_decode, b64u_encode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A page of entities returned by `base::list_page`.
+///
+/// - `next_cursor` is None when there are no more entities after this page.
+/// - `total` is the count of all the entities matching the filter (when requested).
+/// - `limit` and `offset` are the effective ones (i.e., with the defaults applied).
+pub struct ListPage<E> {
+    pub entities: Vec<E>,
+    pub next_cursor: Option<String>,
+    pub total: Option<i64>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// The keyset pagination cursor (opaque to clients, as b64u of its JSON).
+///
+/// It holds the sort key (column, direction, and value) and the id of the last entity
+/// of a page, so that the next page starts right after it, even if rows get inserted
+/// or deleted in the meantime.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ListCursor {
+    pub order_by: String,
+    pub desc: bool,
+    pub key: Value,
+    pub id: i64,
+}
+
+impl ListCursor {
+    pub fn encode(&self) -> String {
+        // Note: Serializing a struct of String/bool/Value/i64 cannot fail.
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        b64u_encode(json)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self> {
+        b64u_decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| Error::ListCursorInvalid {
+                cursor: cursor.to_string(),
+            })
+    }
+}
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[test]
+    fn test_list_cursor_encode_decode_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_cursor = ListCursor {
+            order_by: "title".to_string(),
+            desc: true,
+            key: json!("some title"),
+            id: 1001,
+        };
+
+        // -- Exec
+        let cursor = ListCursor::decode(&fx_cursor.encode())?;
+
+        // -- Check
+        assert_eq!(cursor.order_by, fx_cursor.order_by);
+        assert_eq!(cursor.desc, fx_cursor.desc);
+        assert_eq!(cursor.key, fx_cursor.key);
+        assert_eq!(cursor.id, fx_cursor.id);
+        assert!(matches!(
+            ListCursor::decode("not-a-cursor"),
+            Err(Error::ListCursorInvalid { .. })
+        ));
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
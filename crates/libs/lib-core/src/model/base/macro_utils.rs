@@ -32,6 +32,26 @@ macro_rules! generate_common_bmc_fns {
                 base::list::<Self, _, _>(ctx, mm, filter, list_options).await
             }
 
+            pub async fn list_page(
+                ctx: &Ctx,
+                mm: &ModelManager,
+                filter: Option<Vec<$filter>>,
+                list_options: Option<ListOptions>,
+                cursor: Option<String>,
+                with_total: bool,
+            ) -> Result<base::ListPage<$entity>> {
+                base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor, with_total)
+                    .await
+            }
+
+            pub async fn count(
+                ctx: &Ctx,
+                mm: &ModelManager,
+                filter: Option<Vec<$filter>>,
+            ) -> Result<i64> {
+                base::count::<Self, _>(ctx, mm, filter).await
+            }
+
             pub async fn update(
                 ctx: &Ctx,
                 mm: &ModelManager,
@@ -41,9 +61,62 @@ macro_rules! generate_common_bmc_fns {
                 base::update::<Self, _>(ctx, mm, id, entity_u).await
             }
 
+            pub async fn update_with_version(
+                ctx: &Ctx,
+                mm: &ModelManager,
+                id: i64,
+                entity_u: $for_update,
+                expected_version: Option<i64>,
+            ) -> Result<()> {
+                base::update_with_version::<Self, _>(ctx, mm, id, entity_u, expected_version)
+                    .await
+            }
+
             pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
                 base::delete::<Self>(ctx, mm, id).await
             }
+
+            pub async fn create_many(
+                ctx: &Ctx,
+                mm: &ModelManager,
+                entities_c: Vec<$for_create>,
+            ) -> Result<Vec<base::BatchItemResult>> {
+                base::create_many::<Self, _>(ctx, mm, entities_c).await
+            }
+
+            pub async fn update_many(
+                ctx: &Ctx,
+                mm: &ModelManager,
+                target: base::BatchTarget<Vec<$filter>>,
+                entity_u: $for_update,
+            ) -> Result<Vec<base::BatchItemResult>> {
+                base::update_many::<Self, _, _>(ctx, mm, target, entity_u).await
+            }
+
+            pub async fn delete_many(
+                ctx: &Ctx,
+                mm: &ModelManager,
+                target: base::BatchTarget<Vec<$filter>>,
+            ) -> Result<Vec<base::BatchItemResult>> {
+                base::delete_many::<Self, _>(ctx, mm, target).await
+            }
+
+            pub async fn list_deleted(
+                ctx: &Ctx,
+                mm: &ModelManager,
+                filter: Option<Vec<$filter>>,
+                list_options: Option<ListOptions>,
+            ) -> Result<Vec<$entity>> {
+                base::list_deleted::<Self, _, _>(ctx, mm, filter, list_options).await
+            }
+
+            pub async fn restore(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+                base::restore::<Self>(ctx, mm, id).await
+            }
+
+            pub async fn purge(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+                base::purge::<Self>(ctx, mm, id).await
+            }
         }
     };
 }
@@ -1,15 +1,20 @@
 // region:      --- Modules
 
+mod batch_fns;
 mod crud_fns;
+mod list_cursor;
 mod macro_utils;
 mod utils;
 
 // -- Flatten hierarchy for user code.
+pub use batch_fns::*;
 pub use crud_fns::*;
+pub use list_cursor::ListPage;
 pub use utils::*;
 
+use crate::ctx::Ctx;
 use modql::SIden;
-use sea_query::{Iden, IntoIden, TableRef};
+use sea_query::{Condition, Iden, IntoIden, TableRef};
 
 // endregion:   --- Modules
 
@@ -18,6 +23,8 @@ use sea_query::{Iden, IntoIden, TableRef};
 const LIST_LIMIT_DEFAULT: i64 = 1000;
 const LIST_LIMIT_MAX: i64 = 5000;
 
+const BATCH_SIZE_MAX: i64 = 1000;
+
 // endregion:   --- Consts
 
 // region:      --- SeaQuery Idens
@@ -26,6 +33,7 @@ const LIST_LIMIT_MAX: i64 = 5000;
 pub enum CommonIden {
     Id,
     OwnerId,
+    Version,
 }
 
 #[derive(Iden)]
@@ -36,8 +44,25 @@ pub enum TimestampIden {
     Mtime,
 }
 
+#[derive(Iden)]
+pub enum SoftDeleteIden {
+    DeletedAt,
+    DeletedBy,
+}
+
 // endregion:   --- SeaQuery Idens
 
+/// The access level required on an entity row (see `DbBmc::access_cond`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLevel {
+    /// get, list, count
+    Read,
+    /// create, update (and delete by default, see `DbBmc::delete_access_level`)
+    Write,
+    /// e.g., the ownership transfer (update of the `owner_id`)
+    Owner,
+}
+
 /// The DbBmc must be implemented for the Bmc struct of an entity.
 /// It specifies meta information such as the table name.
 /// weather the table has timestamps columns (cid, ctime, mid, mtime), and more as the
@@ -67,4 +92,58 @@ pub trait DbBmc {
     fn has_owner_id() -> bool {
         false
     }
+
+    /// Specifies if the entity table managed by this BMC has the soft delete
+    /// (deleted_at, deleted_by) columns.
+    /// When true, `base::delete` only marks the row as deleted, and `base::get`/`base::list`
+    /// exclude the deleted rows (see `base::list_deleted`, `base::restore`, and `base::purge`).
+    ///
+    /// default: false
+    fn has_soft_delete() -> bool {
+        false
+    }
+
+    /// Specifies if the entity table managed by this BMC has a `version` column,
+    /// incremented on each update, for optimistic concurrency
+    /// (see `base::update_with_version`).
+    ///
+    /// default: false
+    fn has_version() -> bool {
+        false
+    }
+
+    /// Specifies the columns whose values must not be written in the change log,
+    /// the secrets and the personal data (they are masked in the `ChangeLog` diff,
+    /// see `model::change_log`).
+    ///
+    /// default: none
+    fn secret_columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Specifies the condition restricting the entity rows accessible to the `ctx` user
+    /// at the given `level` (e.g., the projects the user is a member of), for the `base::` functions.
+    /// The root ctx always accesses all the rows (this is not called).
+    ///
+    /// default: None (all rows)
+    fn access_cond(_ctx: &Ctx, _level: AccessLevel) -> Option<Condition> {
+        None
+    }
+
+    /// Specifies the condition of the entity rows whose parent row is not soft deleted
+    /// (e.g., the tasks of the not deleted projects).
+    /// The `base::` functions exclude the other rows as they exclude the soft deleted rows
+    /// (for all the ctx, root included), so they are deleted and restored with their parent.
+    ///
+    /// default: None (no parent)
+    fn not_deleted_parent_cond() -> Option<Condition> {
+        None
+    }
+
+    /// Specifies the access level required to delete, restore, and purge an entity row.
+    ///
+    /// default: AccessLevel::Write
+    fn delete_access_level() -> AccessLevel {
+        AccessLevel::Write
+    }
 }
@@ -1,7 +1,7 @@
-use crate::model::base::{CommonIden, DbBmc, TimestampIden};
+use crate::model::base::{CommonIden, DbBmc, SoftDeleteIden, TimestampIden};
 use lib_utils::time::now_utc;
 use modql::field::{Field, Fields};
-use sea_query::IntoIden;
+use sea_query::{Expr, IntoIden, Keyword, SimpleExpr};
 
 /// This method must be called when a model controller intends to create its entity.
 pub fn prep_fields_for_create<MC>(fields: &mut Fields, user_id: i64)
@@ -25,6 +25,36 @@ where
     if MC::has_timestamps() {
         add_timestamps_for_update(fields, user_id);
     }
+
+    if MC::has_version() {
+        fields.push(Field::new(
+            CommonIden::Version.into_iden(),
+            Expr::col(CommonIden::Version).add(1),
+        ));
+    }
+}
+
+/// This method must be called when a model controller plans to soft delete its entity.
+pub fn prep_fields_for_soft_delete<MC>(fields: &mut Fields, user_id: i64)
+where
+    MC: DbBmc,
+{
+    fields.push(Field::new(SoftDeleteIden::DeletedAt.into_iden(), now_utc().into()));
+    fields.push(Field::new(SoftDeleteIden::DeletedBy.into_iden(), user_id.into()));
+
+    prep_fields_for_update::<MC>(fields, user_id);
+}
+
+/// This method must be called when a model controller plans to restore a soft deleted entity.
+pub fn prep_fields_for_restore<MC>(fields: &mut Fields, user_id: i64)
+where
+    MC: DbBmc,
+{
+    let null = SimpleExpr::Keyword(Keyword::Null);
+    fields.push(Field::new(SoftDeleteIden::DeletedAt.into_iden(), null.clone()));
+    fields.push(Field::new(SoftDeleteIden::DeletedBy.into_iden(), null));
+
+    prep_fields_for_update::<MC>(fields, user_id);
 }
 
 /// Update the timestamps info for create
@@ -0,0 +1,413 @@
+//! Change log (audit) of the model mutations.
+//!
+//! Design:
+//!
+//! - `base::create`, `base::update`, `base::delete`, `base::restore`, `base::purge`, the batch
+//!   mutations (e.g., `base::update_many`), and the `UserBmc` custom mutations record
+//!   a `ChangeLog` row for each mutated entity row.
+//! - The task labels (`LabelBmc::attach`/`detach`) and dependencies (`TaskDependencyBmc::add`/
+//!   `remove`) are recorded as updates of the task, with a `label_ids`/`blocker_task_ids`
+//!   snapshot column.
+//! - The change log row is written in the same `Dbx` transaction as the mutation.
+//! - `diff` is a JSON object with the changed columns, as `{"column": {"old": .., "new": ..}}`.
+//!   The timestamp and version columns are not part of the diff (`user_id`/`changed_at` already
+//!   give this information), and the `DbBmc::secret_columns` values are masked.
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, CommonIden, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_utils::time::{now_utc, Rfc3339};
+use modql::field::{Fields, HasFields};
+use modql::filter::{FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue};
+use sea_query::{Alias, Condition, Expr, Iden, LockType, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// endregion:   --- Modules
+
+// region:      --- Consts
+
+/// Bookkeeping (and computed search) columns not part of the diff.
+const DIFF_SKIP_COLUMNS: &[&str] = &["cid", "ctime", "mid", "mtime", "version", "search_tsv"];
+
+/// Replaces the `DbBmc::secret_columns` values in the diff.
+const SECRET_MASK: &str = "********";
+
+// endregion:   --- Consts
+
+// region:      --- ChangeLog Types
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct ChangeLog {
+    pub id: i64,
+
+    // -- Changed entity
+    pub entity: String,
+    pub entity_id: i64,
+
+    // -- Change
+    pub user_id: i64,
+    pub op: String,
+    #[serde_as(as = "Rfc3339")]
+    pub changed_at: OffsetDateTime,
+    pub diff: Value,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ChangeOp {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Purge,
+}
+
+impl ChangeOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Restore => "restore",
+            Self::Purge => "purge",
+        }
+    }
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct ChangeLogFilter {
+    pub entity: Option<OpValsString>,
+    pub entity_id: Option<OpValsInt64>,
+    pub user_id: Option<OpValsInt64>,
+    pub op: Option<OpValsString>,
+
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub changed_at: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum ChangeLogIden {
+    Entity,
+    EntityId,
+    UserId,
+    Op,
+    ChangedAt,
+    Diff,
+}
+
+// endregion:   --- ChangeLog Types
+
+// region:      --- ChangeLogBmc
+
+pub struct ChangeLogBmc;
+
+impl DbBmc for ChangeLogBmc {
+    const TABLE: &'static str = "change_log";
+
+    fn has_timestamps() -> bool {
+        false
+    }
+}
+
+impl ChangeLogBmc {
+    pub async fn list(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<ChangeLogFilter>>,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<ChangeLog>> {
+        base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+    }
+
+    /// Returns the id of the last change log row (0 if none), as the marker for `list_after`.
+    pub(crate) async fn last_id(mm: &ModelManager) -> Result<i64> {
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .expr(Expr::cust("coalesce(max(id), 0)"))
+            .from(Self::table_ref());
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let (last_id,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+        Ok(last_id)
+    }
+
+    /// Returns the change log rows with an id above `after_id`, ordered by id.
+    pub(crate) async fn list_after(mm: &ModelManager, after_id: i64) -> Result<Vec<ChangeLog>> {
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .from(Self::table_ref())
+            .columns(ChangeLog::field_column_refs())
+            .and_where(Expr::col(CommonIden::Id).gt(after_id))
+            .order_by(CommonIden::Id, Order::Asc);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, ChangeLog, _>(&sql, values);
+        let changes = mm.dbx().fetch_all(sqlx_query).await?;
+
+        Ok(changes)
+    }
+
+    /// Returns the `MC` entity row `id` as a JSON object (None if not found).
+    ///
+    /// Note: The row is locked (`FOR UPDATE`) so that the snapshot taken before a mutation
+    ///       stays valid until the end of its transaction.
+    pub(crate) async fn snapshot<MC>(mm: &ModelManager, id: i64) -> Result<Option<Value>>
+    where
+        MC: DbBmc,
+    {
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .expr(Expr::cust("to_jsonb(t)"))
+            .from_as(MC::table_ref(), Alias::new("t"))
+            .and_where(Expr::col((Alias::new("t"), CommonIden::Id)).eq(id))
+            .lock(LockType::Update);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (Value,), _>(&sql, values);
+        let row = mm.dbx().fetch_optional(sqlx_query).await?;
+
+        Ok(row.map(|(row,)| row))
+    }
+
+    /// Returns the `MC` entity rows matching `cond` as `(id, JSON object)`, ordered by id
+    /// (locked `FOR UPDATE`, as for `snapshot`).
+    pub(crate) async fn snapshots<MC>(
+        mm: &ModelManager,
+        cond: Condition,
+    ) -> Result<Vec<(i64, Value)>>
+    where
+        MC: DbBmc,
+    {
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .expr(Expr::col((Alias::new("t"), CommonIden::Id)))
+            .expr(Expr::cust("to_jsonb(t)"))
+            .from_as(MC::table_ref(), Alias::new("t"))
+            .cond_where(cond)
+            .order_by((Alias::new("t"), CommonIden::Id), Order::Asc)
+            .lock(LockType::Update);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64, Value), _>(&sql, values);
+        let rows = mm.dbx().fetch_all(sqlx_query).await?;
+
+        Ok(rows)
+    }
+
+    /// Record the change of the `MC` entity row `id` from the `before` to the `after` snapshots.
+    ///
+    /// IMPORTANT: Must be called in the transaction of the mutation.
+    pub(crate) async fn log_change<MC>(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        op: ChangeOp,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) -> Result<()>
+    where
+        MC: DbBmc,
+    {
+        Self::log_changes::<MC>(ctx, mm, op, vec![(id, before, after)]).await
+    }
+
+    /// Record the changes of the `MC` entity rows, as `(id, before, after)`, in one insert.
+    ///
+    /// IMPORTANT: Must be called in the transaction of the mutation.
+    pub(crate) async fn log_changes<MC>(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        op: ChangeOp,
+        changes: Vec<(i64, Option<Value>, Option<Value>)>,
+    ) -> Result<()>
+    where
+        MC: DbBmc,
+    {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let user_id = ctx.user_id();
+        let changed_at = now_utc();
+
+        // -- Build query
+        let mut query = Query::insert();
+        query.into_table(Self::table_ref()).columns([
+            ChangeLogIden::Entity,
+            ChangeLogIden::EntityId,
+            ChangeLogIden::UserId,
+            ChangeLogIden::Op,
+            ChangeLogIden::ChangedAt,
+            ChangeLogIden::Diff,
+        ]);
+        for (id, before, after) in changes {
+            let diff = compute_diff(before, after, MC::secret_columns());
+            query.values([
+                MC::TABLE.into(),
+                id.into(),
+                user_id.into(),
+                op.as_str().into(),
+                changed_at.into(),
+                diff.into(),
+            ])?;
+        }
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_with(&sql, values);
+        mm.dbx().execute(sqlx_query).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- ChangeLogBmc
+
+// region:      --- Support
+
+/// Returns `{"column": {"old": .., "new": ..}}` for each column changed between the two snapshots
+/// (a missing snapshot has all its columns null).
+fn compute_diff(before: Option<Value>, after: Option<Value>, secret_columns: &[&str]) -> Value {
+    let before = into_object(before);
+    let after = into_object(after);
+
+    let mut diff = Map::new();
+    for column in before
+        .keys()
+        .chain(after.keys().filter(|k| !before.contains_key(*k)))
+    {
+        if DIFF_SKIP_COLUMNS.contains(&column.as_str()) {
+            continue;
+        }
+
+        let old = before.get(column).unwrap_or(&Value::Null);
+        let new = after.get(column).unwrap_or(&Value::Null);
+        if old == new {
+            continue;
+        }
+
+        let is_secret = secret_columns.contains(&column.as_str());
+        diff.insert(
+            column.to_string(),
+            json!({
+                "old": mask_if(is_secret, old),
+                "new": mask_if(is_secret, new),
+            }),
+        );
+    }
+
+    Value::Object(diff)
+}
+
+fn into_object(snapshot: Option<Value>) -> Map<String, Value> {
+    match snapshot {
+        Some(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn mask_if(is_secret: bool, value: &Value) -> Value {
+    if is_secret && !value.is_null() {
+        SECRET_MASK.into()
+    } else {
+        value.clone()
+    }
+}
+
+// endregion:   --- Support
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::task::{TaskBmc, TaskForUpdate};
+    use anyhow::Result;
+    use serial_test::serial;
+
+    #[test]
+    fn test_compute_diff_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_before = json!({"id": 1, "name": "a", "pwd": "x", "mtime": "t1"});
+        let fx_after = json!({"id": 1, "name": "b", "pwd": "y", "mtime": "t2", "extra": null});
+
+        // -- Exec
+        let diff = compute_diff(Some(fx_before), Some(fx_after), &["pwd"]);
+
+        // -- Check
+        assert_eq!(
+            diff,
+            json!({
+                "name": {"old": "a", "new": "b"},
+                "pwd": {"old": SECRET_MASK, "new": SECRET_MASK},
+            })
+        );
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_log_change_on_update_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_log_change_on_update_ok project").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["title 01"])
+            .await?
+            .remove(0);
+
+        // -- Exec
+        TaskBmc::update(
+            &ctx,
+            &mm,
+            fx_task.id,
+            TaskForUpdate {
+                title: Some("title 02".to_string()),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        // -- Check
+        let filter = ChangeLogFilter {
+            entity: Some("task".into()),
+            entity_id: Some(fx_task.id.into()),
+            ..Default::default()
+        };
+        let changes = ChangeLogBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        let ops: Vec<&str> = changes.iter().map(|c| c.op.as_str()).collect();
+        assert_eq!(ops, &["create", "update"]);
+        assert_eq!(
+            changes[1].diff,
+            json!({"title": {"old": "title 01", "new": "title 02"}})
+        );
+
+        // -- Clean
+        crate::model::project::ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
@@ -0,0 +1,292 @@
+//! Task comments, threaded with their optional parent comment.
+//!
+//! Design:
+//!
+//! - The author is the ctx user creating the comment.
+//! - The users who can read a task can comment on it (the project viewers included).
+//! - Only the author can update a comment. The author or the project owners can delete it.
+//! - The comments of a (soft) deleted task are hidden with it, and deleted with its row
+//!   (as are the replies of a deleted comment).
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessLevel, CommonIden, DbBmc, SoftDeleteIden};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::task::{Task, TaskBmc};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue};
+use sea_query::{Condition, Expr, Iden, Query};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// endregion:   --- Modules
+
+// region:      --- Comment Types
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Comment {
+    pub id: i64,
+    pub task_id: i64,
+    pub parent_id: Option<i64>,
+
+    pub author_id: i64,
+    pub body: String,
+
+    // -- Timestamps
+    //    (creator and last modified user_id/time)
+    pub cid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub ctime: OffsetDateTime,
+    pub mid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub mtime: OffsetDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct CommentForCreate {
+    pub task_id: i64,
+    /// The comment replied to (of the same task).
+    pub parent_id: Option<i64>,
+    pub body: String,
+}
+
+#[derive(Fields)]
+struct CommentForInsert {
+    task_id: i64,
+    parent_id: Option<i64>,
+    author_id: i64,
+    body: String,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct CommentForUpdate {
+    pub body: String,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+pub struct CommentFilter {
+    pub id: Option<OpValsInt64>,
+    pub task_id: Option<OpValsInt64>,
+    pub parent_id: Option<OpValsInt64>,
+    pub author_id: Option<OpValsInt64>,
+    pub body: Option<OpValsString>,
+
+    pub cid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub ctime: Option<OpValsValue>,
+    pub mid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum CommentIden {
+    TaskId,
+}
+
+// endregion:   --- Comment Types
+
+// region:      --- CommentBmc
+
+pub struct CommentBmc;
+
+impl DbBmc for CommentBmc {
+    const TABLE: &'static str = "comment";
+
+    /// The comments of the (non deleted) tasks readable by the user.
+    /// Owner level is the task owner level (for the comment moderation).
+    fn access_cond(ctx: &Ctx, level: AccessLevel) -> Option<Condition> {
+        let task_level = match level {
+            AccessLevel::Read | AccessLevel::Write => AccessLevel::Read,
+            AccessLevel::Owner => AccessLevel::Owner,
+        };
+        let mut task_ids = Query::select();
+        task_ids
+            .column(CommonIden::Id)
+            .from(TaskBmc::table_ref())
+            .cond_where(TaskBmc::access_cond(ctx, task_level)?)
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+
+        Some(Condition::all().add(Expr::col(CommentIden::TaskId).in_subquery(task_ids)))
+    }
+
+    /// The comments are deleted and restored with their task (and its project).
+    fn not_deleted_parent_cond() -> Option<Condition> {
+        let task_ids = TaskBmc::not_deleted_ids_query();
+
+        Some(Condition::all().add(Expr::col(CommentIden::TaskId).in_subquery(task_ids)))
+    }
+}
+
+impl CommentBmc {
+    /// Create the comment, authored by the ctx user.
+    pub async fn create(ctx: &Ctx, mm: &ModelManager, comment_c: CommentForCreate) -> Result<i64> {
+        let CommentForCreate {
+            task_id,
+            parent_id,
+            body,
+        } = comment_c;
+
+        let _task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+        if let Some(parent_id) = parent_id {
+            let parent = Self::get(ctx, mm, parent_id).await?;
+            if parent.task_id != task_id {
+                return Err(Error::CommentParentMismatch { parent_id, task_id });
+            }
+        }
+
+        let comment_i = CommentForInsert {
+            task_id,
+            parent_id,
+            author_id: ctx.user_id(),
+            body,
+        };
+        base::create::<Self, _>(ctx, mm, comment_i).await
+    }
+
+    pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Comment> {
+        base::get::<Self, _>(ctx, mm, id).await
+    }
+
+    /// List the comments of the task.
+    ///
+    /// default order: `ctime` (the oldest first)
+    pub async fn list_for_task(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Comment>> {
+        let _task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+
+        let filter = CommentFilter {
+            task_id: Some(task_id.into()),
+            ..Default::default()
+        };
+        let mut list_options = list_options.unwrap_or_default();
+        if list_options.order_bys.is_none() {
+            list_options.order_bys = Some("ctime".into());
+        }
+
+        base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), Some(list_options)).await
+    }
+
+    /// Update the comment (author only).
+    pub async fn update(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        comment_u: CommentForUpdate,
+    ) -> Result<()> {
+        let comment = Self::get(ctx, mm, id).await?;
+        if comment.author_id != ctx.user_id() {
+            return Err(Error::AccessDenied {
+                entity: Self::TABLE,
+                id,
+            });
+        }
+
+        base::update::<Self, _>(ctx, mm, id, comment_u).await
+    }
+
+    /// Delete the comment and its replies (author or project owners only).
+    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        let comment = Self::get(ctx, mm, id).await?;
+        if comment.author_id != ctx.user_id() {
+            base::ensure_access::<Self>(ctx, mm, AccessLevel::Owner, id).await?;
+        }
+
+        base::delete::<Self>(ctx, mm, id).await
+    }
+}
+
+// endregion:   --- CommentBmc
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::project::ProjectBmc;
+    use crate::model::project_member::{ProjectMemberBmc, ProjectRole};
+    use crate::model::user::{User, UserBmc};
+    use anyhow::{Context, Result};
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_comment_thread_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_comment_thread_ok project").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["task 01"])
+            .await?
+            .remove(0);
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let demo1_ctx = Ctx::new(demo1.id)?;
+        ProjectMemberBmc::add(&ctx, &mm, fx_project_id, demo1.id, ProjectRole::Viewer).await?;
+
+        // -- Exec
+        let comment_c = CommentForCreate {
+            task_id: fx_task.id,
+            parent_id: None,
+            body: "first".to_string(),
+        };
+        let root_comment_id = CommentBmc::create(&ctx, &mm, comment_c).await?;
+        let reply_c = CommentForCreate {
+            task_id: fx_task.id,
+            parent_id: Some(root_comment_id),
+            body: "reply".to_string(),
+        };
+        let reply_id = CommentBmc::create(&demo1_ctx, &mm, reply_c).await?;
+
+        // -- Check
+        let comments = CommentBmc::list_for_task(&demo1_ctx, &mm, fx_task.id, None).await?;
+        let bodies: Vec<&str> = comments.iter().map(|c| c.body.as_str()).collect();
+        assert_eq!(bodies, &["first", "reply"]);
+        assert_eq!(comments[1].author_id, demo1.id);
+        assert_eq!(comments[1].parent_id, Some(root_comment_id));
+
+        let comment_u = || CommentForUpdate {
+            body: "edited".to_string(),
+        };
+        let res = CommentBmc::update(&demo1_ctx, &mm, root_comment_id, comment_u()).await;
+        assert!(
+            matches!(
+                res,
+                Err(Error::AccessDenied {
+                    entity: "comment",
+                    ..
+                })
+            ),
+            "only the author should update the comment"
+        );
+        CommentBmc::update(&demo1_ctx, &mm, reply_id, comment_u()).await?;
+        let reply = CommentBmc::get(&ctx, &mm, reply_id).await?;
+        assert_eq!(reply.body, "edited");
+
+        // Deleting the thread root deletes its replies.
+        CommentBmc::delete(&ctx, &mm, root_comment_id).await?;
+        let comments = CommentBmc::list_for_task(&ctx, &mm, fx_task.id, None).await?;
+        assert!(comments.is_empty(), "replies should be deleted");
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
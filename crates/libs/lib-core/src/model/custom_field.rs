@@ -0,0 +1,578 @@
+//! Custom fields, the project specific task fields (e.g., "customer", "estimate", "sprint").
+//!
+//! Design:
+//!
+//! - A `custom_field_def` (name, type, and options for the `select` type) belongs to a project,
+//!   and gets the access of its project.
+//! - The task values are in the `task.custom` JSONB object, by field name. They are validated
+//!   against the project definitions by `TaskBmc` (see `CustomFieldValidator`),
+//!   and the null values are removed.
+//! - Deleting a definition removes its values from the project tasks (as audited task updates).
+//! - The `TaskFilter` `custom` node filters on the values (see `custom_to_sea_condition`):
+//!     - with a JSON path predicate, e.g., `{"custom": {"$eq": "$.estimate > 3"}}`
+//!       (validated before the task query, see `validate_custom_paths`)
+//!     - or with the values to contain, e.g., `{"custom": {"$eq": {"customer": "ACME"}}}`
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessLevel, CommonIden, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::project::{Project, ProjectBmc};
+use crate::model::task::{TaskBmc, TaskFilter};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::{Field, Fields};
+use modql::filter::{
+    FilterNodes, IntoSeaError, ListOptions, OpValValue, OpValsInt64, OpValsString, OpValsValue,
+    SeaResult,
+};
+use sea_query::extension::postgres::{PgBinOper, PgExpr};
+use sea_query::{Alias, ColumnRef, Condition, ConditionExpression, Expr, Iden, IntoIden, Query};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use time::{format_description, Date};
+
+// endregion:   --- Modules
+
+// region:      --- CustomField Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Bool,
+    /// `YYYY-MM-DD` string.
+    Date,
+    /// One of the definition options.
+    Select,
+}
+
+impl CustomFieldType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Number => "number",
+            Self::Bool => "bool",
+            Self::Date => "date",
+            Self::Select => "select",
+        }
+    }
+}
+
+impl TryFrom<String> for CustomFieldType {
+    type Error = Error;
+
+    fn try_from(field_type: String) -> Result<Self> {
+        match field_type.as_str() {
+            "text" => Ok(Self::Text),
+            "number" => Ok(Self::Number),
+            "bool" => Ok(Self::Bool),
+            "date" => Ok(Self::Date),
+            "select" => Ok(Self::Select),
+            _ => Err(Error::CustomFieldTypeUnknown { field_type }),
+        }
+    }
+}
+
+impl From<CustomFieldType> for sea_query::Value {
+    fn from(field_type: CustomFieldType) -> Self {
+        field_type.as_str().into()
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct CustomFieldDef {
+    pub id: i64,
+    pub project_id: i64,
+
+    pub name: String,
+    #[sqlx(try_from = "String")]
+    pub field_type: CustomFieldType,
+    /// The allowed values of a `select` field (JSON array of strings).
+    pub options: Option<Value>,
+
+    // -- Timestamps
+    //    (creator and last modified user_id/time)
+    pub cid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub ctime: OffsetDateTime,
+    pub mid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub mtime: OffsetDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct CustomFieldDefForCreate {
+    pub project_id: i64,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    /// Required for (and only for) a `select` field.
+    pub options: Option<Vec<String>>,
+}
+
+#[derive(Fields)]
+struct CustomFieldDefForInsert {
+    project_id: i64,
+    name: String,
+    field_type: CustomFieldType,
+    options: Option<Value>,
+}
+
+/// Only the `select` options can be changed
+/// (the existing task values are kept).
+#[derive(Deserialize)]
+pub struct CustomFieldDefForUpdate {
+    pub options: Vec<String>,
+}
+
+#[derive(Fields)]
+struct CustomFieldDefOptions {
+    options: Value,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+pub struct CustomFieldDefFilter {
+    pub id: Option<OpValsInt64>,
+    pub project_id: Option<OpValsInt64>,
+    pub name: Option<OpValsString>,
+    pub field_type: Option<OpValsString>,
+
+    pub cid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub ctime: Option<OpValsValue>,
+    pub mid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum CustomFieldDefIden {
+    ProjectId,
+}
+
+#[derive(Iden)]
+enum TaskCustomIden {
+    ProjectId,
+    Custom,
+}
+
+// endregion:   --- CustomField Types
+
+// region:      --- CustomFieldDefBmc
+
+pub struct CustomFieldDefBmc;
+
+impl DbBmc for CustomFieldDefBmc {
+    const TABLE: &'static str = "custom_field_def";
+
+    /// The definitions of the projects accessible to the user at the same `level`.
+    fn access_cond(ctx: &Ctx, level: AccessLevel) -> Option<Condition> {
+        let mut project_ids = Query::select();
+        project_ids
+            .column(CommonIden::Id)
+            .from(ProjectBmc::table_ref())
+            .cond_where(ProjectBmc::access_cond(ctx, level)?);
+
+        Some(
+            Condition::all().add(Expr::col(CustomFieldDefIden::ProjectId).in_subquery(project_ids)),
+        )
+    }
+
+    /// The definitions are deleted and restored with their project.
+    fn not_deleted_parent_cond() -> Option<Condition> {
+        let project_ids = ProjectBmc::not_deleted_ids_query();
+
+        Some(
+            Condition::all().add(Expr::col(CustomFieldDefIden::ProjectId).in_subquery(project_ids)),
+        )
+    }
+}
+
+impl CustomFieldDefBmc {
+    pub async fn create(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        field_def_c: CustomFieldDefForCreate,
+    ) -> Result<i64> {
+        let CustomFieldDefForCreate {
+            project_id,
+            name,
+            field_type,
+            options,
+        } = field_def_c;
+
+        let options = match (field_type, options) {
+            (CustomFieldType::Select, Some(options)) if !options.is_empty() => Some(json!(options)),
+            (CustomFieldType::Select, _) | (_, Some(_)) => {
+                return Err(Error::CustomFieldOptionsInvalid { name });
+            }
+            (_, None) => None,
+        };
+
+        let field_def_i = CustomFieldDefForInsert {
+            project_id,
+            name,
+            field_type,
+            options,
+        };
+        base::create::<Self, _>(ctx, mm, field_def_i).await
+    }
+
+    pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<CustomFieldDef> {
+        base::get::<Self, _>(ctx, mm, id).await
+    }
+
+    /// List the custom field definitions of the project (by name).
+    pub async fn list(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+    ) -> Result<Vec<CustomFieldDef>> {
+        let _project: Project = ProjectBmc::get(ctx, mm, project_id).await?;
+
+        let filter = CustomFieldDefFilter {
+            project_id: Some(project_id.into()),
+            ..Default::default()
+        };
+        let list_options = ListOptions {
+            order_bys: Some("name".into()),
+            ..Default::default()
+        };
+        base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), Some(list_options)).await
+    }
+
+    /// Replace the options of a `select` field.
+    pub async fn update(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        field_def_u: CustomFieldDefForUpdate,
+    ) -> Result<()> {
+        let field_def = Self::get(ctx, mm, id).await?;
+        let CustomFieldDefForUpdate { options } = field_def_u;
+        if field_def.field_type != CustomFieldType::Select || options.is_empty() {
+            return Err(Error::CustomFieldOptionsInvalid {
+                name: field_def.name,
+            });
+        }
+
+        let options = CustomFieldDefOptions {
+            options: json!(options),
+        };
+        base::update::<Self, _>(ctx, mm, id, options).await
+    }
+
+    /// Delete the definition, and its values from the project tasks
+    /// (as task updates, with their version and change log).
+    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        let field_def = Self::get(ctx, mm, id).await?;
+
+        // -- Prep the task updates
+        let cond = Condition::all()
+            .add(Expr::col(TaskCustomIden::ProjectId).eq(field_def.project_id))
+            .add(Expr::cust_with_values(
+                r#"jsonb_exists("custom", $1)"#,
+                [field_def.name.as_str()],
+            ));
+        let fields = Fields::new(vec![Field::new(
+            TaskCustomIden::Custom.into_iden(),
+            Expr::col(TaskCustomIden::Custom).sub(field_def.name.as_str()),
+        )]);
+
+        // In a transaction
+        mm.in_txn(|mm| async move {
+            base::delete::<Self>(ctx, &mm, id).await?;
+            base::update_by_cond::<TaskBmc>(ctx, &mm, cond, fields).await?;
+
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl CustomFieldDef {
+    fn accepts(&self, value: &Value) -> bool {
+        match self.field_type {
+            CustomFieldType::Text => value.is_string(),
+            CustomFieldType::Number => value.is_number(),
+            CustomFieldType::Bool => value.is_boolean(),
+            CustomFieldType::Date => value.as_str().is_some_and(is_date),
+            CustomFieldType::Select => value.as_str().is_some_and(|value| {
+                self.options
+                    .as_ref()
+                    .and_then(Value::as_array)
+                    .is_some_and(|options| options.iter().any(|option| option == value))
+            }),
+        }
+    }
+}
+
+/// Validates the task `custom` values against their project custom fields
+/// (the definitions are loaded once per project).
+#[derive(Default)]
+pub(crate) struct CustomFieldValidator {
+    field_defs_by_project: HashMap<i64, Vec<CustomFieldDef>>,
+}
+
+impl CustomFieldValidator {
+    /// Returns the `custom` values without the null values, or the first invalid value error.
+    pub(crate) async fn validate(
+        &mut self,
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        custom: Value,
+    ) -> Result<Value> {
+        let Value::Object(values) = custom else {
+            return Err(Error::TaskCustomNotObject);
+        };
+        let field_defs = match self.field_defs_by_project.entry(project_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(CustomFieldDefBmc::list(ctx, mm, project_id).await?)
+            }
+        };
+
+        let mut valid_values = Map::new();
+        for (name, value) in values {
+            if value.is_null() {
+                continue;
+            }
+            let Some(field_def) = field_defs.iter().find(|field_def| field_def.name == name) else {
+                return Err(Error::CustomFieldUnknown { project_id, name });
+            };
+            if !field_def.accepts(&value) {
+                return Err(Error::CustomFieldValueInvalid {
+                    name,
+                    expected: field_def.field_type.as_str(),
+                });
+            }
+            valid_values.insert(name, value);
+        }
+
+        Ok(Value::Object(valid_values))
+    }
+}
+
+fn is_date(value: &str) -> bool {
+    format_description::parse("[year]-[month]-[day]")
+        .is_ok_and(|format| Date::parse(value, &format).is_ok())
+}
+
+// endregion:   --- CustomFieldDefBmc
+
+// region:      --- CustomField Filters
+
+/// `TaskFilter::custom` condition: the tasks with the custom values matching
+/// the JSON path predicate (string), or containing the values (object).
+pub(crate) fn custom_to_sea_condition(
+    col: &ColumnRef,
+    op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+    let OpValValue::Eq(value) = op_value else {
+        return Err(IntoSeaError::custom(
+            "custom filter only supports '$eq' (with a JSON path predicate or a values object)",
+        ));
+    };
+
+    let expr = match value {
+        Value::String(path) => Expr::col(col.clone()).binary(
+            PgBinOper::Matches,
+            Expr::val(path).cast_as(Alias::new("jsonpath")),
+        ),
+        Value::Object(_) => Expr::col(col.clone()).contains(Expr::val(value)),
+        value => {
+            return Err(IntoSeaError::custom(format!(
+                "custom filter should be a JSON path predicate or a values object, not {value}"
+            )))
+        }
+    };
+
+    Ok(ConditionExpression::SimpleExpr(expr))
+}
+
+/// Returns `Error::CustomFilterPathInvalid` if a JSON path predicate of the `TaskFilter::custom`
+/// nodes is not a valid `jsonpath` (parsed by the database, before the task query).
+pub(crate) async fn validate_custom_paths(
+    mm: &ModelManager,
+    filter: Option<&[TaskFilter]>,
+) -> Result<()> {
+    let paths = filter
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|filter| filter.custom.as_ref())
+        .flat_map(|custom| custom.0.iter())
+        .filter_map(|op_value| match op_value {
+            OpValValue::Eq(Value::String(path)) => Some(path),
+            _ => None,
+        });
+
+    for path in paths {
+        let sqlx_query = sqlx::query("SELECT $1::jsonpath").bind(path);
+        if let Err(err) = mm.dbx().execute(sqlx_query).await {
+            let err = Error::from(err);
+            return Err(match err.as_database_error() {
+                Some(_) => Error::CustomFilterPathInvalid { path: path.clone() },
+                None => err,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// endregion:   --- CustomField Filters
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::change_log::{ChangeLogBmc, ChangeLogFilter};
+    use crate::model::project::ProjectBmc;
+    use crate::model::task::{TaskForCreate, TaskForUpdate};
+    use anyhow::Result;
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_custom_fields_validate_filter_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_custom_fields_validate_filter_ok project")
+                .await?;
+        let fx_field_defs = [
+            ("customer", CustomFieldType::Text, None),
+            ("estimate", CustomFieldType::Number, None),
+            (
+                "sprint",
+                CustomFieldType::Select,
+                Some(vec!["S1".to_string()]),
+            ),
+        ];
+        let mut estimate_def_id = 0;
+        for (name, field_type, options) in fx_field_defs {
+            let field_def_c = CustomFieldDefForCreate {
+                project_id: fx_project_id,
+                name: name.to_string(),
+                field_type,
+                options,
+            };
+            let id = CustomFieldDefBmc::create(&ctx, &mm, field_def_c).await?;
+            if name == "estimate" {
+                estimate_def_id = id;
+            }
+        }
+        let fx_task_c = |title: &str, custom: Value| TaskForCreate {
+            project_id: fx_project_id,
+            title: title.to_string(),
+            custom: Some(custom),
+            ..Default::default()
+        };
+
+        // -- Exec & Check (validate)
+        let big_id = TaskBmc::create(
+            &ctx,
+            &mm,
+            fx_task_c(
+                "big",
+                json!({"customer": "ACME", "estimate": 5, "sprint": "S1"}),
+            ),
+        )
+        .await?;
+        TaskBmc::create(
+            &ctx,
+            &mm,
+            fx_task_c("small", json!({"estimate": 1, "sprint": null})),
+        )
+        .await?;
+        let res = TaskBmc::create(&ctx, &mm, fx_task_c("bad", json!({"sprint": "S9"}))).await;
+        assert!(
+            matches!(res, Err(Error::CustomFieldValueInvalid { ref name, .. }) if name == "sprint"),
+            "should reject a value not in the options"
+        );
+        let task_u = TaskForUpdate {
+            custom: Some(json!({"color": "red"})),
+            ..Default::default()
+        };
+        let res = TaskBmc::update(&ctx, &mm, big_id, task_u).await;
+        assert!(
+            matches!(res, Err(Error::CustomFieldUnknown { ref name, .. }) if name == "color"),
+            "should reject an undefined field"
+        );
+
+        // -- Exec & Check (filter)
+        for custom in [json!("$.estimate > 3"), json!({"customer": "ACME"})] {
+            let filter = TaskFilter {
+                project_id: Some(fx_project_id.into()),
+                custom: Some(serde_json::from_value(json!({ "$eq": custom }))?),
+                ..Default::default()
+            };
+            let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+            let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+            assert_eq!(titles, &["big"], "filter {custom}");
+        }
+
+        // -- Exec & Check (delete definition)
+        let fx_version = TaskBmc::get(&ctx, &mm, big_id).await?.version;
+        CustomFieldDefBmc::delete(&ctx, &mm, estimate_def_id).await?;
+        let task = TaskBmc::get(&ctx, &mm, big_id).await?;
+        assert_eq!(task.custom, json!({"customer": "ACME", "sprint": "S1"}));
+        assert_eq!(task.version, fx_version + 1);
+        let filter = ChangeLogFilter {
+            entity: Some(TaskBmc::TABLE.into()),
+            entity_id: Some(big_id.into()),
+            op: Some("update".into()),
+            ..Default::default()
+        };
+        let changes = ChangeLogBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        assert_eq!(changes.len(), 1, "the value removal should be logged");
+        assert_eq!(
+            changes[0].diff["custom"]["new"],
+            json!({"customer": "ACME", "sprint": "S1"})
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_custom_filter_err_path_invalid() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_path = "$.estimate >";
+        let filter = TaskFilter {
+            custom: Some(serde_json::from_value(json!({ "$eq": fx_path }))?),
+            ..Default::default()
+        };
+
+        // -- Exec
+        let res = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await;
+
+        // -- Check
+        assert!(
+            matches!(res, Err(Error::CustomFilterPathInvalid { ref path }) if path == fx_path),
+            "should reject the invalid JSON path"
+        );
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
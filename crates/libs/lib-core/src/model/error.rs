@@ -1,4 +1,5 @@
-use crate::model::store::dbx;
+use crate::model::storage;
+use crate::model::store::{dbx, migrator};
 use derive_more::From;
 use lib_auth::pwd;
 use serde::Serialize;
@@ -17,21 +18,128 @@ pub enum Error {
         entity: &'static str,
         id: i64,
     },
+    AccessDenied {
+        entity: &'static str,
+        id: i64,
+    },
     ListLimitOverMax {
         max: i64,
         actual: i64,
     },
+    ListOrderByUnknown {
+        order_by: String,
+    },
+    ListCursorInvalid {
+        cursor: String,
+    },
+    ListCursorWithMultiOrderBys,
+    ListCursorWithOffset,
+    BatchSizeOverMax {
+        max: i64,
+        actual: i64,
+    },
+    BatchTargetInvalid,
+    BatchFilterEmpty {
+        entity: &'static str,
+    },
+    SoftDeleteNotSupported {
+        entity: &'static str,
+    },
+    VersionNotSupported {
+        entity: &'static str,
+    },
+    VersionConflict {
+        entity: &'static str,
+        id: i64,
+        current: i64,
+    },
 
     // -- Modules
     #[from]
     Pwd(pwd::Error),
     #[from]
     Dbx(dbx::Error),
+    #[from]
+    Migrator(migrator::Error),
+    #[from]
+    Storage(storage::Error),
 
     // -- Db
     UserAlreadyExists {
         username: String,
     },
+    UserNotAdmin {
+        user_id: i64,
+    },
+    UserDeleteTransferToSelf {
+        user_id: i64,
+    },
+    ProjectMemberAlreadyExists {
+        project_id: i64,
+        user_id: i64,
+    },
+    ProjectMemberNotFound {
+        project_id: i64,
+        user_id: i64,
+    },
+    ProjectRoleUnknown {
+        role: String,
+    },
+    TaskStatusUnknown {
+        status: String,
+    },
+    SearchEntityUnknown {
+        entity: String,
+    },
+    TaskParentProjectMismatch {
+        id: i64,
+        parent_task_id: i64,
+    },
+    TaskParentCycle {
+        id: i64,
+        parent_task_id: i64,
+    },
+    TaskBlocked {
+        id: i64,
+    },
+    TaskAssigneeNoAccess {
+        assignee_id: i64,
+        project_id: i64,
+    },
+    TaskPlacementInvalid {
+        id: i64,
+        neighbor_id: i64,
+    },
+    TaskDependencyCycle {
+        task_id: i64,
+        blocker_task_id: i64,
+    },
+    CustomFieldTypeUnknown {
+        field_type: String,
+    },
+    CustomFieldOptionsInvalid {
+        name: String,
+    },
+    CustomFieldUnknown {
+        project_id: i64,
+        name: String,
+    },
+    CustomFieldValueInvalid {
+        name: String,
+        expected: &'static str,
+    },
+    TaskCustomNotObject,
+    CustomFilterPathInvalid {
+        path: String,
+    },
+    LabelProjectMismatch {
+        label_id: i64,
+        task_id: i64,
+    },
+    CommentParentMismatch {
+        parent_id: i64,
+        task_id: i64,
+    },
     UniqueViolation {
         table: String,
         constraint: String,
@@ -0,0 +1,571 @@
+//! Labels, the project scoped tags assigned to the tasks of the project.
+//!
+//! Design:
+//!
+//! - A label belongs to a project (unique name per project), and gets the access of its project.
+//! - The task/label assignments are in the `task_label` join table (`attach`/`detach`),
+//!   which needs write access to the task.
+//! - An `attach`/`detach` is recorded in the change log as a change of the task `label_ids`.
+//! - The `TaskFilter` `labels_any`/`labels_all` and `LabelFilter` `task_id` nodes are
+//!   subqueries on `task_label` (see the `to_sea_condition_fn` functions below), so that the
+//!   `base::list` single table select can filter on the assignments.
+//!     - e.g., `{"labels_any": {"$eq": [1001, 1002]}}` (or `{"labels_all": 1001}`)
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::generate_common_bmc_fns;
+use crate::model::base::{self, AccessLevel, CommonIden, DbBmc};
+use crate::model::change_log::{ChangeLogBmc, ChangeOp};
+use crate::model::modql_utils::{time_to_sea_value, NotNull, NullableValue};
+use crate::model::project::ProjectBmc;
+use crate::model::task::{Task, TaskBmc};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+    FilterNodes, IntoSeaError, ListOptions, OpValValue, OpValsInt64, OpValsString, OpValsValue,
+    SeaResult,
+};
+use sea_query::{
+    ColumnRef, Condition, ConditionExpression, Expr, Iden, OnConflict, Order, PostgresQueryBuilder,
+    Query, SelectStatement,
+};
+use sea_query_binder::{SqlxBinder, SqlxValues};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// endregion:   --- Modules
+
+// region:      --- Label Types
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Label {
+    pub id: i64,
+    pub project_id: i64,
+
+    pub name: String,
+    pub color: Option<String>,
+
+    // -- Timestamps
+    //    (creator and last modified user_id/time)
+    pub cid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub ctime: OffsetDateTime,
+    pub mid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Default, Deserialize)]
+pub struct LabelForCreate {
+    pub project_id: i64,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// `color` is tri-state (absent: not updated, `null`: cleared, see `NullableValue`),
+/// and a `null` `name` is rejected (see `NotNull`).
+#[serde_as]
+#[derive(Fields, Default, Deserialize)]
+pub struct LabelForUpdate {
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde_as(as = "NullableValue<_>")]
+    #[serde(default)]
+    pub color: Option<NullableValue<String>>,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+pub struct LabelFilter {
+    pub id: Option<OpValsInt64>,
+    pub project_id: Option<OpValsInt64>,
+    pub name: Option<OpValsString>,
+    pub color: Option<OpValsString>,
+    /// The labels attached to the task(s).
+    #[modql(to_sea_condition_fn = "task_id_to_sea_condition")]
+    pub task_id: Option<OpValsValue>,
+
+    pub cid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub ctime: Option<OpValsValue>,
+    pub mid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum LabelIden {
+    ProjectId,
+}
+
+#[derive(Iden)]
+enum TaskLabelIden {
+    #[iden = "task_label"]
+    Table,
+    TaskId,
+    LabelId,
+}
+
+// endregion:   --- Label Types
+
+// region:      --- LabelBmc
+
+pub struct LabelBmc;
+
+impl DbBmc for LabelBmc {
+    const TABLE: &'static str = "label";
+
+    /// The labels of the projects accessible to the user at the same `level`.
+    fn access_cond(ctx: &Ctx, level: AccessLevel) -> Option<Condition> {
+        let mut project_ids = Query::select();
+        project_ids
+            .column(CommonIden::Id)
+            .from(ProjectBmc::table_ref())
+            .cond_where(ProjectBmc::access_cond(ctx, level)?);
+
+        Some(Condition::all().add(Expr::col(LabelIden::ProjectId).in_subquery(project_ids)))
+    }
+
+    /// The labels are deleted and restored with their project.
+    fn not_deleted_parent_cond() -> Option<Condition> {
+        let project_ids = ProjectBmc::not_deleted_ids_query();
+
+        Some(Condition::all().add(Expr::col(LabelIden::ProjectId).in_subquery(project_ids)))
+    }
+}
+
+generate_common_bmc_fns!(
+    Bmc: LabelBmc,
+    Entity: Label,
+    ForCreate: LabelForCreate,
+    ForUpdate: LabelForUpdate,
+    Filter: LabelFilter,
+);
+
+impl LabelBmc {
+    /// Attach the label to the task (no-op when already attached).
+    /// The label must be of the task project.
+    pub async fn attach(ctx: &Ctx, mm: &ModelManager, task_id: i64, label_id: i64) -> Result<()> {
+        Self::ensure_task_label(ctx, mm, task_id, label_id).await?;
+
+        let mut query = Query::insert();
+        query
+            .into_table(TaskLabelIden::Table)
+            .columns([TaskLabelIden::TaskId, TaskLabelIden::LabelId])
+            .values([task_id.into(), label_id.into()])?
+            .on_conflict(
+                OnConflict::columns([TaskLabelIden::TaskId, TaskLabelIden::LabelId])
+                    .do_nothing()
+                    .to_owned(),
+            );
+
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        Self::exec_task_label(ctx, mm, task_id, sql, values).await
+    }
+
+    /// Detach the label from the task (no-op when not attached).
+    pub async fn detach(ctx: &Ctx, mm: &ModelManager, task_id: i64, label_id: i64) -> Result<()> {
+        Self::ensure_task_label(ctx, mm, task_id, label_id).await?;
+
+        let mut query = Query::delete();
+        query
+            .from_table(TaskLabelIden::Table)
+            .and_where(Expr::col(TaskLabelIden::TaskId).eq(task_id))
+            .and_where(Expr::col(TaskLabelIden::LabelId).eq(label_id));
+
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        Self::exec_task_label(ctx, mm, task_id, sql, values).await
+    }
+
+    /// List the labels attached to the task.
+    pub async fn list_for_task(ctx: &Ctx, mm: &ModelManager, task_id: i64) -> Result<Vec<Label>> {
+        let _task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+
+        let filter = LabelFilter {
+            task_id: Some(OpValValue::Eq(json!(task_id)).into()),
+            ..Default::default()
+        };
+        let list_options = ListOptions {
+            order_bys: Some("name".into()),
+            ..Default::default()
+        };
+        base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), Some(list_options)).await
+    }
+}
+
+// endregion:   --- LabelBmc
+
+// region:      --- LabelBmc Privates
+
+impl LabelBmc {
+    /// Ensure the ctx user can change the task labels, and the label is of the task project.
+    async fn ensure_task_label(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        label_id: i64,
+    ) -> Result<()> {
+        let task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+        base::ensure_access::<TaskBmc>(ctx, mm, AccessLevel::Write, task_id).await?;
+
+        let label = Self::get(ctx, mm, label_id).await?;
+        if label.project_id != task.project_id {
+            return Err(Error::LabelProjectMismatch { label_id, task_id });
+        }
+
+        Ok(())
+    }
+
+    /// Execute the `task_label` mutation of the task, and record it in the change log
+    /// as a change of the task `label_ids` (nothing is recorded for a no-op).
+    async fn exec_task_label(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        sql: String,
+        values: SqlxValues,
+    ) -> Result<()> {
+        // In a transaction (the change log is written with the mutation)
+        mm.in_txn(|mm| async move {
+            let before = Self::task_snapshot(&mm, task_id).await?;
+
+            // -- Exec query
+            let sqlx_query = sqlx::query_with(&sql, values);
+            let count = mm.dbx().execute(sqlx_query).await?;
+            if count == 0 {
+                return Ok(());
+            }
+
+            // -- Log change
+            let after = Self::task_snapshot(&mm, task_id).await?;
+            ChangeLogBmc::log_change::<TaskBmc>(ctx, &mm, task_id, ChangeOp::Update, before, after)
+                .await
+        })
+        .await
+    }
+
+    /// Returns the change log snapshot of the task (see `ChangeLogBmc::snapshot`),
+    /// with its `label_ids`.
+    async fn task_snapshot(mm: &ModelManager, task_id: i64) -> Result<Option<Value>> {
+        let Some(mut snapshot) = ChangeLogBmc::snapshot::<TaskBmc>(mm, task_id).await? else {
+            return Ok(None);
+        };
+
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .column(TaskLabelIden::LabelId)
+            .from(TaskLabelIden::Table)
+            .and_where(Expr::col(TaskLabelIden::TaskId).eq(task_id))
+            .order_by(TaskLabelIden::LabelId, Order::Asc);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let label_ids: Vec<i64> = mm
+            .dbx()
+            .fetch_all(sqlx_query)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect();
+        snapshot["label_ids"] = json!(label_ids);
+
+        Ok(Some(snapshot))
+    }
+}
+
+// endregion:   --- LabelBmc Privates
+
+// region:      --- Label Filters
+
+/// `TaskFilter::labels_any` condition: the tasks with at least one of the labels.
+pub(crate) fn labels_any_to_sea_condition(
+    _col: &ColumnRef,
+    op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+    let label_ids = ids_from_op_value(op_value)?;
+
+    let task_ids = task_ids_query(&label_ids);
+
+    Ok(ConditionExpression::SimpleExpr(
+        Expr::col(CommonIden::Id).in_subquery(task_ids),
+    ))
+}
+
+/// `TaskFilter::labels_all` condition: the tasks with all the labels.
+pub(crate) fn labels_all_to_sea_condition(
+    _col: &ColumnRef,
+    op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+    let label_ids = ids_from_op_value(op_value)?;
+    if label_ids.is_empty() {
+        return Ok(ConditionExpression::Condition(Condition::all()));
+    }
+
+    let mut task_ids = task_ids_query(&label_ids);
+    task_ids.group_by_col(TaskLabelIden::TaskId).and_having(
+        Expr::col(TaskLabelIden::LabelId)
+            .count_distinct()
+            .eq(label_ids.len() as i64),
+    );
+
+    Ok(ConditionExpression::SimpleExpr(
+        Expr::col(CommonIden::Id).in_subquery(task_ids),
+    ))
+}
+
+/// `LabelFilter::task_id` condition: the labels attached to one of the tasks.
+fn task_id_to_sea_condition(
+    _col: &ColumnRef,
+    op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+    let task_ids = ids_from_op_value(op_value)?;
+
+    let mut label_ids = Query::select();
+    label_ids
+        .column(TaskLabelIden::LabelId)
+        .from(TaskLabelIden::Table)
+        .and_where(Expr::col(TaskLabelIden::TaskId).is_in(task_ids));
+
+    Ok(ConditionExpression::SimpleExpr(
+        Expr::col(CommonIden::Id).in_subquery(label_ids),
+    ))
+}
+
+/// Returns the query of the ids of the tasks with one of the `label_ids`.
+fn task_ids_query(label_ids: &[i64]) -> SelectStatement {
+    let mut query = Query::select();
+    query
+        .column(TaskLabelIden::TaskId)
+        .from(TaskLabelIden::Table)
+        .and_where(Expr::col(TaskLabelIden::LabelId).is_in(label_ids.iter().copied()));
+
+    query
+}
+
+/// Returns the (deduplicated) ids of an `$eq` op value, which is an id or an array of ids.
+///
+/// Note: The other operators are not supported (`$in` is deserialized as `$notIn`
+///       by modql 0.3.10 for the json values).
+fn ids_from_op_value(op_value: OpValValue) -> SeaResult<Vec<i64>> {
+    let OpValValue::Eq(value) = op_value else {
+        return Err(IntoSeaError::custom(
+            "id list filter only supports '$eq' (with an id or an array of ids)",
+        ));
+    };
+
+    let values = match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+    let mut ids = values
+        .into_iter()
+        .map(|value| {
+            value.as_i64().ok_or_else(|| {
+                IntoSeaError::custom(format!("id should be an integer, not {value}"))
+            })
+        })
+        .collect::<SeaResult<Vec<i64>>>()?;
+    ids.sort_unstable();
+    ids.dedup();
+
+    Ok(ids)
+}
+
+// endregion:   --- Label Filters
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::task::TaskFilter;
+    use anyhow::Result;
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_attach_and_filter_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_attach_and_filter_ok project").await?;
+        let fx_tasks =
+            _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["task 01", "task 02", "task 03"])
+                .await?;
+        let mut fx_label_ids = Vec::new();
+        for name in ["backend", "infra"] {
+            let label_c = LabelForCreate {
+                project_id: fx_project_id,
+                name: name.to_string(),
+                ..Default::default()
+            };
+            fx_label_ids.push(LabelBmc::create(&ctx, &mm, label_c).await?);
+        }
+        let (backend_id, infra_id) = (fx_label_ids[0], fx_label_ids[1]);
+
+        // -- Exec
+        LabelBmc::attach(&ctx, &mm, fx_tasks[0].id, backend_id).await?;
+        LabelBmc::attach(&ctx, &mm, fx_tasks[0].id, infra_id).await?;
+        LabelBmc::attach(&ctx, &mm, fx_tasks[1].id, infra_id).await?;
+        LabelBmc::attach(&ctx, &mm, fx_tasks[2].id, backend_id).await?;
+        LabelBmc::attach(&ctx, &mm, fx_tasks[2].id, backend_id).await?; // no-op
+        LabelBmc::detach(&ctx, &mm, fx_tasks[2].id, backend_id).await?;
+
+        // -- Check
+        let labels = LabelBmc::list_for_task(&ctx, &mm, fx_tasks[0].id).await?;
+        let names: Vec<&str> = labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, &["backend", "infra"]);
+
+        let filter_any = TaskFilter {
+            labels_any: Some(OpValValue::Eq(json!([backend_id, infra_id])).into()),
+            ..Default::default()
+        };
+        let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter_any]), None).await?;
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, &["task 01", "task 02"]);
+
+        let filter_all = TaskFilter {
+            labels_all: Some(OpValValue::Eq(json!([backend_id, infra_id])).into()),
+            ..Default::default()
+        };
+        let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter_all]), None).await?;
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, &["task 01"]);
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_update_null_clear_color_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_update_null_clear_color_ok project").await?;
+        let label_c = LabelForCreate {
+            project_id: fx_project_id,
+            name: "backend".to_string(),
+            color: Some("#ff0000".to_string()),
+        };
+        let fx_label_id = LabelBmc::create(&ctx, &mm, label_c).await?;
+
+        // -- Exec
+        let label_u: LabelForUpdate = serde_json::from_value(json!({"color": null}))?;
+        LabelBmc::update(&ctx, &mm, fx_label_id, label_u).await?;
+
+        // -- Check
+        let label = LabelBmc::get(&ctx, &mm, fx_label_id).await?;
+        assert_eq!(label.color, None, "color should be cleared");
+        assert_eq!(label.name, "backend", "absent name should be unchanged");
+        let res = serde_json::from_value::<LabelForUpdate>(json!({"name": null}));
+        assert!(res.is_err(), "null name should be rejected");
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_attach_err_project_mismatch() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_attach_err_project_mismatch project").await?;
+        let fx_other_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_attach_err_project_mismatch other").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["task 01"])
+            .await?
+            .remove(0);
+        let label_c = LabelForCreate {
+            project_id: fx_other_project_id,
+            name: "docs".to_string(),
+            ..Default::default()
+        };
+        let fx_label_id = LabelBmc::create(&ctx, &mm, label_c).await?;
+
+        // -- Exec
+        let res = LabelBmc::attach(&ctx, &mm, fx_task.id, fx_label_id).await;
+
+        // -- Check
+        assert!(
+            matches!(res, Err(Error::LabelProjectMismatch { .. })),
+            "should not attach a label of another project"
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+        ProjectBmc::purge(&ctx, &mm, fx_other_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_attach_dry_run_change_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_attach_dry_run_change_ok project").await?;
+        let fx_tasks = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["task 01"]).await?;
+        let task_id = fx_tasks[0].id;
+        let label_c = LabelForCreate {
+            project_id: fx_project_id,
+            name: "backend".to_string(),
+            ..Default::default()
+        };
+        let label_id = LabelBmc::create(&ctx, &mm, label_c).await?;
+
+        // -- Exec
+        let ((), changes) = mm
+            .dry_run(|mm| {
+                let ctx = ctx.clone();
+                async move {
+                    LabelBmc::attach(&ctx, &mm, task_id, label_id).await?;
+                    // Already attached, so no change.
+                    LabelBmc::attach(&ctx, &mm, task_id, label_id).await
+                }
+            })
+            .await?;
+
+        // -- Check
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].entity, "task");
+        assert_eq!(changes[0].entity_id, task_id);
+        assert_eq!(changes[0].op, "update");
+        assert_eq!(
+            changes[0].diff,
+            json!({"label_ids": {"old": [], "new": [label_id]}})
+        );
+        let labels = LabelBmc::list_for_task(&ctx, &mm, task_id).await?;
+        assert!(
+            labels.is_empty(),
+            "the dry run label should not be attached"
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
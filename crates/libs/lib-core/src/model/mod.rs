@@ -15,6 +15,11 @@
 //! - In frameworks like Axum, Tauri, `ModelManager` are typically used as App State.
 //! - ModelManager are designed to be passed as an argument
 //!   to all Model Controllers functions.
+//! - `ModelManager::dry_run` runs mutations in a transaction that is always rolled back,
+//!   and returns their change set (the `ChangeLog` rows they wrote).
+//! - The attachment storage deletes of a transaction are deferred until it is committed
+//!   (see `ModelManager::delete_storage_after_commit`), so that a rollback keeps the content
+//!   of the restored rows.
 //!
 
 // region:     --- Modules
@@ -22,32 +27,79 @@
 mod base;
 mod error;
 mod modql_utils;
-mod store;
+pub mod storage;
+pub(crate) mod store;
 
+pub mod attachment;
+pub mod change_log;
+pub mod comment;
+pub mod custom_field;
+pub mod label;
 pub mod project;
+pub mod project_member;
+pub mod search;
 pub mod task;
+pub mod task_dependency;
+pub mod task_position;
 pub mod user;
 
+pub use self::base::{BatchItemResult, BatchItemStatus, BatchTarget, ListPage};
 pub use self::error::{Error, Result};
+pub use self::modql_utils::NullableValue;
+pub use self::store::migrator::MigrationStatus;
 
+use crate::core_config;
+use crate::model::change_log::{ChangeLog, ChangeLogBmc};
+use crate::model::storage::{AttachmentStorage, LocalStorage};
 use crate::model::store::dbx::Dbx;
-use crate::model::store::new_db_pool;
+use crate::model::store::{migrator, new_db_pool};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
 
 // endregion:  --- Modules
 
 #[derive(Clone)]
 pub struct ModelManager {
     dbx: Dbx,
+    storage: Arc<dyn AttachmentStorage>,
+    /// The storage keys to delete when the transaction is committed
+    /// (shared by the clones of a ModelManager with txn).
+    storage_deletes: Arc<Mutex<Vec<String>>>,
 }
 
 impl ModelManager {
     /// Constructor
+    /// (applies the pending migrations when `SERVICE_DB_AUTO_MIGRATE` is true)
     pub async fn new() -> Result<Self> {
+        let mm = Self::new_without_migrate().await?;
+
+        if core_config().DB_AUTO_MIGRATE {
+            mm.migrate_up().await?;
+        }
+
+        Ok(mm)
+    }
+
+    /// Constructor that never applies migrations
+    /// (e.g., for migration tooling).
+    pub async fn new_without_migrate() -> Result<Self> {
         let db = new_db_pool()
             .await
             .map_err(|ex| Error::CantCreateModelManagerProvider(ex.to_string()))?;
         let dbx = Dbx::new(db, false)?;
-        Ok(ModelManager { dbx })
+        let storage = Arc::new(LocalStorage::new(&core_config().ATTACHMENT_FOLDER));
+        Ok(ModelManager {
+            dbx,
+            storage,
+            storage_deletes: Arc::default(),
+        })
+    }
+
+    /// Returns this ModelManager with the attachment `storage`
+    /// (the default is the `SERVICE_ATTACHMENT_FOLDER` `LocalStorage`).
+    pub fn with_storage(self, storage: Arc<dyn AttachmentStorage>) -> Self {
+        ModelManager { storage, ..self }
     }
 
     /// Returns the sqlx db pool reference.
@@ -56,8 +108,261 @@ impl ModelManager {
         &self.dbx
     }
 
+    /// Returns the attachment storage.
+    /// (Only for the model layer)
+    pub(crate) fn storage(&self) -> &dyn AttachmentStorage {
+        self.storage.as_ref()
+    }
+
     pub fn new_with_txn(&self) -> Result<ModelManager> {
         let dbx = Dbx::new(self.dbx.db().clone(), true)?;
-        Ok(ModelManager { dbx })
+        Ok(ModelManager {
+            dbx,
+            storage: self.storage.clone(),
+            storage_deletes: Arc::default(),
+        })
+    }
+
+    /// Delete the attachment storage `storage_key` content when the current transaction
+    /// is committed (nothing is deleted if it is rolled back), or now if not in a transaction.
+    pub(crate) async fn delete_storage_after_commit(&self, storage_key: String) -> Result<()> {
+        if self.dbx.has_open_txn().await {
+            self.storage_deletes_guard().push(storage_key);
+            Ok(())
+        } else {
+            Ok(self.storage.delete(&storage_key).await?)
+        }
+    }
+
+    /// Same as `new_with_txn`, but returns a clone of this ModelManager when it is already
+    /// with txn, so that its `begin_txn`/`commit_txn` nest in the current transaction.
+    pub fn ensure_txn(&self) -> Result<ModelManager> {
+        if self.dbx.with_txn() {
+            Ok(self.clone())
+        } else {
+            self.new_with_txn()
+        }
+    }
+
+    /// Run `f` in a transaction (nested in the current one, if any), with a ModelManager
+    /// sharing the transaction. Commits when `f` returns Ok, and rolls back otherwise.
+    ///
+    /// e.g., `mm.in_txn(|mm| async move { ... Ok(id) }).await?`
+    pub async fn in_txn<F, Fut, T, E>(&self, f: F) -> core::result::Result<T, E>
+    where
+        F: FnOnce(ModelManager) -> Fut,
+        Fut: Future<Output = core::result::Result<T, E>>,
+        E: From<Error>,
+    {
+        let mm = self.ensure_txn()?;
+        mm.dbx().begin_txn().await.map_err(Error::from)?;
+        let storage_deletes_len = mm.storage_deletes_guard().len();
+
+        match f(mm.clone()).await {
+            Ok(value) => {
+                if let Err(ex) = mm.dbx().commit_txn().await {
+                    mm.storage_deletes_guard().truncate(storage_deletes_len);
+                    return Err(Error::from(ex).into());
+                }
+                if !mm.dbx().has_open_txn().await {
+                    mm.run_storage_deletes().await;
+                }
+                Ok(value)
+            }
+            Err(ex) => {
+                // The error of `f` is the one returned
+                // (a failed rollback leaves the transaction aborted anyway).
+                let _ = mm.dbx().rollback_txn().await;
+                mm.storage_deletes_guard().truncate(storage_deletes_len);
+                Err(ex)
+            }
+        }
+    }
+
+    /// Run `f` in a new transaction that is always rolled back, and return its result
+    /// with the change set it would have committed (the `ChangeLog` rows it wrote).
+    ///
+    /// Note: The transaction is `REPEATABLE READ`, so that the change log rows
+    ///       committed by other transactions meanwhile are not part of the change set.
+    pub async fn dry_run<F, Fut, T, E>(&self, f: F) -> core::result::Result<(T, Vec<ChangeLog>), E>
+    where
+        F: FnOnce(ModelManager) -> Fut,
+        Fut: Future<Output = core::result::Result<T, E>>,
+        E: From<Error>,
+    {
+        let mm = self.new_with_txn()?;
+        mm.dbx().begin_txn().await.map_err(Error::from)?;
+
+        let res: core::result::Result<_, E> = async {
+            let sqlx_query = sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ");
+            mm.dbx().execute(sqlx_query).await.map_err(Error::from)?;
+            let last_change_id = ChangeLogBmc::last_id(&mm).await?;
+
+            let value = f(mm.clone()).await?;
+            let changes = ChangeLogBmc::list_after(&mm, last_change_id).await?;
+
+            Ok((value, changes))
+        }
+        .await;
+
+        // The error of `f` (or of the change set query) prevails over the rollback one.
+        let rollback_res = mm.dbx().rollback_txn().await.map_err(Error::from);
+        let value = res?;
+        rollback_res?;
+
+        Ok(value)
+    }
+}
+
+// Storage deletes
+impl ModelManager {
+    fn storage_deletes_guard(&self) -> std::sync::MutexGuard<'_, Vec<String>> {
+        // A poisoned list is still a valid list of keys.
+        self.storage_deletes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Delete the storage contents of the committed transaction.
+    /// (The transaction is committed, so a failed delete only leaves an orphan content.)
+    async fn run_storage_deletes(&self) {
+        let storage_keys = std::mem::take(&mut *self.storage_deletes_guard());
+        for storage_key in storage_keys {
+            if let Err(ex) = self.storage.delete(&storage_key).await {
+                warn!("{:<12} - storage delete - {storage_key}: {ex:?}", "MODEL");
+            }
+        }
+    }
+}
+
+// Migrations
+impl ModelManager {
+    /// Apply all pending migrations.
+    /// Returns the applied versions.
+    pub async fn migrate_up(&self) -> Result<Vec<i64>> {
+        Ok(migrator::migrate_up(self.dbx.db()).await?)
+    }
+
+    /// Revert the applied migrations above `target_version` (0 to revert all).
+    /// Returns the reverted versions.
+    pub async fn migrate_down(&self, target_version: i64) -> Result<Vec<i64>> {
+        Ok(migrator::migrate_down(self.dbx.db(), target_version).await?)
+    }
+
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        Ok(migrator::migration_status(self.dbx.db()).await?)
+    }
+}
+
+// region:     --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::ctx::Ctx;
+    use crate::model::project::{ProjectBmc, ProjectFilter, ProjectForCreate};
+    use anyhow::Result;
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_in_txn_nested_rollback_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_names = [
+            "test_in_txn_nested_rollback_ok kept",
+            "test_in_txn_nested_rollback_ok nested",
+            "test_in_txn_nested_rollback_ok failed",
+        ];
+        let create = |mm: ModelManager, name: &str| {
+            let (ctx, project_c) = (
+                ctx.clone(),
+                ProjectForCreate {
+                    name: name.to_string(),
+                },
+            );
+            async move { ProjectBmc::create(&ctx, &mm, project_c).await }
+        };
+
+        // -- Exec
+        let kept_id = mm
+            .in_txn(|mm| async move {
+                let kept_id = create(mm.clone(), fx_names[0]).await?;
+                let nested_res: core::result::Result<(), Error> = mm
+                    .in_txn(|mm| async move {
+                        create(mm, fx_names[1]).await?;
+                        Err(Error::EntityNotFound {
+                            entity: "project",
+                            id: 0,
+                        })
+                    })
+                    .await;
+                assert!(nested_res.is_err());
+                Ok::<_, Error>(kept_id)
+            })
+            .await?;
+        let res: Result<()> = mm
+            .in_txn(|mm| async move {
+                create(mm, fx_names[2]).await?;
+                Err(anyhow::anyhow!("fail after the create"))
+            })
+            .await;
+
+        // -- Check
+        assert!(res.is_err());
+        for (name, expected_count) in fx_names.iter().zip([1, 0, 0]) {
+            let filter = ProjectFilter {
+                name: Some((*name).into()),
+                ..Default::default()
+            };
+            let projects = ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+            assert_eq!(projects.len(), expected_count, "project '{name}'");
+        }
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, kept_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_dry_run_rollback_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_name = "test_dry_run_rollback_ok project";
+
+        // -- Exec
+        let (project_id, changes) = mm
+            .dry_run(|mm| {
+                let ctx = ctx.clone();
+                async move {
+                    let project_c = ProjectForCreate {
+                        name: fx_name.to_string(),
+                    };
+                    ProjectBmc::create(&ctx, &mm, project_c).await
+                }
+            })
+            .await?;
+
+        // -- Check
+        let change = changes
+            .iter()
+            .find(|c| c.entity == "project" && c.entity_id == project_id)
+            .ok_or_else(|| anyhow::anyhow!("no change log for the project"))?;
+        assert_eq!(change.op, "create");
+        assert_eq!(change.diff["name"]["new"], fx_name);
+        let res = ProjectBmc::get(&ctx, &mm, project_id).await;
+        assert!(
+            matches!(res, Err(Error::EntityNotFound { .. })),
+            "the dry run project should not exist"
+        );
+
+        Ok(())
     }
 }
+
+// endregion:  --- Tests
@@ -1,3 +1,8 @@
+use serde::de::{Error as _, Unexpected};
+use serde::Deserializer;
+use serde_json::{Map, Value};
+use serde_with::DeserializeAs;
+use std::marker::PhantomData;
 use time::serde::rfc3339;
 
 pub fn time_to_sea_value(
@@ -5,3 +10,150 @@ pub fn time_to_sea_value(
 ) -> modql::filter::SeaResult<sea_query::Value> {
     Ok(rfc3339::deserialize(json_value)?.into())
 }
+
+/// Field value which can be NULL, for the `Fields` structs to update a column to NULL
+/// (i.e., `Some(NullableValue(None))`, as a `None` field is not updated).
+///
+/// For the tri-state (nullable) `...ForUpdate` fields, deserialize with
+/// `#[serde_as(as = "NullableValue<_>")]` and `#[serde(default)]`, so that an absent property
+/// is `None` (not updated), and a `null` one is `Some(NullableValue(None))` (set to NULL).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullableValue<T>(pub Option<T>);
+
+impl<T> From<NullableValue<T>> for sea_query::Value
+where
+    Option<T>: Into<sea_query::Value>,
+{
+    fn from(value: NullableValue<T>) -> Self {
+        value.0.into()
+    }
+}
+
+impl<T> sea_query::Nullable for NullableValue<T>
+where
+    Option<T>: Into<sea_query::Value>,
+{
+    fn null() -> sea_query::Value {
+        Option::<T>::None.into()
+    }
+}
+
+impl<'de, T, U> DeserializeAs<'de, Option<NullableValue<T>>> for NullableValue<U>
+where
+    U: DeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Option<NullableValue<T>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = <Option<U> as DeserializeAs<'de, Option<T>>>::deserialize_as(deserializer)?;
+        Ok(Some(NullableValue(value)))
+    }
+}
+
+/// For the non nullable `...ForUpdate` fields, deserialize with
+/// `#[serde_as(as = "NotNull<_>")]` and `#[serde(default)]`, so that an absent property
+/// is `None` (not updated), and a `null` one is rejected (rather than silently not updated).
+pub struct NotNull<T>(PhantomData<T>);
+
+impl<'de, T, U> DeserializeAs<'de, Option<T>> for NotNull<U>
+where
+    U: DeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = <Option<U> as DeserializeAs<'de, Option<T>>>::deserialize_as(deserializer)?;
+        match value {
+            Some(value) => Ok(Some(value)),
+            None => Err(D::Error::invalid_type(
+                Unexpected::Unit,
+                &"a non null value",
+            )),
+        }
+    }
+}
+
+/// Applies the JSON merge `patch` (RFC 7396) to the `target` value, for the object values
+/// of the `...ForUpdate` fields (e.g., `TaskForUpdate::custom`):
+/// the patch object members are merged recursively (a `null` member removes the key),
+/// and any other patch value replaces the target.
+pub fn json_merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                json_merge_patch(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_merge_patch_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (
+                json!({"a": "b", "b": "c"}),
+                json!({"a": null}),
+                json!({"b": "c"}),
+            ),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"c": "d"}}),
+                json!({"a": {"b": "c", "c": "d"}}),
+            ),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": null}}),
+                json!({"a": {}}),
+            ),
+            (
+                json!({"a": [{"b": "c"}]}),
+                json!({"a": [1]}),
+                json!({"a": [1]}),
+            ),
+            (
+                json!({"a": "c"}),
+                json!({"a": {"b": {"c": null}}}),
+                json!({"a": {"b": {}}}),
+            ),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+        ];
+
+        for (mut target, patch, expected) in fx_cases {
+            // -- Exec
+            json_merge_patch(&mut target, patch.clone());
+
+            // -- Check
+            assert_eq!(target, expected, "patch {patch}");
+        }
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
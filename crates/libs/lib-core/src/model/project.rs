@@ -1,14 +1,20 @@
 // region:      --- Modules
 
 use crate::ctx::Ctx;
-use crate::generate_common_bmc_fns;
-use crate::model::base::{self, DbBmc};
-use crate::model::modql_utils::time_to_sea_value;
+use crate::model::base::{
+    self, AccessLevel, BatchItemResult, BatchTarget, CommonIden, DbBmc, ListPage, SoftDeleteIden,
+};
+use crate::model::modql_utils::{time_to_sea_value, NotNull};
+use crate::model::project_member::ProjectMemberBmc;
+use crate::model::user::UserBmc;
 use crate::model::ModelManager;
 use crate::model::Result;
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
-use modql::filter::{FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue};
+use modql::filter::{
+    FilterGroups, FilterNode, FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{Condition, Expr, Query, SelectStatement};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
@@ -34,6 +40,14 @@ pub struct Project {
     pub mid: i64,
     #[serde_as(as = "Rfc3339")]
     pub mtime: OffsetDateTime,
+
+    // -- Soft delete
+    #[serde_as(as = "Option<Rfc3339>")]
+    pub deleted_at: Option<OffsetDateTime>,
+    pub deleted_by: Option<i64>,
+
+    // -- Version
+    pub version: i64,
 }
 
 #[derive(Fields, Deserialize)]
@@ -41,23 +55,30 @@ pub struct ProjectForCreate {
     pub name: String,
 }
 
+/// A `null` property is rejected (see `NotNull`).
+#[serde_as]
 #[derive(Fields, Deserialize)]
 pub struct ProjectForUpdate {
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
     pub name: Option<String>,
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
     pub owner_id: Option<i64>,
 }
 
 #[derive(FilterNodes, Default, Deserialize)]
 pub struct ProjectFilter {
-    id: Option<OpValsInt64>,
-    name: Option<OpValsString>,
+    pub id: Option<OpValsInt64>,
+    pub owner_id: Option<OpValsInt64>,
+    pub name: Option<OpValsString>,
 
-    cid: Option<OpValsInt64>,
+    pub cid: Option<OpValsInt64>,
     #[modql(to_sea_value_fn = "time_to_sea_value")]
-    ctime: Option<OpValsValue>,
-    mid: Option<OpValsInt64>,
+    pub ctime: Option<OpValsValue>,
+    pub mid: Option<OpValsInt64>,
     #[modql(to_sea_value_fn = "time_to_sea_value")]
-    mtime: Option<OpValsValue>,
+    pub mtime: Option<OpValsValue>,
 }
 
 // endregion:   --- Project Types
@@ -72,14 +93,293 @@ impl DbBmc for ProjectBmc {
     fn has_owner_id() -> bool {
         true
     }
+
+    fn has_soft_delete() -> bool {
+        true
+    }
+
+    fn has_version() -> bool {
+        true
+    }
+
+    /// The projects owned by the user, or the user is a member of with a role giving the `level`.
+    fn access_cond(ctx: &Ctx, level: AccessLevel) -> Option<Condition> {
+        let member_project_ids = ProjectMemberBmc::project_ids_query(ctx.user_id(), level);
+
+        Some(
+            Condition::any()
+                .add(Expr::col(CommonIden::OwnerId).eq(ctx.user_id()))
+                .add(Expr::col(CommonIden::Id).in_subquery(member_project_ids)),
+        )
+    }
+
+    /// Only the project owners can delete (and restore, purge) the project.
+    fn delete_access_level() -> AccessLevel {
+        AccessLevel::Owner
+    }
+}
+
+impl ProjectBmc {
+    /// The ids of the not deleted projects,
+    /// for the `not_deleted_parent_cond` of the project entities (e.g., `TaskBmc`).
+    pub(crate) fn not_deleted_ids_query() -> SelectStatement {
+        let mut project_ids = Query::select();
+        project_ids
+            .column(CommonIden::Id)
+            .from(Self::table_ref())
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+
+        project_ids
+    }
 }
 
-generate_common_bmc_fns!(
-    Bmc: ProjectBmc,
-    Entity: Project,
-    ForCreate: ProjectForCreate,
-    ForUpdate: ProjectForUpdate,
-    Filter: ProjectFilter,
-);
+// Note: Same as the `generate_common_bmc_fns!` functions, except the updates
+//       which validate the new owner of an ownership transfer (see `ProjectForUpdate::owner_id`).
+impl ProjectBmc {
+    pub async fn create(ctx: &Ctx, mm: &ModelManager, project_c: ProjectForCreate) -> Result<i64> {
+        base::create::<Self, _>(ctx, mm, project_c).await
+    }
+
+    pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Project> {
+        base::get::<Self, _>(ctx, mm, id).await
+    }
+
+    pub async fn list(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<ProjectFilter>>,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Project>> {
+        base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+    }
+
+    pub async fn list_page(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<ProjectFilter>>,
+        list_options: Option<ListOptions>,
+        cursor: Option<String>,
+        with_total: bool,
+    ) -> Result<ListPage<Project>> {
+        base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor, with_total).await
+    }
+
+    pub async fn count(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<ProjectFilter>>,
+    ) -> Result<i64> {
+        base::count::<Self, _>(ctx, mm, filter).await
+    }
+
+    pub async fn update(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        project_u: ProjectForUpdate,
+    ) -> Result<()> {
+        Self::update_with_version(ctx, mm, id, project_u, None).await
+    }
+
+    /// Returns `Error::EntityNotFound` if the new owner does not exist (or is deleted),
+    /// and `Error::ProjectMemberNotFound` if it is not a member of the project.
+    pub async fn update_with_version(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        project_u: ProjectForUpdate,
+        expected_version: Option<i64>,
+    ) -> Result<()> {
+        if let Some(owner_id) = project_u.owner_id {
+            let cond = Condition::all().add(Expr::col(CommonIden::Id).eq(id));
+            Self::ensure_new_owner(ctx, mm, owner_id, cond).await?;
+        }
+
+        base::update_with_version::<Self, _>(ctx, mm, id, project_u, expected_version).await
+    }
+
+    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        base::delete::<Self>(ctx, mm, id).await
+    }
+
+    pub async fn create_many(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        projects_c: Vec<ProjectForCreate>,
+    ) -> Result<Vec<BatchItemResult>> {
+        base::create_many::<Self, _>(ctx, mm, projects_c).await
+    }
+
+    /// Returns `Error::EntityNotFound` if the new owner does not exist (or is deleted),
+    /// and `Error::ProjectMemberNotFound` if it is not a member of a targeted project,
+    /// without updating any project.
+    pub async fn update_many(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        target: BatchTarget<Vec<ProjectFilter>>,
+        project_u: ProjectForUpdate,
+    ) -> Result<Vec<BatchItemResult>> {
+        let target: BatchTarget<FilterGroups> = match target {
+            BatchTarget::Ids(ids) => BatchTarget::Ids(ids),
+            BatchTarget::Filter(filter) => BatchTarget::Filter(filter.into()),
+        };
+
+        if let Some(owner_id) = project_u.owner_id {
+            let cond = Self::target_cond(&target)?;
+            Self::ensure_new_owner(ctx, mm, owner_id, cond).await?;
+        }
+
+        base::update_many::<Self, _, _>(ctx, mm, target, project_u).await
+    }
+
+    pub async fn delete_many(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        target: BatchTarget<Vec<ProjectFilter>>,
+    ) -> Result<Vec<BatchItemResult>> {
+        base::delete_many::<Self, _>(ctx, mm, target).await
+    }
+
+    pub async fn list_deleted(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<ProjectFilter>>,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Project>> {
+        base::list_deleted::<Self, _, _>(ctx, mm, filter, list_options).await
+    }
+
+    pub async fn restore(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        base::restore::<Self>(ctx, mm, id).await
+    }
+
+    pub async fn purge(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        base::purge::<Self>(ctx, mm, id).await
+    }
+}
+
+// region:     --- ProjectBmc Privates
+
+impl ProjectBmc {
+    /// Validate the new owner `owner_id` of the projects matching `cond`
+    /// (only the projects owned by the ctx user can be transferred).
+    async fn ensure_new_owner(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        owner_id: i64,
+        cond: Condition,
+    ) -> Result<()> {
+        let cond = base::scope_cond::<Self>(ctx, AccessLevel::Owner, Some(cond))
+            .unwrap_or_else(Condition::all);
+        UserBmc::ensure_transfer_target(ctx, mm, owner_id, cond).await
+    }
+
+    /// Returns the condition of the `update_many` target
+    /// (the filter groups are cloned, as they are consumed by the update).
+    fn target_cond(target: &BatchTarget<FilterGroups>) -> Result<Condition> {
+        let cond = match target {
+            BatchTarget::Ids(ids) => {
+                Condition::all().add(Expr::col(CommonIden::Id).is_in(ids.clone()))
+            }
+            BatchTarget::Filter(filters) => {
+                let groups: Vec<Vec<FilterNode>> = filters
+                    .groups()
+                    .iter()
+                    .map(|group| group.nodes().clone())
+                    .collect();
+                FilterGroups::from(groups).try_into()?
+            }
+        };
+
+        Ok(cond)
+    }
+}
+
+// endregion:  --- ProjectBmc Privates
 
 // endregion:   --- ProjectBmc
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::project_member::ProjectRole;
+    use crate::model::user::{OwnedProjectsAction, User, UserForCreate};
+    use crate::model::Error;
+    use anyhow::{Context, Result};
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_update_owner_err_not_member_or_deleted() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let mut user_ids = Vec::new();
+        for username in ["owner", "deleted"] {
+            let user_c = UserForCreate {
+                username: format!("test_update_owner_err_not_member_or_deleted-{username}"),
+                pwd_clear: "welcome".to_string(),
+            };
+            user_ids.push(UserBmc::create(&ctx, &mm, user_c).await?);
+        }
+        let (owner_id, deleted_id) = (user_ids[0], user_ids[1]);
+        UserBmc::delete(&ctx, &mm, deleted_id, OwnedProjectsAction::Archive).await?;
+        let owner_ctx = Ctx::new(owner_id)?;
+        let project_id = _dev_utils::seed_project(
+            &owner_ctx,
+            &mm,
+            "test_update_owner_err_not_member_or_deleted project",
+        )
+        .await?;
+        let to_owner = |owner_id| ProjectForUpdate {
+            name: None,
+            owner_id: Some(owner_id),
+        };
+
+        // -- Exec
+        let deleted_res =
+            ProjectBmc::update(&owner_ctx, &mm, project_id, to_owner(deleted_id)).await;
+        let missing_res = ProjectBmc::update(&owner_ctx, &mm, project_id, to_owner(-1)).await;
+        let not_member_res = ProjectBmc::update_many(
+            &owner_ctx,
+            &mm,
+            BatchTarget::Ids(vec![project_id]),
+            to_owner(demo1.id),
+        )
+        .await;
+        ProjectMemberBmc::add(&owner_ctx, &mm, project_id, demo1.id, ProjectRole::Editor).await?;
+        ProjectBmc::update(&owner_ctx, &mm, project_id, to_owner(demo1.id)).await?;
+
+        // -- Check
+        assert!(
+            matches!(deleted_res, Err(Error::EntityNotFound { id, .. }) if id == deleted_id),
+            "should reject a deleted new owner"
+        );
+        assert!(
+            matches!(missing_res, Err(Error::EntityNotFound { id: -1, .. })),
+            "should reject a missing new owner"
+        );
+        assert!(
+            matches!(not_member_res, Err(Error::ProjectMemberNotFound { project_id: id, .. }) if id == project_id),
+            "should reject a new owner not member of the project"
+        );
+        let project = ProjectBmc::get(&ctx, &mm, project_id).await?;
+        assert_eq!(project.owner_id, demo1.id);
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, project_id).await?;
+        for user_id in user_ids {
+            base::purge::<UserBmc>(&ctx, &mm, user_id).await?;
+        }
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
@@ -0,0 +1,402 @@
+//! Project members, the users sharing a project with its owner.
+//!
+//! Design:
+//!
+//! - The project `owner_id` user is always an owner (without a `project_member` row).
+//! - A member has a `ProjectRole`, which gives its access level on the project and its tasks
+//!   (see `ProjectRole::for_access_level` and `ProjectBmc::access_cond`):
+//!     - `viewer` can read.
+//!     - `editor` can also create, update, and delete tasks, and update the project.
+//!     - `owner` can also delete the project, transfer its ownership, and manage its members.
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessLevel, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::project::{Project, ProjectBmc};
+use crate::model::user::{User, UserBmc};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, OpValsInt64, OpValsString, OpValsValue};
+use sea_query::{Condition, Expr, Iden, Query, SelectStatement};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// endregion:   --- Modules
+
+// region:      --- ProjectMember Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectRole {
+    Owner,
+    Editor,
+    Viewer,
+}
+
+impl ProjectRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Editor => "editor",
+            Self::Viewer => "viewer",
+        }
+    }
+
+    /// Returns the roles giving the access `level`.
+    pub fn for_access_level(level: AccessLevel) -> &'static [ProjectRole] {
+        match level {
+            AccessLevel::Read => &[Self::Owner, Self::Editor, Self::Viewer],
+            AccessLevel::Write => &[Self::Owner, Self::Editor],
+            AccessLevel::Owner => &[Self::Owner],
+        }
+    }
+}
+
+impl TryFrom<String> for ProjectRole {
+    type Error = Error;
+
+    fn try_from(role: String) -> Result<Self> {
+        match role.as_str() {
+            "owner" => Ok(Self::Owner),
+            "editor" => Ok(Self::Editor),
+            "viewer" => Ok(Self::Viewer),
+            _ => Err(Error::ProjectRoleUnknown { role }),
+        }
+    }
+}
+
+impl From<ProjectRole> for sea_query::Value {
+    fn from(role: ProjectRole) -> Self {
+        role.as_str().into()
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct ProjectMember {
+    pub id: i64,
+
+    pub project_id: i64,
+    pub user_id: i64,
+    #[sqlx(try_from = "String")]
+    pub role: ProjectRole,
+
+    // -- Timestamps
+    //    (creator and last modified user_id/time)
+    pub cid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub ctime: OffsetDateTime,
+    pub mid: i64,
+    #[serde_as(as = "Rfc3339")]
+    pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields)]
+struct ProjectMemberForCreate {
+    project_id: i64,
+    user_id: i64,
+    role: ProjectRole,
+}
+
+#[derive(Fields)]
+struct ProjectMemberForUpdate {
+    role: ProjectRole,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+pub struct ProjectMemberFilter {
+    pub project_id: Option<OpValsInt64>,
+    pub user_id: Option<OpValsInt64>,
+    pub role: Option<OpValsString>,
+
+    pub cid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub ctime: Option<OpValsValue>,
+    pub mid: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum ProjectMemberIden {
+    ProjectId,
+    UserId,
+    Role,
+}
+
+// endregion:   --- ProjectMember Types
+
+// region:      --- ProjectMemberBmc
+
+pub struct ProjectMemberBmc;
+
+impl DbBmc for ProjectMemberBmc {
+    const TABLE: &'static str = "project_member";
+
+    /// The members of the projects readable by the user, which only the project owners can change.
+    fn access_cond(ctx: &Ctx, level: AccessLevel) -> Option<Condition> {
+        let project_level = match level {
+            AccessLevel::Read => AccessLevel::Read,
+            AccessLevel::Write | AccessLevel::Owner => AccessLevel::Owner,
+        };
+        let mut project_ids = Query::select();
+        project_ids
+            .column(base::CommonIden::Id)
+            .from(ProjectBmc::table_ref())
+            .cond_where(ProjectBmc::access_cond(ctx, project_level)?);
+
+        Some(Condition::all().add(Expr::col(ProjectMemberIden::ProjectId).in_subquery(project_ids)))
+    }
+}
+
+impl ProjectMemberBmc {
+    /// Add (invite) the user to the project with the `role` (project owners only).
+    pub async fn add(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        user_id: i64,
+        role: ProjectRole,
+    ) -> Result<i64> {
+        Self::ensure_project_owner(ctx, mm, project_id).await?;
+        let _user: User = UserBmc::get(ctx, mm, user_id).await?;
+
+        let member_c = ProjectMemberForCreate {
+            project_id,
+            user_id,
+            role,
+        };
+        base::create::<Self, _>(ctx, mm, member_c)
+            .await
+            .map_err(|model_error| {
+                Error::resolve_unique_violation(
+                    model_error,
+                    Some(|table: &str, constraint: &str| {
+                        if table == "project_member" && constraint.contains("project_user") {
+                            Some(Error::ProjectMemberAlreadyExists {
+                                project_id,
+                                user_id,
+                            })
+                        } else {
+                            None // Error::UniqueViolation will be created by resolve_unique_violation
+                        }
+                    }),
+                )
+            })
+    }
+
+    /// Returns the member `user_id` of the project.
+    pub async fn get(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        user_id: i64,
+    ) -> Result<ProjectMember> {
+        let filter = ProjectMemberFilter {
+            project_id: Some(project_id.into()),
+            user_id: Some(user_id.into()),
+            ..Default::default()
+        };
+        base::list::<Self, ProjectMember, _>(ctx, mm, Some(vec![filter]), None)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::ProjectMemberNotFound {
+                project_id,
+                user_id,
+            })
+    }
+
+    /// List the members of the project (readable by all its members).
+    pub async fn list(ctx: &Ctx, mm: &ModelManager, project_id: i64) -> Result<Vec<ProjectMember>> {
+        let _project: Project = ProjectBmc::get(ctx, mm, project_id).await?;
+
+        let filter = ProjectMemberFilter {
+            project_id: Some(project_id.into()),
+            ..Default::default()
+        };
+        base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), None).await
+    }
+
+    /// Change the role of a project member (project owners only).
+    pub async fn update_role(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        user_id: i64,
+        role: ProjectRole,
+    ) -> Result<()> {
+        Self::ensure_project_owner(ctx, mm, project_id).await?;
+        let member = Self::get(ctx, mm, project_id, user_id).await?;
+
+        base::update::<Self, _>(ctx, mm, member.id, ProjectMemberForUpdate { role }).await
+    }
+
+    /// Remove a member from the project (project owners only).
+    pub async fn remove(ctx: &Ctx, mm: &ModelManager, project_id: i64, user_id: i64) -> Result<()> {
+        Self::ensure_project_owner(ctx, mm, project_id).await?;
+        let member = Self::get(ctx, mm, project_id, user_id).await?;
+
+        base::delete::<Self>(ctx, mm, member.id).await
+    }
+
+    /// Remove the user from all the projects it is a member of (e.g., on the user deletion),
+    /// each removal being logged in the change log.
+    ///
+    /// NOTE: No project owner check, the caller must have authorized it.
+    pub(crate) async fn remove_user(ctx: &Ctx, mm: &ModelManager, user_id: i64) -> Result<()> {
+        // Note: As for `UserBmc::delete`, the page is listed until empty.
+        loop {
+            let filter = ProjectMemberFilter {
+                user_id: Some(user_id.into()),
+                ..Default::default()
+            };
+            let members: Vec<ProjectMember> =
+                base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), None).await?;
+            if members.is_empty() {
+                break;
+            }
+
+            for member in members {
+                base::delete::<Self>(ctx, mm, member.id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the query of the ids of the projects the user is a member of,
+    /// with a role giving the access `level`.
+    pub(crate) fn project_ids_query(user_id: i64, level: AccessLevel) -> SelectStatement {
+        let roles = ProjectRole::for_access_level(level)
+            .iter()
+            .map(|role| role.as_str());
+
+        let mut query = Query::select();
+        query
+            .column(ProjectMemberIden::ProjectId)
+            .from(Self::table_ref())
+            .and_where(Expr::col(ProjectMemberIden::UserId).eq(user_id))
+            .and_where(Expr::col(ProjectMemberIden::Role).is_in(roles));
+
+        query
+    }
+}
+
+// endregion:   --- ProjectMemberBmc
+
+// region:      --- ProjectMemberBmc Privates
+
+impl ProjectMemberBmc {
+    async fn ensure_project_owner(ctx: &Ctx, mm: &ModelManager, project_id: i64) -> Result<()> {
+        let _project: Project = ProjectBmc::get(ctx, mm, project_id).await?;
+        base::ensure_access::<ProjectBmc>(ctx, mm, AccessLevel::Owner, project_id).await
+    }
+}
+
+// endregion:   --- ProjectMemberBmc Privates
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::project::ProjectForUpdate;
+    use crate::model::task::{TaskBmc, TaskForUpdate};
+    use anyhow::{Context, Result};
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_member_roles_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_member_roles_ok project").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["title 01"])
+            .await?
+            .remove(0);
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let demo1_ctx = Ctx::new(demo1.id)?;
+        let fx_task_u = || TaskForUpdate {
+            done: Some(true),
+            ..Default::default()
+        };
+
+        // -- Exec & Check (viewer)
+        ProjectMemberBmc::add(&ctx, &mm, fx_project_id, demo1.id, ProjectRole::Viewer).await?;
+        let _task = TaskBmc::get(&demo1_ctx, &mm, fx_task.id).await?;
+        let res = TaskBmc::update(&demo1_ctx, &mm, fx_task.id, fx_task_u()).await;
+        assert!(
+            matches!(res, Err(Error::AccessDenied { entity: "task", .. })),
+            "viewer should not update tasks"
+        );
+
+        // -- Exec & Check (editor)
+        ProjectMemberBmc::update_role(&ctx, &mm, fx_project_id, demo1.id, ProjectRole::Editor)
+            .await?;
+        TaskBmc::update(&demo1_ctx, &mm, fx_task.id, fx_task_u()).await?;
+        let res = ProjectBmc::delete(&demo1_ctx, &mm, fx_project_id).await;
+        assert!(
+            matches!(
+                res,
+                Err(Error::AccessDenied {
+                    entity: "project",
+                    ..
+                })
+            ),
+            "editor should not delete the project"
+        );
+        let project_u = ProjectForUpdate {
+            name: None,
+            owner_id: Some(demo1.id),
+        };
+        let res = ProjectBmc::update(&demo1_ctx, &mm, fx_project_id, project_u).await;
+        assert!(
+            matches!(
+                res,
+                Err(Error::AccessDenied {
+                    entity: "project",
+                    ..
+                })
+            ),
+            "editor should not transfer the project ownership"
+        );
+        let res = ProjectMemberBmc::remove(&demo1_ctx, &mm, fx_project_id, demo1.id).await;
+        assert!(
+            matches!(
+                res,
+                Err(Error::AccessDenied {
+                    entity: "project",
+                    ..
+                })
+            ),
+            "editor should not manage the members"
+        );
+
+        // -- Exec & Check (removed)
+        ProjectMemberBmc::remove(&ctx, &mm, fx_project_id, demo1.id).await?;
+        let res = TaskBmc::get(&demo1_ctx, &mm, fx_task.id).await;
+        assert!(
+            matches!(res, Err(Error::AccessDenied { entity: "task", .. })),
+            "removed member should not read tasks"
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
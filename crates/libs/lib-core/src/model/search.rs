@@ -0,0 +1,430 @@
+//! Full-text search over the tasks, comments, and projects.
+//!
+//! Design:
+//!
+//! - The searched text is in the `search_tsv` generated columns, with their GIN indexes
+//!   (see the `0014-search` migration): the task title and description, the project name,
+//!   and the comment body.
+//! - The search text has the web search syntax (e.g., `login "time out" -mobile`,
+//!   see the Postgres `websearch_to_tsquery`).
+//! - The hits of all the entity types are ranked together (`ts_rank`, with the titles and names
+//!   weighting more than the descriptions and comments), and only the hits of the page
+//!   get their (costly) highlighted snippet (of the html escaped text).
+//! - The hits are scoped by the `Ctx` read access of their entity (see `DbBmc::access_cond`),
+//!   and the deleted entities (with the entities of a deleted project) are excluded.
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessLevel, CommonIden, DbBmc, SoftDeleteIden};
+use crate::model::comment::CommentBmc;
+use crate::model::project::ProjectBmc;
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use sea_query::extension::postgres::PgBinOper;
+use sea_query::{
+    Condition, Expr, Iden, Order, PostgresQueryBuilder, Query, SelectStatement, SimpleExpr,
+    UnionType,
+};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// endregion:   --- Modules
+
+// region:      --- Consts
+
+/// The text search configuration of the `search_tsv` columns (see the `0014-search` migration).
+const SEARCH_CONFIG: &str = "english";
+
+const SEARCH_LIMIT_DEFAULT: i64 = 20;
+const SEARCH_LIMIT_MAX: i64 = 100;
+
+/// The `ts_headline` options of the hit snippets.
+const SNIPPET_OPTIONS: &str =
+    "StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=24, MinWords=8";
+
+/// The html escaped `content` of the hit snippets, so that only the `<mark>` tags are html
+/// (the `ts_headline` parser keeps the escapes as single tokens, so they are never cut).
+const SNIPPET_CONTENT: &str = r#"replace(replace(replace(replace(replace("content", '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;')"#;
+
+// endregion:   --- Consts
+
+// region:      --- Search Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEntity {
+    Task,
+    Comment,
+    Project,
+}
+
+impl SearchEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Task => "task",
+            Self::Comment => "comment",
+            Self::Project => "project",
+        }
+    }
+}
+
+impl TryFrom<String> for SearchEntity {
+    type Error = Error;
+
+    fn try_from(entity: String) -> Result<Self> {
+        match entity.as_str() {
+            "task" => Ok(Self::Task),
+            "comment" => Ok(Self::Comment),
+            "project" => Ok(Self::Project),
+            _ => Err(Error::SearchEntityUnknown { entity }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct SearchHit {
+    #[sqlx(try_from = "String")]
+    pub entity: SearchEntity,
+    pub id: i64,
+    pub project_id: i64,
+    /// The task of the task and comment hits.
+    pub task_id: Option<i64>,
+    /// The task title (of the task and comment hits), or the project name.
+    pub title: String,
+    /// The matching fragments of the text, with the matches between `<mark>` and `</mark>`.
+    ///
+    /// Note: The text is html escaped, so the snippet can be rendered as html.
+    pub snippet: String,
+    /// The higher the more relevant.
+    pub rank: f32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchOptions {
+    /// The searched entity types (all when None).
+    pub entities: Option<Vec<SearchEntity>>,
+    /// Only the hits of this project.
+    pub project_id: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Iden)]
+enum SearchIden {
+    SearchTsv,
+    ProjectId,
+    TaskId,
+}
+
+#[derive(Iden)]
+enum SearchHitIden {
+    #[iden = "hit"]
+    Table,
+    Entity,
+    Id,
+    ProjectId,
+    TaskId,
+    Title,
+    /// The text of the snippet.
+    Content,
+    Snippet,
+    Rank,
+}
+
+// endregion:   --- Search Types
+
+// region:      --- SearchBmc
+
+pub struct SearchBmc;
+
+impl SearchBmc {
+    /// Search the `text` in the entities readable by the ctx user.
+    ///
+    /// order: `rank` (the most relevant first)
+    pub async fn search(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        text: &str,
+        options: Option<SearchOptions>,
+    ) -> Result<Vec<SearchHit>> {
+        let SearchOptions {
+            entities,
+            project_id,
+            limit,
+            offset,
+        } = options.unwrap_or_default();
+
+        let limit = limit.unwrap_or(SEARCH_LIMIT_DEFAULT).max(0);
+        if limit > SEARCH_LIMIT_MAX {
+            return Err(Error::ListLimitOverMax {
+                max: SEARCH_LIMIT_MAX,
+                actual: limit,
+            });
+        }
+        let text = text.trim();
+        if text.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let entities = entities.unwrap_or_else(|| {
+            vec![
+                SearchEntity::Task,
+                SearchEntity::Comment,
+                SearchEntity::Project,
+            ]
+        });
+
+        // -- Build the ranked hits query
+        //    (the page of the union of the entity queries)
+        let mut entity_queries = Vec::new();
+        for entity in [
+            SearchEntity::Task,
+            SearchEntity::Comment,
+            SearchEntity::Project,
+        ] {
+            if entities.contains(&entity) {
+                entity_queries.push(entity_query(ctx, entity, text, project_id));
+            }
+        }
+        let mut entity_queries = entity_queries.into_iter();
+        let Some(mut hits_query) = entity_queries.next() else {
+            return Ok(Vec::new());
+        };
+        for entity_query in entity_queries {
+            hits_query.union(UnionType::All, entity_query);
+        }
+        hits_query
+            .order_by(SearchHitIden::Rank, Order::Desc)
+            .order_by(SearchHitIden::Entity, Order::Asc)
+            .order_by(SearchHitIden::Id, Order::Asc)
+            .limit(limit as u64)
+            .offset(offset.unwrap_or(0).max(0) as u64);
+
+        // -- Build the query
+        //    (with the snippets of the page hits)
+        let mut query = Query::select();
+        query
+            .columns([
+                SearchHitIden::Entity,
+                SearchHitIden::Id,
+                SearchHitIden::ProjectId,
+                SearchHitIden::TaskId,
+                SearchHitIden::Title,
+            ])
+            .expr_as(
+                Expr::cust_with_values(
+                    format!(
+                        r#"ts_headline('{SEARCH_CONFIG}', {SNIPPET_CONTENT}, websearch_to_tsquery('{SEARCH_CONFIG}', $1), '{SNIPPET_OPTIONS}')"#
+                    ),
+                    [text],
+                ),
+                SearchHitIden::Snippet,
+            )
+            .column(SearchHitIden::Rank)
+            .from_subquery(hits_query, SearchHitIden::Table)
+            .order_by(SearchHitIden::Rank, Order::Desc)
+            .order_by(SearchHitIden::Entity, Order::Asc)
+            .order_by(SearchHitIden::Id, Order::Asc);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, SearchHit, _>(&sql, values);
+        let hits = mm.dbx().fetch_all(sqlx_query).await?;
+
+        Ok(hits)
+    }
+}
+
+// endregion:   --- SearchBmc
+
+// region:      --- Search Utils
+
+/// Returns the query of the `entity` hits (with their `SearchHitIden` columns).
+fn entity_query(
+    ctx: &Ctx,
+    entity: SearchEntity,
+    text: &str,
+    project_id: Option<i64>,
+) -> SelectStatement {
+    let mut query = Query::select();
+    query
+        .expr_as(Expr::val(entity.as_str()), SearchHitIden::Entity)
+        .column(CommonIden::Id);
+
+    // -- The entity columns, and its (non deleted) scope
+    let scope = match entity {
+        SearchEntity::Task => {
+            query
+                .column(SearchIden::ProjectId)
+                .expr_as(Expr::col(CommonIden::Id), SearchHitIden::TaskId)
+                .expr_as(Expr::cust(r#""title""#), SearchHitIden::Title)
+                .expr_as(
+                    Expr::cust(r#"concat_ws(' ', "title", "description")"#),
+                    SearchHitIden::Content,
+                )
+                .from(TaskBmc::table_ref())
+                .cond_where(Condition::all().add_option(base::not_deleted_cond::<TaskBmc>()));
+            if let Some(project_id) = project_id {
+                query.and_where(Expr::col(SearchIden::ProjectId).eq(project_id));
+            }
+            base::scope_cond::<TaskBmc>(ctx, AccessLevel::Read, None)
+        }
+        SearchEntity::Comment => {
+            let mut task_ids = TaskBmc::not_deleted_ids_query();
+            if let Some(project_id) = project_id {
+                task_ids.and_where(Expr::col(SearchIden::ProjectId).eq(project_id));
+            }
+            query
+                .expr_as(
+                    Expr::cust(
+                        r#"(SELECT "project_id" FROM "task" WHERE "task"."id" = "comment"."task_id")"#,
+                    ),
+                    SearchHitIden::ProjectId,
+                )
+                .column(SearchIden::TaskId)
+                .expr_as(
+                    Expr::cust(
+                        r#"(SELECT "title" FROM "task" WHERE "task"."id" = "comment"."task_id")"#,
+                    ),
+                    SearchHitIden::Title,
+                )
+                .expr_as(Expr::cust(r#""body""#), SearchHitIden::Content)
+                .from(CommentBmc::table_ref())
+                .and_where(Expr::col(SearchIden::TaskId).in_subquery(task_ids));
+            base::scope_cond::<CommentBmc>(ctx, AccessLevel::Read, None)
+        }
+        SearchEntity::Project => {
+            query
+                .expr_as(Expr::col(CommonIden::Id), SearchHitIden::ProjectId)
+                .expr_as(Expr::cust("NULL::BIGINT"), SearchHitIden::TaskId)
+                .expr_as(Expr::cust(r#""name""#), SearchHitIden::Title)
+                .expr_as(Expr::cust(r#""name""#), SearchHitIden::Content)
+                .from(ProjectBmc::table_ref())
+                .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+            if let Some(project_id) = project_id {
+                query.and_where(Expr::col(CommonIden::Id).eq(project_id));
+            }
+            base::scope_cond::<ProjectBmc>(ctx, AccessLevel::Read, None)
+        }
+    };
+    if let Some(scope) = scope {
+        query.cond_where(scope);
+    }
+
+    // -- The match and its rank
+    query
+        .expr_as(
+            Expr::cust_with_expr(r#"ts_rank("search_tsv", $1)"#, ts_query(text)),
+            SearchHitIden::Rank,
+        )
+        .and_where(Expr::col(SearchIden::SearchTsv).binary(PgBinOper::Matches, ts_query(text)));
+
+    query
+}
+
+fn ts_query(text: &str) -> SimpleExpr {
+    Expr::cust_with_values(
+        format!("websearch_to_tsquery('{SEARCH_CONFIG}', $1)"),
+        [text],
+    )
+}
+
+// endregion:   --- Search Utils
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::comment::CommentForCreate;
+    use crate::model::project_member::{ProjectMemberBmc, ProjectRole};
+    use crate::model::task::TaskForUpdate;
+    use crate::model::user::{User, UserBmc};
+    use crate::model::NullableValue;
+    use anyhow::{Context, Result};
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_search_rank_scope_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_search_rank_scope_ok project").await?;
+        let fx_tasks = _dev_utils::seed_tasks(
+            &ctx,
+            &mm,
+            fx_project_id,
+            &["Fix the zeppelin login", "Update the docs"],
+        )
+        .await?;
+        let (title_task_id, description_task_id) = (fx_tasks[0].id, fx_tasks[1].id);
+        let task_u = TaskForUpdate {
+            description: Some(NullableValue(Some(
+                "Mention the zeppelin login timeouts.".to_string(),
+            ))),
+            ..Default::default()
+        };
+        TaskBmc::update(&ctx, &mm, description_task_id, task_u).await?;
+        let comment_c = CommentForCreate {
+            task_id: title_task_id,
+            parent_id: None,
+            body: "The zeppelin cannot login since <img onerror=x> Monday.".to_string(),
+        };
+        let comment_id = CommentBmc::create(&ctx, &mm, comment_c).await?;
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let demo1_ctx = Ctx::new(demo1.id)?;
+
+        // -- Exec
+        let hits = SearchBmc::search(&ctx, &mm, "zeppelins logins", None).await?;
+        let demo1_hits = SearchBmc::search(&demo1_ctx, &mm, "zeppelin", None).await?;
+
+        // -- Check
+        assert_eq!(hits.len(), 3);
+        assert_eq!(
+            (hits[0].entity, hits[0].id),
+            (SearchEntity::Task, title_task_id),
+            "title match should rank first"
+        );
+        let comment_hit = hits
+            .iter()
+            .find(|h| h.entity == SearchEntity::Comment)
+            .context("Should have the comment hit")?;
+        assert_eq!(comment_hit.id, comment_id);
+        assert_eq!(comment_hit.task_id, Some(title_task_id));
+        assert_eq!(comment_hit.title, "Fix the zeppelin login");
+        assert!(hits.iter().any(|h| h.id == description_task_id));
+        assert!(hits[0].snippet.contains("<mark>zeppelin</mark>"));
+        assert!(
+            comment_hit.snippet.contains("&lt;img onerror=x&gt;"),
+            "snippet text should be html escaped"
+        );
+        assert!(!comment_hit.snippet.contains("<img"));
+        assert!(
+            demo1_hits.is_empty(),
+            "non member should not find the tasks"
+        );
+
+        ProjectMemberBmc::add(&ctx, &mm, fx_project_id, demo1.id, ProjectRole::Viewer).await?;
+        let options = SearchOptions {
+            entities: Some(vec![SearchEntity::Project]),
+            ..Default::default()
+        };
+        let demo1_hits = SearchBmc::search(&demo1_ctx, &mm, "rank scope", Some(options)).await?;
+        assert_eq!(demo1_hits.len(), 1);
+        assert_eq!(demo1_hits[0].id, fx_project_id);
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
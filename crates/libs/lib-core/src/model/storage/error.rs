@@ -0,0 +1,34 @@
+use derive_more::From;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+// region:      --- Error
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize, From)]
+pub enum Error {
+    KeyInvalid {
+        key: String,
+    },
+    ObjectNotFound {
+        key: String,
+    },
+
+    // -- Externals
+    #[from]
+    Io(#[serde_as(as = "DisplayFromStr")] std::io::Error),
+}
+
+// region:     Error Boilerplate
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl std::error::Error for Error {}
+// endregion:  Error Boilerplate
+
+// endregion:   --- Error
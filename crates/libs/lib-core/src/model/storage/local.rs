@@ -0,0 +1,87 @@
+use crate::model::storage::{AttachmentStorage, Error, Result, StorageReader};
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use tokio::fs::{self, File};
+use tokio::io::{self, AsyncRead, AsyncWriteExt};
+
+/// Stores the objects as files in the `root` folder (one file per key).
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Constructor (the `root` folder is created on the first `put`).
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the file path of the `key` object.
+    /// (the keys cannot contain path separators, or `.` to reach a parent folder)
+    fn path_for(&self, key: &str) -> Result<PathBuf> {
+        let valid = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(Error::KeyInvalid {
+                key: key.to_string(),
+            });
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl AttachmentStorage for LocalStorage {
+    async fn put(&self, key: &str, reader: &mut (dyn AsyncRead + Send + Unpin)) -> Result<u64> {
+        let path = self.path_for(key)?;
+        fs::create_dir_all(&self.root).await?;
+
+        // Written to a part file first, so that an interrupted upload never leaves
+        // a partial `key` object.
+        let part_path = path.with_extension("part");
+        let res: io::Result<u64> = async {
+            let mut file = File::create(&part_path).await?;
+            let size = io::copy(reader, &mut file).await?;
+            file.flush().await?;
+            Ok(size)
+        }
+        .await;
+
+        match res {
+            Ok(size) => {
+                fs::rename(&part_path, &path).await?;
+                Ok(size)
+            }
+            Err(ex) => {
+                let _ = fs::remove_file(&part_path).await;
+                Err(ex.into())
+            }
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<StorageReader> {
+        let path = self.path_for(key)?;
+
+        match File::open(&path).await {
+            Ok(file) => Ok(Box::new(file)),
+            Err(ex) if ex.kind() == ErrorKind::NotFound => Err(Error::ObjectNotFound {
+                key: key.to_string(),
+            }),
+            Err(ex) => Err(ex.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let path = self.path_for(key)?;
+
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(ex) if ex.kind() == ErrorKind::NotFound => Ok(()),
+            Err(ex) => Err(ex.into()),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Attachment storage, where the content of the attachment files is stored.
+//!
+//! Design:
+//!
+//! - The storage is a key/content object store behind the `AttachmentStorage` trait,
+//!   so that other backends (e.g., S3) can be plugged in with `ModelManager::with_storage`.
+//! - The default storage is `LocalStorage`, in the `SERVICE_ATTACHMENT_FOLDER` folder.
+//! - The keys are generated by the model layer (see `AttachmentBmc`), and the metadata
+//!   (filename, size, ...) is stored in the db.
+
+// region:      --- Modules
+
+mod error;
+mod local;
+
+pub use self::error::{Error, Result};
+pub use self::local::LocalStorage;
+
+use async_trait::async_trait;
+use tokio::io::AsyncRead;
+
+// endregion:   --- Modules
+
+/// Reader of a stored object content.
+pub type StorageReader = Box<dyn AsyncRead + Send + Unpin>;
+
+#[async_trait]
+pub trait AttachmentStorage: Send + Sync {
+    /// Write the `reader` content as the `key` object (replaced if it exists).
+    /// Returns the content size.
+    async fn put(&self, key: &str, reader: &mut (dyn AsyncRead + Send + Unpin)) -> Result<u64>;
+
+    /// Returns `Error::ObjectNotFound` if there is no `key` object.
+    async fn get(&self, key: &str) -> Result<StorageReader>;
+
+    /// Delete the `key` object (no-op if there is none).
+    async fn delete(&self, key: &str) -> Result<()>;
+}
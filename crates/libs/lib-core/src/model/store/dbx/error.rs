@@ -10,8 +10,10 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Serialize, From)]
 pub enum Error {
     TxnCantCommitNoOpenTxn,
+    TxnCantRollbackNoOpenTxn,
     CannotBeginTxnWithTxnFalse,
     CannotCommitTxnWithTxnFalse,
+    CannotRollbackTxnWithTxnFalse,
 
     // -- Externals
     #[from]
@@ -1,3 +1,14 @@
+//! Db executor, with an optional (nestable) transaction.
+//!
+//! Design:
+//!
+//! - A `Dbx` with txn shares its transaction with its clones (e.g., the cloned `ModelManager`).
+//! - The first `begin_txn` begins the db transaction, and the nested ones create a savepoint.
+//!   Each `begin_txn` must be closed by a `commit_txn` (which releases the savepoint,
+//!   or commits the transaction for the first one) or a `rollback_txn` (which rolls back
+//!   to the savepoint, or rolls back the transaction for the first one).
+//! - Prefer `ModelManager::in_txn`, which closes the transaction level in all cases.
+
 // region:      --- Modules
 
 mod error;
@@ -50,6 +61,11 @@ impl TxnHolder {
         self.counter -= 1;
         self.counter
     }
+
+    /// The savepoint of the nested transaction level (the counter is at least 2).
+    fn savepoint_name(&self) -> String {
+        format!("txn_level_{}", self.counter)
+    }
 }
 
 impl Deref for TxnHolder {
@@ -74,9 +90,14 @@ impl Dbx {
 
         let mut txh_g = self.txn_holder.lock().await;
 
-        // If we already have a tx holder, we increment
+        // If we already have a tx holder, we increment, with a savepoint for this level
         if let Some(txh) = txh_g.as_mut() {
             txh.inc();
+            let sql = format!("SAVEPOINT {}", txh.savepoint_name());
+            if let Err(ex) = sqlx::query(&sql).execute(txh.txn.as_mut()).await {
+                txh.dec();
+                return Err(ex.into());
+            }
         }
         // If not, we create one with a new transaction
         else {
@@ -95,6 +116,14 @@ impl Dbx {
         let mut txh_g = self.txn_holder.lock().await;
 
         if let Some(txh) = txh_g.as_mut() {
+            // If nested, release the savepoint of this level
+            if txh.counter > 1 {
+                let sql = format!("RELEASE SAVEPOINT {}", txh.savepoint_name());
+                txh.dec();
+                sqlx::query(&sql).execute(txh.txn.as_mut()).await?;
+                return Ok(());
+            }
+
             let counter = txh.dec();
             // If 0, then, it should be matching commit for the first begin_txn
             // so we can commit.
@@ -111,10 +140,52 @@ impl Dbx {
         }
     }
 
+    /// Roll back the changes since the matching `begin_txn`
+    /// (to its savepoint when nested, otherwise the whole transaction).
+    pub async fn rollback_txn(&self) -> Result<()> {
+        if !self.with_txn {
+            return Err(Error::CannotRollbackTxnWithTxnFalse);
+        }
+
+        let mut txh_g = self.txn_holder.lock().await;
+
+        match txh_g.as_mut() {
+            // If nested, roll back to the savepoint of this level (and release it)
+            Some(txh) if txh.counter > 1 => {
+                let name = txh.savepoint_name();
+                txh.dec();
+                sqlx::query(&format!("ROLLBACK TO SAVEPOINT {name}"))
+                    .execute(txh.txn.as_mut())
+                    .await?;
+                sqlx::query(&format!("RELEASE SAVEPOINT {name}"))
+                    .execute(txh.txn.as_mut())
+                    .await?;
+                Ok(())
+            }
+            // Otherwise, roll back the transaction
+            Some(_) => {
+                if let Some(mut txh) = txh_g.take() {
+                    txh.txn.as_mut().rollback().await?;
+                }
+                Ok(())
+            }
+            None => Err(Error::TxnCantRollbackNoOpenTxn),
+        }
+    }
+
     pub fn db(&self) -> &Db {
         &self.db_pool
     }
 
+    pub fn with_txn(&self) -> bool {
+        self.with_txn
+    }
+
+    /// Returns true while a `begin_txn` is not closed (at any level).
+    pub async fn has_open_txn(&self) -> bool {
+        self.txn_holder.lock().await.is_some()
+    }
+
     pub async fn fetch_one<'q, O, A>(&self, query: QueryAs<'q, Postgres, O, A>) -> Result<O>
     where
         O: for<'r> FromRow<'r, <Postgres as sqlx::Database>::Row> + Send + Unpin,
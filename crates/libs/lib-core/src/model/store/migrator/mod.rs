@@ -0,0 +1,300 @@
+//! Versioned schema migrations.
+//!
+//! Design:
+//!
+//! - Migrations live in `sql/migrations/` as `{version}-{name}.up.sql` and `{version}-{name}.down.sql`,
+//!   and are embedded in the binary (see `MIGRATIONS` below), so deployed services do not need the sql folder.
+//! - Applied migrations are tracked in the `_migrations` table, with the checksum of their up script.
+//!   A migration already applied whose script has changed since is an error (add a new migration instead).
+//! - A run (up or down) is executed in a single transaction, guarded by a transaction-level advisory lock,
+//!   so that concurrent service instances cannot apply the same migration twice.
+//! - Scripts are split into statements with `split_sql_statements`, which understands quotes, comments,
+//!   and dollar quoting (e.g., `DO $$ ... $$` blocks).
+//!
+//! Note: Since a run is transactional, statements that cannot run in a transaction
+//!       (e.g., `CREATE INDEX CONCURRENTLY`) are not supported.
+
+// region:      --- Modules
+
+mod error;
+mod sql_split;
+
+pub use error::{Error, Result};
+pub use sql_split::split_sql_statements;
+
+use crate::model::store::Db;
+use lib_utils::b64::b64u_encode;
+use lib_utils::time::Rfc3339;
+use serde::Serialize;
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{PgConnection, Postgres, Transaction};
+use tracing::info;
+
+// endregion:   --- Modules
+
+// region:      --- Migrations
+
+/// Embeds the `{file_stem}.up.sql` and `{file_stem}.down.sql` files from `sql/migrations/`.
+macro_rules! migration {
+    ($version:literal, $file_stem:literal) => {
+        Migration {
+            version: $version,
+            name: $file_stem,
+            up: include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../../sql/migrations/",
+                $file_stem,
+                ".up.sql"
+            )),
+            down: include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../../sql/migrations/",
+                $file_stem,
+                ".down.sql"
+            )),
+        }
+    };
+}
+
+/// All the migrations, in version order.
+///
+/// NOTE: Once released, a migration must not be modified (its checksum is verified on each run).
+const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001-create-schema"),
+    migration!(2, "0002-soft-delete"),
+    migration!(3, "0003-user-deletion"),
+    migration!(4, "0004-change-log"),
+    migration!(5, "0005-version"),
+    migration!(6, "0006-project-member"),
+    migration!(7, "0007-task-details"),
+    migration!(8, "0008-labels"),
+    migration!(9, "0009-comment"),
+    migration!(10, "0010-subtask"),
+    migration!(11, "0011-task-dependency"),
+    migration!(12, "0012-attachment"),
+    migration!(13, "0013-custom-field"),
+    migration!(14, "0014-search"),
+    migration!(15, "0015-task-assignee"),
+    migration!(16, "0016-task-position"),
+    migration!(17, "0017-deleted-project-blockers"),
+];
+
+/// Arbitrary key for the `pg_advisory_xact_lock` taken by each migration run.
+const MIGRATION_LOCK_KEY: i64 = 7_210_001;
+
+struct Migration {
+    version: i64,
+    name: &'static str,
+    up: &'static str,
+    down: &'static str,
+}
+
+impl Migration {
+    fn checksum(&self) -> String {
+        b64u_encode(Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+// endregion:   --- Migrations
+
+// region:      --- Types
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: &'static str,
+    #[serde_as(as = "Option<Rfc3339>")]
+    pub applied_on: Option<OffsetDateTime>,
+}
+
+struct AppliedMigration {
+    version: i64,
+    applied_on: OffsetDateTime,
+}
+
+// endregion:   --- Types
+
+// region:      --- Public Functions
+
+/// Apply all the pending migrations, in version order.
+/// Returns the versions applied by this run.
+pub async fn migrate_up(db: &Db) -> Result<Vec<i64>> {
+    let mut txn = begin_locked(db).await?;
+    let applied = applied_migrations(&mut txn).await?;
+
+    let mut versions = Vec::new();
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|m| !applied.iter().any(|a| a.version == m.version))
+    {
+        info!("{:<12} - migrate_up: {}", "MIGRATOR", migration.name);
+
+        exec_script(&mut txn, migration.up).await?;
+        sqlx::query("INSERT INTO _migrations (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(migration.checksum())
+            .execute(&mut *txn)
+            .await?;
+
+        versions.push(migration.version);
+    }
+
+    txn.commit().await?;
+
+    Ok(versions)
+}
+
+/// Revert the applied migrations above `target_version`, in reverse version order.
+/// (`target_version` 0 reverts all migrations)
+/// Returns the versions reverted by this run.
+pub async fn migrate_down(db: &Db, target_version: i64) -> Result<Vec<i64>> {
+    if target_version != 0 && !MIGRATIONS.iter().any(|m| m.version == target_version) {
+        return Err(Error::MigrationTargetUnknown {
+            version: target_version,
+        });
+    }
+
+    let mut txn = begin_locked(db).await?;
+    let applied = applied_migrations(&mut txn).await?;
+
+    let mut versions = Vec::new();
+    for migration in MIGRATIONS.iter().rev().filter(|m| {
+        m.version > target_version && applied.iter().any(|a| a.version == m.version)
+    }) {
+        info!("{:<12} - migrate_down: {}", "MIGRATOR", migration.name);
+
+        exec_script(&mut txn, migration.down).await?;
+        sqlx::query("DELETE FROM _migrations WHERE version = $1")
+            .bind(migration.version)
+            .execute(&mut *txn)
+            .await?;
+
+        versions.push(migration.version);
+    }
+
+    txn.commit().await?;
+
+    Ok(versions)
+}
+
+/// Returns all the known migrations with their eventual applied time.
+pub async fn migration_status(db: &Db) -> Result<Vec<MigrationStatus>> {
+    let mut txn = begin_locked(db).await?;
+    let applied = applied_migrations(&mut txn).await?;
+    txn.commit().await?;
+
+    let status = MIGRATIONS
+        .iter()
+        .map(|m| MigrationStatus {
+            version: m.version,
+            name: m.name,
+            applied_on: applied
+                .iter()
+                .find(|a| a.version == m.version)
+                .map(|a| a.applied_on),
+        })
+        .collect();
+
+    Ok(status)
+}
+
+// endregion:   --- Public Functions
+
+// region:      --- Privates
+
+/// Begin the run transaction, take the migration lock, and make sure the `_migrations` table exists.
+async fn begin_locked(db: &Db) -> Result<Transaction<'static, Postgres>> {
+    let mut txn = db.begin().await?;
+
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *txn)
+        .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS _migrations (
+            version BIGINT PRIMARY KEY,
+            name VARCHAR(256) NOT NULL,
+            checksum VARCHAR(128) NOT NULL,
+            applied_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+        )",
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    Ok(txn)
+}
+
+/// Returns the applied migrations, after verifying that they are known and unchanged.
+async fn applied_migrations(con: &mut PgConnection) -> Result<Vec<AppliedMigration>> {
+    let rows: Vec<(i64, String, OffsetDateTime)> =
+        sqlx::query_as("SELECT version, checksum, applied_on FROM _migrations ORDER BY version")
+            .fetch_all(&mut *con)
+            .await?;
+
+    let mut applied = Vec::new();
+    for (version, checksum, applied_on) in rows {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|m| m.version == version)
+            .ok_or(Error::MigrationAppliedButUnknown { version })?;
+
+        if migration.checksum() != checksum {
+            return Err(Error::MigrationChecksumMismatch {
+                version,
+                name: migration.name.to_string(),
+            });
+        }
+
+        applied.push(AppliedMigration {
+            version,
+            applied_on,
+        });
+    }
+
+    Ok(applied)
+}
+
+async fn exec_script(con: &mut PgConnection, script: &str) -> Result<()> {
+    for sql in split_sql_statements(script) {
+        sqlx::query(sql).execute(&mut *con).await?;
+    }
+
+    Ok(())
+}
+
+// endregion:   --- Privates
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use anyhow::Result;
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_migrate_up_already_applied_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+
+        // -- Exec
+        let versions = migrate_up(mm.dbx().db()).await?;
+        let status = migration_status(mm.dbx().db()).await?;
+
+        // -- Check
+        assert!(versions.is_empty(), "should not re-apply migrations");
+        assert_eq!(status.len(), MIGRATIONS.len());
+        assert!(status.iter().all(|s| s.applied_on.is_some()));
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
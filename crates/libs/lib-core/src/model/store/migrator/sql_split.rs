@@ -0,0 +1,250 @@
+//! A small SQL script splitter for PostgreSQL.
+//!
+//! Splits a script into its individual statements on `;`, ignoring any `;` found in:
+//!
+//! - single quoted strings (`'...'`, with `''` escapes, and `E'...'` with backslash escapes),
+//! - quoted identifiers (`"..."`),
+//! - line comments (`-- ...`) and (nested) block comments (`/* ... */`),
+//! - dollar quoted strings (`$$ ... $$` or `$tag$ ... $tag$`), as used by function bodies and `DO` blocks.
+//!
+//! Statements that only contain whitespace and/or comments are skipped.
+
+/// Split a SQL script into its statements (trimmed, without the trailing `;`).
+pub fn split_sql_statements(content: &str) -> Vec<&str> {
+    let bytes = content.as_bytes();
+    let len = bytes.len();
+
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut has_content = false;
+    let mut i = 0;
+
+    while i < len {
+        match bytes[i] {
+            // -- Line comment
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = find_from(bytes, i + 2, b"\n").map(|p| p + 1).unwrap_or(len);
+            }
+
+            // -- Block comment (Postgres allows nesting)
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_block_comment(bytes, i);
+            }
+
+            // -- Single quoted string (E'...' allows backslash escapes)
+            b'\'' => {
+                let backslash_escapes = i > 0 && matches!(bytes[i - 1], b'E' | b'e');
+                i = skip_quoted(bytes, i, b'\'', backslash_escapes);
+                has_content = true;
+            }
+
+            // -- Quoted identifier
+            b'"' => {
+                i = skip_quoted(bytes, i, b'"', false);
+                has_content = true;
+            }
+
+            // -- Dollar quoted string
+            b'$' => {
+                match dollar_tag(bytes, i) {
+                    Some(tag) => {
+                        i = find_from(bytes, i + tag.len(), tag)
+                            .map(|p| p + tag.len())
+                            .unwrap_or(len);
+                    }
+                    // Not a tag (e.g., `$1` positional parameter).
+                    None => i += 1,
+                }
+                has_content = true;
+            }
+
+            // -- End of statement
+            b';' => {
+                if has_content {
+                    statements.push(content[start..i].trim());
+                }
+                i += 1;
+                start = i;
+                has_content = false;
+            }
+
+            c => {
+                if !c.is_ascii_whitespace() {
+                    has_content = true;
+                }
+                i += 1;
+            }
+        }
+    }
+
+    // -- Last statement (without trailing `;`)
+    if has_content {
+        statements.push(content[start..].trim());
+    }
+
+    statements
+}
+
+// region:      --- Support
+
+/// Returns the position of the first `needle` found at or after `from`.
+fn find_from(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    if from > bytes.len() {
+        return None;
+    }
+    bytes[from..]
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| p + from)
+}
+
+/// Returns the position right after the block comment starting at `start`.
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    let len = bytes.len();
+    let mut depth = 0;
+    let mut i = start;
+
+    while i < len {
+        if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            depth += 1;
+            i += 2;
+        } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    len
+}
+
+/// Returns the position right after the quoted string/identifier starting at `start`.
+/// A doubled quote (e.g., `''`) is an escaped quote.
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, backslash_escapes: bool) -> usize {
+    let len = bytes.len();
+    let mut i = start + 1;
+
+    while i < len {
+        match bytes[i] {
+            b'\\' if backslash_escapes => i += 2,
+            c if c == quote => {
+                if bytes.get(i + 1) == Some(&quote) {
+                    i += 2;
+                } else {
+                    return i + 1;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    len
+}
+
+/// Returns the dollar quote tag (e.g., `$$` or `$body$`) starting at `start`, if any.
+fn dollar_tag(bytes: &[u8], start: usize) -> Option<&[u8]> {
+    // A tag cannot directly follow an identifier character (e.g., `foo$bar`).
+    if start > 0 && is_ident_char(bytes[start - 1]) {
+        return None;
+    }
+
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'$' => return Some(&bytes[start..=i]),
+            c if is_ident_char(c) && !(i == start + 1 && c.is_ascii_digit()) => i += 1,
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+// endregion:   --- Support
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_split_simple_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_sql = "CREATE TABLE a (id BIGINT);\n\nINSERT INTO a (id) VALUES (1);\n-- trailing comment\n";
+
+        // -- Exec
+        let statements = split_sql_statements(fx_sql);
+
+        // -- Check
+        assert_eq!(
+            statements,
+            &["CREATE TABLE a (id BIGINT)", "INSERT INTO a (id) VALUES (1)"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_quotes_and_comments_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_sql = r#"
+            INSERT INTO a (name) VALUES ('semi;colon'), ('it''s;'), (E'back\';slash');
+            SELECT "odd;name" FROM a; -- comment; with semicolon
+            /* block; /* nested; */ still comment; */
+            SELECT 2
+        "#;
+
+        // -- Exec
+        let statements = split_sql_statements(fx_sql);
+
+        // -- Check
+        assert_eq!(statements.len(), 3);
+        assert_eq!(
+            statements[0],
+            r#"INSERT INTO a (name) VALUES ('semi;colon'), ('it''s;'), (E'back\';slash')"#
+        );
+        assert_eq!(statements[1], r#"SELECT "odd;name" FROM a"#);
+        assert!(statements[2].ends_with("SELECT 2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_dollar_quoted_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_sql = r#"
+            DO $$
+            BEGIN
+              PERFORM 1; PERFORM 2;
+            END
+            $$;
+            CREATE FUNCTION f(x int) RETURNS int AS $body$
+              SELECT $1 + 1; -- $$ inside a tagged body
+            $body$ LANGUAGE sql;
+            SELECT 3;
+        "#;
+
+        // -- Exec
+        let statements = split_sql_statements(fx_sql);
+
+        // -- Check
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].starts_with("DO $$") && statements[0].ends_with("$$"));
+        assert!(statements[1].ends_with("$body$ LANGUAGE sql"));
+        assert_eq!(statements[2], "SELECT 3");
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
@@ -1,6 +1,7 @@
 // region:     --- Modules
 
 pub(in crate::model) mod dbx;
+pub(crate) mod migrator;
 
 use crate::core_config;
 use sqlx::postgres::PgPoolOptions;
@@ -1,26 +1,108 @@
 use crate::ctx::Ctx;
-use crate::generate_common_bmc_fns;
-use crate::model::base::{self, DbBmc};
-use crate::model::modql_utils::time_to_sea_value;
+use crate::model::base::{
+    self, AccessLevel, BatchItemResult, BatchTarget, CommonIden, DbBmc, ListPage, SoftDeleteIden,
+};
+use crate::model::custom_field::{
+    custom_to_sea_condition, validate_custom_paths, CustomFieldValidator,
+};
+use crate::model::label::{labels_all_to_sea_condition, labels_any_to_sea_condition};
+use crate::model::modql_utils::{json_merge_patch, time_to_sea_value, NotNull, NullableValue};
+use crate::model::project::ProjectBmc;
+use crate::model::task_position::PositionAppender;
+use crate::model::user::UserBmc;
 use crate::model::ModelManager;
-use crate::model::Result;
-use lib_utils::time::Rfc3339;
-use modql::field::Fields;
-use modql::filter::{FilterNodes, ListOptions, OpValsBool, OpValsInt64, OpValsString, OpValsValue};
+use crate::model::{Error, Result};
+use lib_utils::time::{format_time, now_utc, Rfc3339};
+use modql::field::{Field, Fields, HasFields};
+use modql::filter::{
+    FilterGroups, FilterNode, FilterNodes, ListOptions, OpValString, OpValValue, OpValsBool,
+    OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{
+    CommonTableExpression, Condition, Expr, Iden, IntoIden, LockType, Order, PostgresQueryBuilder,
+    Query, SelectStatement, SimpleExpr, UnionType, WithClause,
+};
+use sea_query_binder::SqlxBinder;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
 
 // region:     Task Types
+
+/// The task state (`done` is kept in sync with it, see the `0007-task-details` migration).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    #[default]
+    Todo,
+    InProgress,
+    Blocked,
+    Done,
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Todo => "todo",
+            Self::InProgress => "in_progress",
+            Self::Blocked => "blocked",
+            Self::Done => "done",
+        }
+    }
+}
+
+impl TryFrom<String> for TaskStatus {
+    type Error = Error;
+
+    fn try_from(status: String) -> Result<Self> {
+        match status.as_str() {
+            "todo" => Ok(Self::Todo),
+            "in_progress" => Ok(Self::InProgress),
+            "blocked" => Ok(Self::Blocked),
+            "done" => Ok(Self::Done),
+            _ => Err(Error::TaskStatusUnknown { status }),
+        }
+    }
+}
+
+impl From<TaskStatus> for sea_query::Value {
+    fn from(status: TaskStatus) -> Self {
+        status.as_str().into()
+    }
+}
+
+impl sea_query::Nullable for TaskStatus {
+    fn null() -> sea_query::Value {
+        sea_query::Value::String(None)
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Fields, FromRow, Serialize)]
 pub struct Task {
     pub id: i64,
     pub project_id: i64,
+    pub parent_task_id: Option<i64>,
+    /// The user who should do the task (with access to the project).
+    pub assignee_id: Option<i64>,
 
     pub title: String,
     pub done: bool,
+    pub description: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub status: TaskStatus,
+    /// 0 (none) and up, the higher the more urgent.
+    pub priority: i16,
+    #[serde_as(as = "Option<Rfc3339>")]
+    pub due_at: Option<OffsetDateTime>,
+    /// Has open blockers (not done), see `TaskDependencyBmc` (computed).
+    pub blocked: bool,
+    /// The order in the (project, status) board column, see `TaskPositionBmc`.
+    pub position: String,
+    /// The custom field values, by field name (see `custom_field` module).
+    pub custom: Value,
 
     // --   Timestamps
     //      (Creator and last modifier)
@@ -30,26 +112,159 @@ pub struct Task {
     pub mid: i64,
     #[serde_as(as = "Rfc3339")]
     pub mtime: OffsetDateTime,
+
+    // --   Soft delete
+    #[serde_as(as = "Option<Rfc3339>")]
+    pub deleted_at: Option<OffsetDateTime>,
+    pub deleted_by: Option<i64>,
+
+    // --   Version
+    pub version: i64,
 }
 
-#[derive(Fields, Deserialize)]
+#[serde_as]
+#[derive(Fields, Default, Deserialize)]
 pub struct TaskForCreate {
     pub project_id: i64,
+    /// The parent task (of the same project).
+    pub parent_task_id: Option<i64>,
+    /// The user who should do the task (with access to the project).
+    pub assignee_id: Option<i64>,
     pub title: String,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub priority: Option<i16>,
+    #[serde_as(as = "Option<Rfc3339>")]
+    pub due_at: Option<OffsetDateTime>,
+    pub custom: Option<Value>,
+}
+
+/// A `TaskForCreate` with its position, at the end of its board column.
+#[derive(Fields)]
+struct TaskForInsert {
+    project_id: i64,
+    parent_task_id: Option<i64>,
+    assignee_id: Option<i64>,
+    title: String,
+    description: Option<String>,
+    status: Option<TaskStatus>,
+    priority: Option<i16>,
+    due_at: Option<OffsetDateTime>,
+    custom: Option<Value>,
+    position: String,
+}
+
+impl TaskForInsert {
+    async fn new(
+        mm: &ModelManager,
+        positions: &mut PositionAppender,
+        task_c: TaskForCreate,
+    ) -> Result<Self> {
+        let position = positions
+            .next(mm, task_c.project_id, task_c.status.unwrap_or_default())
+            .await?;
+
+        Ok(Self {
+            project_id: task_c.project_id,
+            parent_task_id: task_c.parent_task_id,
+            assignee_id: task_c.assignee_id,
+            title: task_c.title,
+            description: task_c.description,
+            status: task_c.status,
+            priority: task_c.priority,
+            due_at: task_c.due_at,
+            custom: task_c.custom,
+            position,
+        })
+    }
 }
 
+/// The nullable columns are tri-state (absent: not updated, `null`: cleared, see `NullableValue`),
+/// and a `null` is rejected for the other columns (see `NotNull`).
+#[serde_as]
 #[derive(Fields, Default, Deserialize)]
 pub struct TaskForUpdate {
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
     pub title: Option<String>,
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
     pub done: Option<bool>,
+    #[serde_as(as = "NullableValue<_>")]
+    #[serde(default)]
+    pub description: Option<NullableValue<String>>,
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
+    pub status: Option<TaskStatus>,
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
+    pub priority: Option<i16>,
+    #[serde_as(as = "NullableValue<Rfc3339>")]
+    #[serde(default)]
+    pub due_at: Option<NullableValue<OffsetDateTime>>,
+    /// A JSON merge patch of the custom field values (a `null` value removes the field,
+    /// see `json_merge_patch`).
+    #[serde_as(as = "NotNull<_>")]
+    #[serde(default)]
+    pub custom: Option<Value>,
+
+    /// Mark the task done even if it is blocked (not a column).
+    #[field(skip)]
+    #[serde(default)]
+    pub force: bool,
+}
+
+impl TaskForUpdate {
+    fn marks_done(&self) -> bool {
+        self.status == Some(TaskStatus::Done) || self.done == Some(true)
+    }
+
+    /// Returns the status of a `current` status task after the update,
+    /// as the `done`/`status` sync trigger (see the `0007-task-details` migration).
+    fn status_after(&self, current: TaskStatus) -> TaskStatus {
+        let status = self.status.unwrap_or(current);
+        if status != current {
+            return status;
+        }
+        match self.done {
+            Some(done) if done != (current == TaskStatus::Done) => {
+                if done {
+                    TaskStatus::Done
+                } else {
+                    TaskStatus::Todo
+                }
+            }
+            _ => current,
+        }
+    }
 }
 
 #[derive(FilterNodes, Deserialize, Default, Debug)]
 pub struct TaskFilter {
-    id: Option<OpValsInt64>,
-    project_id: Option<OpValsInt64>,
-    title: Option<OpValsString>,
-    done: Option<OpValsBool>,
+    pub id: Option<OpValsInt64>,
+    pub project_id: Option<OpValsInt64>,
+    /// `{"$null": true}` for the top-level tasks only.
+    pub parent_task_id: Option<OpValsInt64>,
+    /// `{"$null": true}` for the unassigned tasks only.
+    pub assignee_id: Option<OpValsInt64>,
+    pub title: Option<OpValsString>,
+    pub done: Option<OpValsBool>,
+    pub description: Option<OpValsString>,
+    pub status: Option<OpValsString>,
+    pub priority: Option<OpValsInt64>,
+    #[modql(to_sea_value_fn = "time_to_sea_value")]
+    pub due_at: Option<OpValsValue>,
+    pub blocked: Option<OpValsBool>,
+    /// The tasks with any of the label ids (see `label` module).
+    #[modql(to_sea_condition_fn = "labels_any_to_sea_condition")]
+    pub labels_any: Option<OpValsValue>,
+    /// The tasks with all the label ids.
+    #[modql(to_sea_condition_fn = "labels_all_to_sea_condition")]
+    pub labels_all: Option<OpValsValue>,
+    /// The tasks with the custom values matching a JSON path predicate, or containing
+    /// the values (see `custom_field` module).
+    #[modql(to_sea_condition_fn = "custom_to_sea_condition")]
+    pub custom: Option<OpValsValue>,
 
     pub cid: Option<OpValsInt64>,
     #[modql(to_sea_value_fn = "time_to_sea_value")]
@@ -59,6 +274,77 @@ pub struct TaskFilter {
     pub mtime: Option<OpValsValue>,
 }
 
+/// A task of a `TaskTree`, with its depth (0 for the tree root task).
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct TaskTreeNode {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub task: Task,
+    pub depth: i32,
+}
+
+/// A task with all its (non deleted) descendants (see `TaskBmc::list_tree`).
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTree {
+    /// The root task first, then its descendants (by depth, then id).
+    pub tasks: Vec<TaskTreeNode>,
+    /// Roll-up of the descendants (done ones, and all).
+    pub done_count: i64,
+    pub total_count: i64,
+}
+
+#[derive(Fields)]
+struct TaskForMove {
+    /// `NullableValue(None)` moves the task to the top level.
+    parent_task_id: Option<NullableValue<i64>>,
+}
+
+#[derive(Fields)]
+struct TaskForAssign {
+    /// `NullableValue(None)` unassigns the task.
+    assignee_id: Option<NullableValue<i64>>,
+}
+
+#[derive(Iden)]
+enum TaskIden {
+    #[iden = "task"]
+    Table,
+    ProjectId,
+    ParentTaskId,
+    Blocked,
+    Status,
+    Position,
+    Custom,
+}
+
+/// A task targeted by an update, with the values the update depends on (see `lock_targets`).
+#[derive(FromRow)]
+struct TaskTarget {
+    id: i64,
+    project_id: i64,
+    #[sqlx(try_from = "String")]
+    status: TaskStatus,
+    custom: Value,
+}
+
+/// The `list_tree` recursive CTE.
+#[derive(Iden)]
+enum TaskTreeIden {
+    #[iden = "task_tree"]
+    Table,
+    Id,
+    Depth,
+}
+
+/// The `ensure_not_ancestor` recursive CTE.
+#[derive(Iden)]
+enum TaskAncestorIden {
+    #[iden = "task_ancestor"]
+    Table,
+    Id,
+    ParentTaskId,
+}
+
 // endregion:  Task Types
 
 // region:     TaskBmc
@@ -67,28 +353,682 @@ pub struct TaskBmc;
 
 impl DbBmc for TaskBmc {
     const TABLE: &'static str = "task";
+
+    fn has_soft_delete() -> bool {
+        true
+    }
+
+    fn has_version() -> bool {
+        true
+    }
+
+    /// The tasks of the projects accessible to the user at the same `level`.
+    fn access_cond(ctx: &Ctx, level: AccessLevel) -> Option<Condition> {
+        let mut project_ids = Query::select();
+        project_ids
+            .column(CommonIden::Id)
+            .from(ProjectBmc::table_ref())
+            .cond_where(ProjectBmc::access_cond(ctx, level)?);
+
+        Some(Condition::all().add(Expr::col(TaskIden::ProjectId).in_subquery(project_ids)))
+    }
+
+    /// The tasks are deleted and restored with their project.
+    fn not_deleted_parent_cond() -> Option<Condition> {
+        let project_ids = ProjectBmc::not_deleted_ids_query();
+
+        Some(Condition::all().add(Expr::col(TaskIden::ProjectId).in_subquery(project_ids)))
+    }
+}
+
+// Note: Same as the `generate_common_bmc_fns!` functions, except the updates
+//       which refuse to mark a blocked task done (see `TaskForUpdate::force`).
+impl TaskBmc {
+    /// Returns `Error::TaskAssigneeNoAccess` if the assignee cannot read the project,
+    /// or a `CustomField...` error if the `custom` values are not valid for the project.
+    pub async fn create(ctx: &Ctx, mm: &ModelManager, mut task_c: TaskForCreate) -> Result<i64> {
+        if let Some(assignee_id) = task_c.assignee_id {
+            Self::ensure_assignable(mm, task_c.project_id, assignee_id).await?;
+        }
+        if let Some(custom) = task_c.custom.take() {
+            let mut validator = CustomFieldValidator::default();
+            let custom = validator
+                .validate(ctx, mm, task_c.project_id, custom)
+                .await?;
+            task_c.custom = Some(custom);
+        }
+        let task_i = TaskForInsert::new(mm, &mut PositionAppender::default(), task_c).await?;
+
+        base::create::<Self, _>(ctx, mm, task_i).await
+    }
+
+    pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Task> {
+        base::get::<Self, _>(ctx, mm, id).await
+    }
+
+    pub async fn list(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<TaskFilter>>,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Task>> {
+        validate_custom_paths(mm, filter.as_deref()).await?;
+        base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+    }
+
+    pub async fn list_page(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<TaskFilter>>,
+        list_options: Option<ListOptions>,
+        cursor: Option<String>,
+        with_total: bool,
+    ) -> Result<ListPage<Task>> {
+        validate_custom_paths(mm, filter.as_deref()).await?;
+        base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor, with_total).await
+    }
+
+    pub async fn count(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<TaskFilter>>,
+    ) -> Result<i64> {
+        validate_custom_paths(mm, filter.as_deref()).await?;
+        base::count::<Self, _>(ctx, mm, filter).await
+    }
+
+    pub async fn update(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        task_u: TaskForUpdate,
+    ) -> Result<()> {
+        Self::update_with_version(ctx, mm, id, task_u, None).await
+    }
+
+    /// Returns `Error::TaskBlocked` when marking the task done while it is blocked
+    /// (unless `force`), or a `CustomField...` error if the patched `custom` values
+    /// are not valid.
+    ///
+    /// A status change moves the task to the end of its new board column.
+    pub async fn update_with_version(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        task_u: TaskForUpdate,
+        expected_version: Option<i64>,
+    ) -> Result<()> {
+        let cond = Condition::all().add(Expr::col(CommonIden::Id).eq(id));
+        if task_u.marks_done() && !task_u.force {
+            Self::ensure_not_blocked(ctx, mm, cond.clone()).await?;
+        }
+
+        // In a transaction (the task is locked while its update fields are computed)
+        mm.in_txn(|mm| async move {
+            let fields = Self::update_fields(ctx, &mm, cond, task_u).await?;
+
+            base::update_fields_with_version::<Self>(ctx, &mm, id, fields, expected_version).await
+        })
+        .await
+    }
+
+    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        base::delete::<Self>(ctx, mm, id).await
+    }
+
+    /// Returns `Error::TaskAssigneeNoAccess` if the assignee of an item cannot read its project,
+    /// or a `CustomField...` error if the `custom` values of an item are not valid
+    /// for its project, without creating any task.
+    pub async fn create_many(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        mut tasks_c: Vec<TaskForCreate>,
+    ) -> Result<Vec<BatchItemResult>> {
+        let mut validator = CustomFieldValidator::default();
+        for task_c in tasks_c.iter_mut() {
+            if let Some(assignee_id) = task_c.assignee_id {
+                Self::ensure_assignable(mm, task_c.project_id, assignee_id).await?;
+            }
+            if let Some(custom) = task_c.custom.take() {
+                let custom = validator
+                    .validate(ctx, mm, task_c.project_id, custom)
+                    .await?;
+                task_c.custom = Some(custom);
+            }
+        }
+        let mut positions = PositionAppender::default();
+        let mut tasks_i = Vec::with_capacity(tasks_c.len());
+        for task_c in tasks_c {
+            tasks_i.push(TaskForInsert::new(mm, &mut positions, task_c).await?);
+        }
+
+        base::create_many::<Self, _>(ctx, mm, tasks_i).await
+    }
+
+    /// Returns `Error::TaskBlocked` when marking done a targeted task which is blocked
+    /// (unless `force`), or a `CustomField...` error if the patched `custom` values of
+    /// a targeted task are not valid for its project, without updating any task.
+    ///
+    /// A status change moves the tasks to the end of their new board column (in id order).
+    pub async fn update_many(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        target: BatchTarget<Vec<TaskFilter>>,
+        task_u: TaskForUpdate,
+    ) -> Result<Vec<BatchItemResult>> {
+        if let BatchTarget::Filter(filter) = &target {
+            validate_custom_paths(mm, Some(filter)).await?;
+        }
+        let target: BatchTarget<FilterGroups> = match target {
+            BatchTarget::Ids(ids) => BatchTarget::Ids(ids),
+            BatchTarget::Filter(filter) => BatchTarget::Filter(filter.into()),
+        };
+
+        if task_u.marks_done() && !task_u.force {
+            let cond = Self::target_cond(&target)?;
+            Self::ensure_not_blocked(ctx, mm, cond).await?;
+        }
+        let cond = Self::target_cond(&target)?;
+
+        // In a transaction (the tasks are locked while their update fields are computed)
+        mm.in_txn(|mm| async move {
+            let fields = Self::update_fields(ctx, &mm, cond, task_u).await?;
+
+            base::update_many_fields::<Self, _>(ctx, &mm, target, fields).await
+        })
+        .await
+    }
+
+    pub async fn delete_many(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        target: BatchTarget<Vec<TaskFilter>>,
+    ) -> Result<Vec<BatchItemResult>> {
+        if let BatchTarget::Filter(filter) = &target {
+            validate_custom_paths(mm, Some(filter)).await?;
+        }
+        base::delete_many::<Self, _>(ctx, mm, target).await
+    }
+
+    pub async fn list_deleted(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<TaskFilter>>,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Task>> {
+        validate_custom_paths(mm, filter.as_deref()).await?;
+        base::list_deleted::<Self, _, _>(ctx, mm, filter, list_options).await
+    }
+
+    pub async fn restore(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        base::restore::<Self>(ctx, mm, id).await
+    }
+
+    pub async fn purge(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        base::purge::<Self>(ctx, mm, id).await
+    }
 }
 
-generate_common_bmc_fns!(
-    Bmc: TaskBmc,
-    Entity: Task,
-    ForCreate: TaskForCreate,
-    ForUpdate: TaskForUpdate,
-    Filter: TaskFilter,
-);
+impl TaskBmc {
+    /// List the overdue tasks (past their `due_at`, and not done), optionally of one project.
+    ///
+    /// default order: `due_at` (the most overdue first)
+    pub async fn list_overdue(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: Option<i64>,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Task>> {
+        let filter = TaskFilter {
+            project_id: project_id.map(Into::into),
+            status: Some(OpValString::Not(TaskStatus::Done.as_str().to_string()).into()),
+            due_at: Some(OpValValue::Lt(json!(format_time(now_utc()))).into()),
+            ..Default::default()
+        };
+        let list_options = list_options.unwrap_or_else(|| ListOptions {
+            order_bys: Some("due_at".into()),
+            ..Default::default()
+        });
+
+        base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), Some(list_options)).await
+    }
+
+    /// Returns the task with all its (non deleted) descendants, and their done/total roll-up.
+    pub async fn list_tree(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<TaskTree> {
+        // The descendants are of the task project, so with the same access.
+        let _task: Task = Self::get(ctx, mm, id).await?;
+
+        // -- Build query
+        let mut tree_query = Query::select();
+        tree_query
+            .column(CommonIden::Id)
+            .expr(Expr::val(0))
+            .from(Self::table_ref())
+            .and_where(Expr::col(CommonIden::Id).eq(id));
+        let mut children_query = Query::select();
+        children_query
+            .column((TaskIden::Table, CommonIden::Id))
+            .expr(Expr::col((TaskTreeIden::Table, TaskTreeIden::Depth)).add(1))
+            .from(Self::table_ref())
+            .inner_join(
+                TaskTreeIden::Table,
+                Expr::col((TaskIden::Table, TaskIden::ParentTaskId))
+                    .equals((TaskTreeIden::Table, TaskTreeIden::Id)),
+            )
+            .and_where(Expr::col((TaskIden::Table, SoftDeleteIden::DeletedAt)).is_null());
+        tree_query.union(UnionType::All, children_query);
+        let tree_cte = CommonTableExpression::new()
+            .query(tree_query)
+            .columns([TaskTreeIden::Id, TaskTreeIden::Depth])
+            .table_name(TaskTreeIden::Table)
+            .to_owned();
+
+        let mut query = Query::select();
+        query
+            .columns(Task::field_column_refs_with_rel(TaskIden::Table))
+            .column((TaskTreeIden::Table, TaskTreeIden::Depth))
+            .from(Self::table_ref())
+            .inner_join(
+                TaskTreeIden::Table,
+                Expr::col((TaskIden::Table, CommonIden::Id))
+                    .equals((TaskTreeIden::Table, TaskTreeIden::Id)),
+            )
+            .order_by((TaskTreeIden::Table, TaskTreeIden::Depth), Order::Asc)
+            .order_by((TaskIden::Table, CommonIden::Id), Order::Asc);
+        let query = query.with(WithClause::new().recursive(true).cte(tree_cte).to_owned());
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, TaskTreeNode, _>(&sql, values);
+        let tasks = mm.dbx().fetch_all(sqlx_query).await?;
+
+        // -- Roll-up
+        let descendants = tasks.iter().filter(|node| node.depth > 0);
+        let total_count = descendants.clone().count() as i64;
+        let done_count = descendants
+            .filter(|node| node.task.status == TaskStatus::Done)
+            .count() as i64;
+
+        Ok(TaskTree {
+            tasks,
+            done_count,
+            total_count,
+        })
+    }
+
+    /// Move the task under the `parent_task_id` task (of the same project),
+    /// or to the top level when None.
+    pub async fn move_to_parent(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        parent_task_id: Option<i64>,
+    ) -> Result<()> {
+        let task = Self::get(ctx, mm, id).await?;
+
+        if let Some(parent_task_id) = parent_task_id {
+            let parent = Self::get(ctx, mm, parent_task_id).await?;
+            if parent.project_id != task.project_id {
+                return Err(Error::TaskParentProjectMismatch { id, parent_task_id });
+            }
+            Self::ensure_not_ancestor(mm, id, parent_task_id).await?;
+        }
+
+        let task_m = TaskForMove {
+            parent_task_id: Some(NullableValue(parent_task_id)),
+        };
+        base::update::<Self, _>(ctx, mm, id, task_m).await
+    }
+
+    /// Assign the task to the `assignee_id` user, or unassign it when None.
+    /// Returns `Error::TaskAssigneeNoAccess` if the assignee cannot read the task project.
+    pub async fn assign(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        assignee_id: Option<i64>,
+    ) -> Result<()> {
+        let task = Self::get(ctx, mm, id).await?;
+
+        if let Some(assignee_id) = assignee_id {
+            Self::ensure_assignable(mm, task.project_id, assignee_id).await?;
+        }
+
+        let task_a = TaskForAssign {
+            assignee_id: Some(NullableValue(assignee_id)),
+        };
+        base::update::<Self, _>(ctx, mm, id, task_a).await
+    }
+
+    /// List the tasks assigned to the ctx user (the "my work" view),
+    /// matching the optional `filter`.
+    ///
+    /// default order: `due_at` (the most urgent first, then the ones without due date)
+    pub async fn list_mine(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: Option<Vec<TaskFilter>>,
+        list_options: Option<ListOptions>,
+    ) -> Result<Vec<Task>> {
+        let filter: Vec<TaskFilter> = filter
+            .filter(|filter| !filter.is_empty())
+            .unwrap_or_else(|| vec![TaskFilter::default()])
+            .into_iter()
+            .map(|filter| TaskFilter {
+                assignee_id: Some(ctx.user_id().into()),
+                ..filter
+            })
+            .collect();
+        let mut list_options = list_options.unwrap_or_default();
+        if list_options.order_bys.is_none() {
+            list_options.order_bys = Some("due_at".into());
+        }
+
+        validate_custom_paths(mm, Some(&filter)).await?;
+        base::list::<Self, _, _>(ctx, mm, Some(filter), Some(list_options)).await
+    }
+}
 
 // endregion:  TaskBmc
 
+// region:     TaskBmc Privates
+
+impl TaskBmc {
+    /// The ids of the not deleted tasks (of the not deleted projects),
+    /// for the `not_deleted_parent_cond` of the task entities (e.g., `CommentBmc`).
+    pub(crate) fn not_deleted_ids_query() -> SelectStatement {
+        let mut task_ids = Query::select();
+        task_ids
+            .column(CommonIden::Id)
+            .from(Self::table_ref())
+            .cond_where(Condition::all().add_option(base::not_deleted_cond::<Self>()));
+
+        task_ids
+    }
+
+    /// Returns the condition of the `update_many` target
+    /// (the filter groups are cloned, as they are consumed by the update).
+    fn target_cond(target: &BatchTarget<FilterGroups>) -> Result<Condition> {
+        let cond = match target {
+            BatchTarget::Ids(ids) => {
+                Condition::all().add(Expr::col(CommonIden::Id).is_in(ids.clone()))
+            }
+            BatchTarget::Filter(filters) => {
+                let groups: Vec<Vec<FilterNode>> = filters
+                    .groups()
+                    .iter()
+                    .map(|group| group.nodes().clone())
+                    .collect();
+                FilterGroups::from(groups).try_into()?
+            }
+        };
+
+        Ok(cond)
+    }
+
+    /// Returns the (non deleted) tasks matching `cond` and writable by the ctx user,
+    /// locked (`FOR UPDATE`) until the end of the transaction.
+    ///
+    /// NOTE: Must be called in the update transaction (see `ModelManager::in_txn`).
+    async fn lock_targets(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        cond: Condition,
+    ) -> Result<Vec<TaskTarget>> {
+        let cond = Condition::all()
+            .add(cond)
+            .add_option(base::not_deleted_cond::<Self>());
+        let cond = base::scope_cond::<Self>(ctx, AccessLevel::Write, Some(cond));
+
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .columns([
+                CommonIden::Id.into_iden(),
+                TaskIden::ProjectId.into_iden(),
+                TaskIden::Status.into_iden(),
+                TaskIden::Custom.into_iden(),
+            ])
+            .from(Self::table_ref())
+            .cond_where(Condition::all().add_option(cond))
+            .order_by(CommonIden::Id, Order::Asc)
+            .lock(LockType::Update);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, TaskTarget, _>(&sql, values);
+        let tasks = mm.dbx().fetch_all(sqlx_query).await?;
+
+        Ok(tasks)
+    }
+
+    /// Returns the fields of `task_u` for the tasks matching `cond` (see `lock_targets`),
+    /// with the per task `CASE` expressions of:
+    /// - their `custom` values patched with the `task_u.custom` merge patch,
+    /// - their position at the end of their new column, for the tasks changing status
+    ///   (as for the created tasks, see `PositionAppender`).
+    ///
+    /// NOTE: Must be called in the update transaction (see `ModelManager::in_txn`).
+    async fn update_fields(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        cond: Condition,
+        mut task_u: TaskForUpdate,
+    ) -> Result<Fields> {
+        let custom_patch = task_u.custom.take();
+        if custom_patch.is_none() && task_u.status.is_none() && task_u.done.is_none() {
+            return Ok(task_u.not_none_fields());
+        }
+        let tasks = Self::lock_targets(ctx, mm, cond).await?;
+
+        // -- Patch the custom values of each task
+        let custom = match custom_patch {
+            Some(custom_patch) => {
+                let customs = Self::patch_customs(ctx, mm, &tasks, custom_patch).await?;
+                Self::case_by_id(customs, TaskIden::Custom)
+            }
+            None => None,
+        };
+
+        // -- Append the tasks changing status to their new column
+        let mut appender = PositionAppender::default();
+        let mut positions = Vec::new();
+        for task in tasks.iter() {
+            let status = task_u.status_after(task.status);
+            if status != task.status {
+                let position = appender.next(mm, task.project_id, status).await?;
+                positions.push((task.id, position));
+            }
+        }
+        let position = Self::case_by_id(positions, TaskIden::Position);
+
+        let mut fields = task_u.not_none_fields();
+        if let Some(custom) = custom {
+            fields.push(Field::new(TaskIden::Custom.into_iden(), custom));
+        }
+        if let Some(position) = position {
+            fields.push(Field::new(TaskIden::Position.into_iden(), position));
+        }
+
+        Ok(fields)
+    }
+
+    /// Returns the `custom` values of the `tasks` patched with `custom_patch`,
+    /// or the first invalid value error.
+    async fn patch_customs(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        tasks: &[TaskTarget],
+        custom_patch: Value,
+    ) -> Result<Vec<(i64, Value)>> {
+        let mut validator = CustomFieldValidator::default();
+        let mut customs = Vec::new();
+        for task in tasks {
+            let mut custom = task.custom.clone();
+            json_merge_patch(&mut custom, custom_patch.clone());
+            let custom = validator.validate(ctx, mm, task.project_id, custom).await?;
+            customs.push((task.id, custom));
+        }
+
+        Ok(customs)
+    }
+
+    /// Returns the `CASE` expression giving its value to each task id
+    /// (the other rows keep their `column` value), or None when no values.
+    fn case_by_id<V>(values: Vec<(i64, V)>, column: TaskIden) -> Option<SimpleExpr>
+    where
+        V: Into<SimpleExpr>,
+    {
+        let mut values = values.into_iter();
+        let (id, value) = values.next()?;
+        let mut case = Expr::case(Expr::col(CommonIden::Id).eq(id), value);
+        for (id, value) in values {
+            case = case.case(Expr::col(CommonIden::Id).eq(id), value);
+        }
+
+        Some(case.finally(Expr::col(column)).into())
+    }
+
+    /// Returns `Error::TaskAssigneeNoAccess` if the `assignee_id` user is not an existing
+    /// (non deleted) user with read access to the project.
+    async fn ensure_assignable(mm: &ModelManager, project_id: i64, assignee_id: i64) -> Result<()> {
+        let no_access_err = Error::TaskAssigneeNoAccess {
+            assignee_id,
+            project_id,
+        };
+        let Ok(assignee_ctx) = Ctx::new(assignee_id) else {
+            return Err(no_access_err);
+        };
+        let Some(access) = ProjectBmc::access_cond(&assignee_ctx, AccessLevel::Read) else {
+            return Ok(());
+        };
+
+        // -- Build query
+        let mut assignee_query = Query::select();
+        assignee_query
+            .column(CommonIden::Id)
+            .from(UserBmc::table_ref())
+            .and_where(Expr::col(CommonIden::Id).eq(assignee_id))
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+        let mut query = Query::select();
+        query
+            .expr(Expr::col(CommonIden::Id).count())
+            .from(ProjectBmc::table_ref())
+            .and_where(Expr::col(CommonIden::Id).eq(project_id))
+            .cond_where(access)
+            .and_where(Expr::exists(assignee_query));
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let (count,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+        if count > 0 {
+            Ok(())
+        } else {
+            Err(no_access_err)
+        }
+    }
+
+    /// Returns `Error::TaskBlocked` if one of the (non deleted) tasks matching `cond`,
+    /// and writable by the ctx user, is blocked.
+    async fn ensure_not_blocked(ctx: &Ctx, mm: &ModelManager, cond: Condition) -> Result<()> {
+        let cond = Condition::all()
+            .add(cond)
+            .add(Expr::col(TaskIden::Blocked).eq(true))
+            .add(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+        let cond = base::scope_cond::<Self>(ctx, AccessLevel::Write, Some(cond));
+
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .column(CommonIden::Id)
+            .from(Self::table_ref())
+            .cond_where(Condition::all().add_option(cond))
+            .order_by(CommonIden::Id, Order::Asc)
+            .limit(1);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        match mm.dbx().fetch_optional(sqlx_query).await? {
+            Some((id,)) => Err(Error::TaskBlocked { id }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `Error::TaskParentCycle` if the task `id` is the `parent_task_id` task,
+    /// or one of its ancestors.
+    async fn ensure_not_ancestor(mm: &ModelManager, id: i64, parent_task_id: i64) -> Result<()> {
+        // -- Build query
+        //    (the ancestors of the parent task, including itself)
+        let mut ancestor_query = Query::select();
+        ancestor_query
+            .columns([
+                CommonIden::Id.into_iden(),
+                TaskIden::ParentTaskId.into_iden(),
+            ])
+            .from(Self::table_ref())
+            .and_where(Expr::col(CommonIden::Id).eq(parent_task_id));
+        let mut parent_query = Query::select();
+        parent_query
+            .columns([
+                (TaskIden::Table, CommonIden::Id.into_iden()),
+                (TaskIden::Table, TaskIden::ParentTaskId.into_iden()),
+            ])
+            .from(Self::table_ref())
+            .inner_join(
+                TaskAncestorIden::Table,
+                Expr::col((TaskIden::Table, CommonIden::Id))
+                    .equals((TaskAncestorIden::Table, TaskAncestorIden::ParentTaskId)),
+            );
+        ancestor_query.union(UnionType::All, parent_query);
+        let ancestor_cte = CommonTableExpression::new()
+            .query(ancestor_query)
+            .columns([TaskAncestorIden::Id, TaskAncestorIden::ParentTaskId])
+            .table_name(TaskAncestorIden::Table)
+            .to_owned();
+
+        let mut query = Query::select();
+        query
+            .expr(Expr::col(TaskAncestorIden::Id).count())
+            .from(TaskAncestorIden::Table)
+            .and_where(Expr::col(TaskAncestorIden::Id).eq(id));
+        let query = query.with(
+            WithClause::new()
+                .recursive(true)
+                .cte(ancestor_cte)
+                .to_owned(),
+        );
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let (count,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+        if count > 0 {
+            Err(Error::TaskParentCycle { id, parent_task_id })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// endregion:  TaskBmc Privates
+
 // region:    --- Tests
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::_dev_utils;
-    use crate::model::project::ProjectBmc;
+    use crate::model::custom_field::{CustomFieldDefBmc, CustomFieldDefForCreate, CustomFieldType};
+    use crate::model::project_member::{ProjectMemberBmc, ProjectRole};
+    use crate::model::task_dependency::TaskDependencyBmc;
+    use crate::model::user::User;
     use crate::model::Error;
-    use anyhow::Result;
+    use anyhow::{Context, Result};
     use lib_utils::time::{format_time, now_utc};
-    use modql::filter::OpValString;
+    use modql::filter::{OpValInt64, OpValString};
     use serde_json::json;
     use serial_test::serial;
     use std::time::Duration;
@@ -108,6 +1048,7 @@ mod tests {
         let task_c = TaskForCreate {
             project_id: fx_project_id,
             title: fx_title.to_string(),
+            ..Default::default()
         };
         let id = TaskBmc::create(&ctx, &mm, task_c).await?;
 
@@ -259,6 +1200,176 @@ mod tests {
         Ok(())
     }
 
+    #[serial]
+    #[tokio::test]
+    async fn test_list_page_cursor_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_titles = &[
+            "test_list_page_cursor_ok 01",
+            "test_list_page_cursor_ok 02",
+            "test_list_page_cursor_ok 04",
+            "test_list_page_cursor_ok 05",
+        ];
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_list_page_cursor_ok project for task")
+                .await?;
+        _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+        let fx_filter = || TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+        let fx_list_options = || -> Result<ListOptions> {
+            Ok(serde_json::from_value(json!({
+                "limit": 2,
+                "order_bys": "!title"
+            }))?)
+        };
+
+        // -- Exec
+        let page_1 = TaskBmc::list_page(
+            &ctx,
+            &mm,
+            Some(vec![fx_filter()]),
+            Some(fx_list_options()?),
+            None,
+            false,
+        )
+        .await?;
+        // Inserted after the page 1 cursor, so should be in page 2.
+        _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["test_list_page_cursor_ok 03"]).await?;
+        let page_2 = TaskBmc::list_page(
+            &ctx,
+            &mm,
+            Some(vec![fx_filter()]),
+            Some(fx_list_options()?),
+            page_1.next_cursor.clone(),
+            true,
+        )
+        .await?;
+        let page_3 = TaskBmc::list_page(
+            &ctx,
+            &mm,
+            Some(vec![fx_filter()]),
+            Some(fx_list_options()?),
+            page_2.next_cursor.clone(),
+            false,
+        )
+        .await?;
+
+        // -- Check
+        let titles =
+            |tasks: &[Task]| -> Vec<String> { tasks.iter().map(|t| t.title.to_string()).collect() };
+        assert_eq!(
+            titles(&page_1.entities),
+            &["test_list_page_cursor_ok 05", "test_list_page_cursor_ok 04"]
+        );
+        assert_eq!(
+            titles(&page_2.entities),
+            &["test_list_page_cursor_ok 03", "test_list_page_cursor_ok 02"]
+        );
+        assert_eq!(titles(&page_3.entities), &["test_list_page_cursor_ok 01"]);
+        assert_eq!(page_1.total, None);
+        assert_eq!(page_2.total, Some(5), "total should not depend on the cursor");
+        assert!(page_3.next_cursor.is_none(), "should be the last page");
+        let mut list_options = fx_list_options()?;
+        list_options.offset = Some(1);
+        let res = TaskBmc::list_page(
+            &ctx,
+            &mm,
+            Some(vec![fx_filter()]),
+            Some(list_options),
+            page_1.next_cursor.clone(),
+            false,
+        )
+        .await;
+        assert!(
+            matches!(res, Err(Error::ListCursorWithOffset)),
+            "should reject the cursor with an offset"
+        );
+
+        // -- Cleanup
+        ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_list_page_cursor_null_key_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id = _dev_utils::seed_project(
+            &ctx,
+            &mm,
+            "test_list_page_cursor_null_key_ok project for task",
+        )
+        .await?;
+        let tasks = _dev_utils::seed_tasks(
+            &ctx,
+            &mm,
+            fx_project_id,
+            &["null 01", "desc a", "null 02", "desc b"],
+        )
+        .await?;
+        for (task, description) in [(&tasks[1], "a"), (&tasks[3], "b")] {
+            let task_u: TaskForUpdate =
+                serde_json::from_value(json!({ "description": description }))?;
+            TaskBmc::update(&ctx, &mm, task.id, task_u).await?;
+        }
+        let fx_filter = || TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+
+        // -- Exec
+        let mut titles_by_order_by = Vec::new();
+        for order_bys in ["description", "!description"] {
+            let mut titles = Vec::new();
+            let mut cursor = None;
+            loop {
+                let list_options = serde_json::from_value(json!({
+                    "limit": 1,
+                    "order_bys": order_bys
+                }))?;
+                let page = TaskBmc::list_page(
+                    &ctx,
+                    &mm,
+                    Some(vec![fx_filter()]),
+                    Some(list_options),
+                    cursor,
+                    false,
+                )
+                .await?;
+                titles.extend(page.entities.into_iter().map(|task| task.title));
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+            titles_by_order_by.push(titles);
+        }
+
+        // -- Check
+        assert_eq!(
+            titles_by_order_by[0],
+            &["desc a", "desc b", "null 01", "null 02"],
+            "null keys should be last (asc)"
+        );
+        assert_eq!(
+            titles_by_order_by[1],
+            &["null 02", "null 01", "desc b", "desc a"],
+            "null keys should be first (desc)"
+        );
+
+        // -- Cleanup
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
     #[serial]
     #[tokio::test]
     async fn test_update_ok() -> Result<()> {
@@ -297,18 +1408,474 @@ mod tests {
 
     #[serial]
     #[tokio::test]
-    async fn test_list_by_ctime_ok() -> Result<()> {
+    async fn test_update_null_clear_ok() -> Result<()> {
         // -- Setup & Fixtures
         let mm = _dev_utils::init_test().await;
         let ctx = Ctx::root_ctx();
         let fx_project_id =
-            _dev_utils::seed_project(&ctx, &mm, "project for tasks test_list_by_ctime_ok").await?;
-        let fx_titles_01 = &[
-            "test_list_by_ctime_ok 01.1",
-            "test_list_by_ctime_ok 01.2",
-            "test_list_by_ctime_ok 01.3",
-        ];
-        _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles_01).await?;
+            _dev_utils::seed_project(&ctx, &mm, "test_update_null_clear_ok project").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["task 01"])
+            .await?
+            .remove(0);
+        let fx_due_at = "2030-01-02T03:04:05Z";
+        let fx_patch_set = json!({"description": "desc 01", "due_at": fx_due_at});
+        let fx_patch_clear = json!({"title": "task 01 - new", "description": null});
+        let fx_patch_clear_due_at = json!({"due_at": null});
+
+        // -- Exec
+        let task_u: TaskForUpdate = serde_json::from_value(fx_patch_set)?;
+        TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+        let task_u: TaskForUpdate = serde_json::from_value(fx_patch_clear)?;
+        TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+
+        // -- Check
+        let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+        assert_eq!(task.title, "task 01 - new");
+        assert_eq!(task.description, None, "description should be cleared");
+        assert_eq!(
+            task.due_at.map(format_time).as_deref(),
+            Some(fx_due_at),
+            "absent due_at should be unchanged"
+        );
+
+        let task_u: TaskForUpdate = serde_json::from_value(fx_patch_clear_due_at)?;
+        TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+        let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+        assert_eq!(task.due_at, None, "due_at should be cleared");
+        assert_eq!(
+            task.title, "task 01 - new",
+            "absent title should be unchanged"
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_err_null_not_nullable() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_patches = [
+            json!({"title": null}),
+            json!({"done": null}),
+            json!({"status": null}),
+            json!({"priority": null}),
+            json!({"custom": null}),
+        ];
+
+        for fx_patch in fx_patches {
+            // -- Exec
+            let res = serde_json::from_value::<TaskForUpdate>(fx_patch.clone());
+
+            // -- Check
+            assert!(res.is_err(), "{fx_patch} should be rejected");
+        }
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_update_custom_merge_patch_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_update_custom_merge_patch_ok project")
+                .await?;
+        for (name, field_type) in [
+            ("customer", CustomFieldType::Text),
+            ("estimate", CustomFieldType::Number),
+        ] {
+            let field_def_c = CustomFieldDefForCreate {
+                project_id: fx_project_id,
+                name: name.to_string(),
+                field_type,
+                options: None,
+            };
+            CustomFieldDefBmc::create(&ctx, &mm, field_def_c).await?;
+        }
+        let fx_task_c = |title: &str| TaskForCreate {
+            project_id: fx_project_id,
+            title: title.to_string(),
+            custom: Some(json!({"customer": "ACME", "estimate": 1})),
+            ..Default::default()
+        };
+        let task_id = TaskBmc::create(&ctx, &mm, fx_task_c("task 01")).await?;
+        let other_task_id = TaskBmc::create(&ctx, &mm, fx_task_c("task 02")).await?;
+
+        // -- Exec
+        let task_u: TaskForUpdate = serde_json::from_value(json!({"custom": {"estimate": 3}}))?;
+        TaskBmc::update(&ctx, &mm, task_id, task_u).await?;
+        let task_u: TaskForUpdate = serde_json::from_value(json!({"custom": {"customer": null}}))?;
+        TaskBmc::update_many(
+            &ctx,
+            &mm,
+            BatchTarget::Ids(vec![task_id, other_task_id]),
+            task_u,
+        )
+        .await?;
+
+        // -- Check
+        let task = TaskBmc::get(&ctx, &mm, task_id).await?;
+        assert_eq!(task.custom, json!({"estimate": 3}));
+        let other_task = TaskBmc::get(&ctx, &mm, other_task_id).await?;
+        assert_eq!(
+            other_task.custom,
+            json!({"estimate": 1}),
+            "the other values should be unchanged"
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_update_status_position_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_update_status_position_ok project").await?;
+        let fx_tasks = _dev_utils::seed_tasks(
+            &ctx,
+            &mm,
+            fx_project_id,
+            &["done 01", "todo 01", "todo 02", "todo 03"],
+        )
+        .await?;
+        let done_task_u = TaskForUpdate {
+            status: Some(TaskStatus::Done),
+            ..Default::default()
+        };
+        TaskBmc::update(&ctx, &mm, fx_tasks[0].id, done_task_u).await?;
+
+        // -- Exec
+        let task_u = TaskForUpdate {
+            done: Some(true),
+            ..Default::default()
+        };
+        TaskBmc::update(&ctx, &mm, fx_tasks[3].id, task_u).await?;
+        let task_u = TaskForUpdate {
+            status: Some(TaskStatus::Done),
+            ..Default::default()
+        };
+        let ids = vec![fx_tasks[1].id, fx_tasks[2].id];
+        TaskBmc::update_many(&ctx, &mm, BatchTarget::Ids(ids), task_u).await?;
+
+        // -- Check
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+        let list_options = serde_json::from_value(json!({"order_bys": "position"}))?;
+        let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), Some(list_options)).await?;
+        let titles: Vec<&str> = tasks.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            &["done 01", "todo 03", "todo 01", "todo 02"],
+            "the moved tasks should be appended to the done column"
+        );
+        assert!(tasks.iter().all(|task| task.status == TaskStatus::Done));
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_update_with_version_err_conflict() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id = _dev_utils::seed_project(
+            &ctx,
+            &mm,
+            "test_update_with_version_err_conflict project for task",
+        )
+        .await?;
+        let fx_task = _dev_utils::seed_tasks(
+            &ctx,
+            &mm,
+            fx_project_id,
+            &["test_update_with_version_err_conflict - task 01"],
+        )
+        .await?
+        .remove(0);
+        let fx_task_u = || TaskForUpdate {
+            done: Some(true),
+            ..Default::default()
+        };
+
+        // -- Exec
+        TaskBmc::update_with_version(&ctx, &mm, fx_task.id, fx_task_u(), Some(fx_task.version))
+            .await?;
+        let res =
+            TaskBmc::update_with_version(&ctx, &mm, fx_task.id, fx_task_u(), Some(fx_task.version))
+                .await;
+
+        // -- Check
+        let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+        assert_eq!(task.version, fx_task.version + 1);
+        assert!(
+            matches!(
+                res,
+                Err(Error::VersionConflict {
+                    entity: "task",
+                    id,
+                    current,
+                }) if id == fx_task.id && current == task.version
+            ),
+            "Should be a VersionConflict, was: {res:?}"
+        );
+
+        // -- Clean
+        ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_create_many_update_many_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_titles = &["test_create_many 01", "test_create_many 02"];
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_create_many_update_many_ok project").await?;
+        let fx_missing_id = 100_000_000;
+
+        // -- Exec
+        let tasks_c = fx_titles
+            .iter()
+            .map(|title| TaskForCreate {
+                project_id: fx_project_id,
+                title: title.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let created = TaskBmc::create_many(&ctx, &mm, tasks_c).await?;
+        let mut ids: Vec<i64> = created.iter().map(|r| r.id).collect();
+        ids.push(fx_missing_id);
+        let task_u = TaskForUpdate {
+            done: Some(true),
+            ..Default::default()
+        };
+        let updated =
+            TaskBmc::update_many(&ctx, &mm, base::BatchTarget::Ids(ids), task_u).await?;
+
+        // -- Check
+        assert!(created
+            .iter()
+            .all(|r| r.status == base::BatchItemStatus::Created));
+        let statuses: Vec<_> = updated.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            &[
+                base::BatchItemStatus::Updated,
+                base::BatchItemStatus::Updated,
+                base::BatchItemStatus::NotFound
+            ]
+        );
+        for (created, fx_title) in created.iter().zip(fx_titles) {
+            let task = TaskBmc::get(&ctx, &mm, created.id).await?;
+            assert_eq!(task.title, *fx_title);
+            assert!(task.done);
+            assert_eq!(task.version, 2);
+        }
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_delete_many_by_filter_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_delete_many_by_filter_ok project").await?;
+        _dev_utils::seed_tasks(
+            &ctx,
+            &mm,
+            fx_project_id,
+            &["test_delete_many 01", "test_delete_many 02", "keep 03"],
+        )
+        .await?;
+        let fx_filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            title: Some(OpValString::StartsWith("test_delete_many".to_string()).into()),
+            ..Default::default()
+        };
+
+        // -- Exec
+        let deleted =
+            TaskBmc::delete_many(&ctx, &mm, base::BatchTarget::Filter(vec![fx_filter])).await?;
+
+        // -- Check
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted
+            .iter()
+            .all(|r| r.status == base::BatchItemStatus::Deleted));
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+        let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "keep 03");
+        let res =
+            TaskBmc::delete_many(&ctx, &mm, base::BatchTarget::Filter(Vec::new())).await;
+        assert!(
+            matches!(res, Err(Error::BatchFilterEmpty { entity: "task" })),
+            "empty filter should not delete all tasks"
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_access_other_user_err() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_access_other_user_err project").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["title 01"])
+            .await?
+            .remove(0);
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .ok_or(anyhow::anyhow!("Should have user 'demo1'"))?;
+        let demo1_ctx = Ctx::new(demo1.id)?;
+
+        // -- Exec
+        let get_res = TaskBmc::get(&demo1_ctx, &mm, fx_task.id).await;
+        let delete_res = ProjectBmc::delete(&demo1_ctx, &mm, fx_project_id).await;
+        let task_c = TaskForCreate {
+            project_id: fx_project_id,
+            title: "title 02".to_string(),
+            ..Default::default()
+        };
+        let create_res = TaskBmc::create(&demo1_ctx, &mm, task_c).await;
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+        let tasks = TaskBmc::list(&demo1_ctx, &mm, Some(vec![filter]), None).await?;
+
+        // -- Check
+        assert!(
+            matches!(get_res, Err(Error::AccessDenied { entity: "task", .. })),
+            "other user task should not be readable"
+        );
+        assert!(
+            matches!(delete_res, Err(Error::AccessDenied { entity: "project", .. })),
+            "other user project should not be deletable"
+        );
+        assert!(
+            matches!(create_res, Err(Error::AccessDenied { entity: "task", .. })),
+            "task should not be created in other user project"
+        );
+        assert!(tasks.is_empty(), "other user tasks should not be listed");
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+        let count = TaskBmc::count(&ctx, &mm, Some(vec![filter])).await?;
+        assert_eq!(count, 1, "denied create should be rolled back");
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_list_overdue_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_list_overdue_ok project").await?;
+        let fx_now = now_utc();
+        let fx_tasks = [
+            ("overdue 01", TaskStatus::InProgress, fx_now - Duration::from_secs(3600)),
+            ("overdue 02", TaskStatus::Todo, fx_now - Duration::from_secs(60)),
+            ("done 03", TaskStatus::Done, fx_now - Duration::from_secs(3600)),
+            ("future 04", TaskStatus::Todo, fx_now + Duration::from_secs(3600)),
+        ];
+        for (title, status, due_at) in fx_tasks {
+            let task_c = TaskForCreate {
+                project_id: fx_project_id,
+                title: title.to_string(),
+                status: Some(status),
+                due_at: Some(due_at),
+                ..Default::default()
+            };
+            TaskBmc::create(&ctx, &mm, task_c).await?;
+        }
+
+        // -- Exec
+        let tasks = TaskBmc::list_overdue(&ctx, &mm, Some(fx_project_id), None).await?;
+
+        // -- Check
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, &["overdue 01", "overdue 02"]);
+        assert!(tasks.iter().all(|t| !t.done));
+
+        // -- Exec & Check (done/status sync)
+        let task_u = TaskForUpdate {
+            status: Some(TaskStatus::Done),
+            ..Default::default()
+        };
+        TaskBmc::update(&ctx, &mm, tasks[0].id, task_u).await?;
+        assert!(TaskBmc::get(&ctx, &mm, tasks[0].id).await?.done);
+        let task_u = TaskForUpdate {
+            done: Some(true),
+            ..Default::default()
+        };
+        TaskBmc::update(&ctx, &mm, tasks[1].id, task_u).await?;
+        let task = TaskBmc::get(&ctx, &mm, tasks[1].id).await?;
+        assert_eq!(task.status, TaskStatus::Done);
+        let tasks = TaskBmc::list_overdue(&ctx, &mm, Some(fx_project_id), None).await?;
+        assert!(tasks.is_empty(), "done tasks should not be overdue");
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_list_by_ctime_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "project for tasks test_list_by_ctime_ok").await?;
+        let fx_titles_01 = &[
+            "test_list_by_ctime_ok 01.1",
+            "test_list_by_ctime_ok 01.2",
+            "test_list_by_ctime_ok 01.3",
+        ];
+        _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles_01).await?;
 
         let time_marker = format_time(now_utc());
         sleep(Duration::from_millis(300)).await;
@@ -358,5 +1925,253 @@ mod tests {
 
         Ok(())
     }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_delete_soft_and_restore_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_delete_soft_and_restore_ok project").await?;
+        let fx_task = _dev_utils::seed_tasks(
+            &ctx,
+            &mm,
+            fx_project_id,
+            &["test_delete_soft_and_restore_ok 01"],
+        )
+        .await?
+        .remove(0);
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+
+        // -- Exec & Check (delete)
+        TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+        let res = TaskBmc::get(&ctx, &mm, fx_task.id).await;
+        assert!(
+            matches!(res, Err(Error::EntityNotFound { entity: "task", .. })),
+            "deleted task should not be found"
+        );
+        let deleted = TaskBmc::list_deleted(&ctx, &mm, Some(vec![filter]), None).await?;
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].deleted_by, Some(ctx.user_id()));
+        assert!(deleted[0].deleted_at.is_some());
+
+        // -- Exec & Check (restore)
+        TaskBmc::restore(&ctx, &mm, fx_task.id).await?;
+        let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+        assert!(task.deleted_at.is_none());
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_project_delete_hides_tasks_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let root_ctx = Ctx::root_ctx();
+        let demo1: User = UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let ctx = Ctx::new(demo1.id)?;
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_project_delete_hides_tasks_ok project")
+                .await?;
+        let fx_other_project_id = _dev_utils::seed_project(
+            &ctx,
+            &mm,
+            "test_project_delete_hides_tasks_ok other project",
+        )
+        .await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["blocker 01"])
+            .await?
+            .remove(0);
+        let fx_other_task = _dev_utils::seed_tasks(&ctx, &mm, fx_other_project_id, &["task 02"])
+            .await?
+            .remove(0);
+        TaskDependencyBmc::add(&ctx, &mm, fx_other_task.id, fx_task.id).await?;
+
+        // -- Exec
+        ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+        // -- Check
+        let res = TaskBmc::get(&ctx, &mm, fx_task.id).await;
+        assert!(
+            matches!(res, Err(Error::EntityNotFound { entity: "task", .. })),
+            "task of a deleted project should not be found"
+        );
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            ..Default::default()
+        };
+        assert!(TaskBmc::list(&ctx, &mm, Some(vec![filter]), None)
+            .await?
+            .is_empty());
+        let res = TaskBmc::get(&root_ctx, &mm, fx_task.id).await;
+        assert!(
+            matches!(res, Err(Error::EntityNotFound { entity: "task", .. })),
+            "task of a deleted project should not be found by root"
+        );
+        let other_task = TaskBmc::get(&ctx, &mm, fx_other_task.id).await?;
+        assert!(!other_task.blocked, "deleted project task should not block");
+        assert!(
+            TaskDependencyBmc::list_blockers(&ctx, &mm, fx_other_task.id)
+                .await?
+                .is_empty()
+        );
+
+        ProjectBmc::restore(&ctx, &mm, fx_project_id).await?;
+        TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+        let other_task = TaskBmc::get(&ctx, &mm, fx_other_task.id).await?;
+        assert!(other_task.blocked, "restored project task should block");
+
+        // -- Clean
+        ProjectBmc::purge(&root_ctx, &mm, fx_project_id).await?;
+        ProjectBmc::purge(&root_ctx, &mm, fx_other_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_purge_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id = _dev_utils::seed_project(&ctx, &mm, "test_purge_ok project").await?;
+        let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["test_purge_ok 01"])
+            .await?
+            .remove(0);
+        TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+        // -- Exec
+        TaskBmc::purge(&ctx, &mm, fx_task.id).await?;
+
+        // -- Check
+        let res = TaskBmc::restore(&ctx, &mm, fx_task.id).await;
+        assert!(
+            matches!(res, Err(Error::EntityNotFound { entity: "task", .. })),
+            "purged task should not be restorable"
+        );
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_list_tree_and_move_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_list_tree_and_move_ok project").await?;
+        let fx_create = |title: &str, parent_task_id: Option<i64>, done: bool| TaskForCreate {
+            project_id: fx_project_id,
+            parent_task_id,
+            title: title.to_string(),
+            status: done.then_some(TaskStatus::Done),
+            ..Default::default()
+        };
+        let epic_id = TaskBmc::create(&ctx, &mm, fx_create("epic", None, false)).await?;
+        let step_01_id =
+            TaskBmc::create(&ctx, &mm, fx_create("step 01", Some(epic_id), true)).await?;
+        let step_02_id =
+            TaskBmc::create(&ctx, &mm, fx_create("step 02", Some(epic_id), false)).await?;
+        let check_id =
+            TaskBmc::create(&ctx, &mm, fx_create("check", Some(step_02_id), true)).await?;
+
+        // -- Exec & Check (tree)
+        let tree = TaskBmc::list_tree(&ctx, &mm, epic_id).await?;
+        let nodes: Vec<(&str, i32)> = tree
+            .tasks
+            .iter()
+            .map(|node| (node.task.title.as_str(), node.depth))
+            .collect();
+        assert_eq!(
+            nodes,
+            &[("epic", 0), ("step 01", 1), ("step 02", 1), ("check", 2)]
+        );
+        assert_eq!((tree.done_count, tree.total_count), (2, 3));
+
+        // -- Exec & Check (move)
+        let res = TaskBmc::move_to_parent(&ctx, &mm, epic_id, Some(check_id)).await;
+        assert!(
+            matches!(res, Err(Error::TaskParentCycle { .. })),
+            "should not move a task under its descendant"
+        );
+        TaskBmc::move_to_parent(&ctx, &mm, step_01_id, Some(step_02_id)).await?;
+        TaskBmc::move_to_parent(&ctx, &mm, step_02_id, None).await?;
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            parent_task_id: Some(OpValInt64::Null(true).into()),
+            ..Default::default()
+        };
+        let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, &["epic", "step 02"]);
+        let tree = TaskBmc::list_tree(&ctx, &mm, step_02_id).await?;
+        assert_eq!((tree.done_count, tree.total_count), (2, 2));
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_assign_and_list_mine_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_assign_and_list_mine_ok project").await?;
+        let fx_tasks =
+            _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["task 01", "task 02"]).await?;
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let demo1_ctx = Ctx::new(demo1.id)?;
+
+        // -- Exec & Check (assign)
+        let res = TaskBmc::assign(&ctx, &mm, fx_tasks[0].id, Some(demo1.id)).await;
+        assert!(
+            matches!(res, Err(Error::TaskAssigneeNoAccess { assignee_id, .. }) if assignee_id == demo1.id),
+            "should not assign a user without access to the project"
+        );
+        ProjectMemberBmc::add(&ctx, &mm, fx_project_id, demo1.id, ProjectRole::Viewer).await?;
+        for task in fx_tasks.iter() {
+            TaskBmc::assign(&ctx, &mm, task.id, Some(demo1.id)).await?;
+        }
+        TaskBmc::assign(&ctx, &mm, fx_tasks[1].id, None).await?;
+
+        // -- Exec & Check (list mine)
+        let tasks = TaskBmc::list_mine(&demo1_ctx, &mm, None, None).await?;
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, &["task 01"]);
+        assert_eq!(tasks[0].assignee_id, Some(demo1.id));
+        let filter = TaskFilter {
+            project_id: Some(fx_project_id.into()),
+            assignee_id: Some(OpValInt64::Null(true).into()),
+            ..Default::default()
+        };
+        let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, &["task 02"]);
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
 }
 // endregion: --- Tests
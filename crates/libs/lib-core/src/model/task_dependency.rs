@@ -0,0 +1,371 @@
+//! Task dependencies, where a blocker task blocks a task until done.
+//!
+//! Design:
+//!
+//! - A dependency is a `task_dependency` row (task, blocker task), changed with write access
+//!   to the (blocked) task.
+//! - The dependencies form a directed acyclic graph (`add` rejects the cycles).
+//! - An `add`/`remove` is recorded in the change log as a change of the task `blocker_task_ids`.
+//! - The `task.blocked` column is computed by the db (see the `0011-task-dependency` migration),
+//!   and `TaskBmc` refuses to mark a blocked task done (unless forced).
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessLevel};
+use crate::model::change_log::{ChangeLogBmc, ChangeOp};
+use crate::model::task::{Task, TaskBmc, TaskFilter};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::now_utc;
+use modql::filter::{ListOptions, OpValInt64};
+use sea_query::{
+    CommonTableExpression, Expr, Iden, OnConflict, Order, PostgresQueryBuilder, Query, UnionType,
+    WithClause,
+};
+use sea_query_binder::SqlxBinder;
+use serde_json::{json, Value};
+
+// endregion:   --- Modules
+
+// region:      --- TaskDependency Types
+
+#[derive(Iden)]
+enum TaskDependencyIden {
+    #[iden = "task_dependency"]
+    Table,
+    TaskId,
+    BlockerTaskId,
+    Cid,
+    Ctime,
+}
+
+/// The `ensure_no_cycle` recursive CTE.
+#[derive(Iden)]
+enum BlockerChainIden {
+    #[iden = "blocker_chain"]
+    Table,
+    Id,
+}
+
+// endregion:   --- TaskDependency Types
+
+// region:      --- TaskDependencyBmc
+
+pub struct TaskDependencyBmc;
+
+impl TaskDependencyBmc {
+    /// Add the `blocker_task_id` task as a blocker of the `task_id` task (no-op when already).
+    /// Returns `Error::TaskDependencyCycle` if the blocker task is (transitively) blocked
+    /// by the task.
+    pub async fn add(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        blocker_task_id: i64,
+    ) -> Result<()> {
+        Self::ensure_task_write(ctx, mm, task_id).await?;
+        let _blocker: Task = TaskBmc::get(ctx, mm, blocker_task_id).await?;
+
+        // In a transaction
+        // (the dependency adds are serialized, so that two adds cannot make a cycle)
+        mm.in_txn(|mm| async move {
+            let sqlx_query = sqlx::query("LOCK TABLE task_dependency IN SHARE ROW EXCLUSIVE MODE");
+            mm.dbx().execute(sqlx_query).await?;
+
+            Self::ensure_no_cycle(&mm, task_id, blocker_task_id).await?;
+
+            let before = Self::task_snapshot(&mm, task_id).await?;
+            let count = Self::insert(ctx, &mm, task_id, blocker_task_id).await?;
+            Self::log_change(ctx, &mm, task_id, count, before).await
+        })
+        .await
+    }
+
+    /// Remove the `blocker_task_id` task from the blockers of the `task_id` task
+    /// (no-op when not a blocker).
+    pub async fn remove(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        blocker_task_id: i64,
+    ) -> Result<()> {
+        Self::ensure_task_write(ctx, mm, task_id).await?;
+
+        let mut query = Query::delete();
+        query
+            .from_table(TaskDependencyIden::Table)
+            .and_where(Expr::col(TaskDependencyIden::TaskId).eq(task_id))
+            .and_where(Expr::col(TaskDependencyIden::BlockerTaskId).eq(blocker_task_id));
+
+        // In a transaction (the change log is written with the mutation)
+        mm.in_txn(|mm| async move {
+            let before = Self::task_snapshot(&mm, task_id).await?;
+
+            let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+            let sqlx_query = sqlx::query_with(&sql, values);
+            let count = mm.dbx().execute(sqlx_query).await?;
+
+            Self::log_change(ctx, &mm, task_id, count, before).await
+        })
+        .await
+    }
+
+    /// List the (direct, and readable) blocker tasks of the task, done ones included.
+    pub async fn list_blockers(ctx: &Ctx, mm: &ModelManager, task_id: i64) -> Result<Vec<Task>> {
+        let _task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+
+        // -- Blocker ids
+        let mut query = Query::select();
+        query
+            .column(TaskDependencyIden::BlockerTaskId)
+            .from(TaskDependencyIden::Table)
+            .and_where(Expr::col(TaskDependencyIden::TaskId).eq(task_id));
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let blocker_ids: Vec<i64> = mm
+            .dbx()
+            .fetch_all(sqlx_query)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect();
+        if blocker_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let filter = TaskFilter {
+            id: Some(OpValInt64::In(blocker_ids).into()),
+            ..Default::default()
+        };
+        let list_options = ListOptions {
+            order_bys: Some("id".into()),
+            ..Default::default()
+        };
+        TaskBmc::list(ctx, mm, Some(vec![filter]), Some(list_options)).await
+    }
+}
+
+// endregion:   --- TaskDependencyBmc
+
+// region:      --- TaskDependencyBmc Privates
+
+impl TaskDependencyBmc {
+    async fn ensure_task_write(ctx: &Ctx, mm: &ModelManager, task_id: i64) -> Result<()> {
+        let _task: Task = TaskBmc::get(ctx, mm, task_id).await?;
+        base::ensure_access::<TaskBmc>(ctx, mm, AccessLevel::Write, task_id).await
+    }
+
+    /// Returns `Error::TaskDependencyCycle` if the `task_id` task is the `blocker_task_id`
+    /// task, or one of its (transitive) blockers.
+    async fn ensure_no_cycle(mm: &ModelManager, task_id: i64, blocker_task_id: i64) -> Result<()> {
+        let cycle_err = Error::TaskDependencyCycle {
+            task_id,
+            blocker_task_id,
+        };
+        if task_id == blocker_task_id {
+            return Err(cycle_err);
+        }
+
+        // -- Build query
+        //    (the transitive blockers of the blocker task)
+        let mut chain_query = Query::select();
+        chain_query
+            .column(TaskDependencyIden::BlockerTaskId)
+            .from(TaskDependencyIden::Table)
+            .and_where(Expr::col(TaskDependencyIden::TaskId).eq(blocker_task_id));
+        let mut next_query = Query::select();
+        next_query
+            .column((TaskDependencyIden::Table, TaskDependencyIden::BlockerTaskId))
+            .from(TaskDependencyIden::Table)
+            .inner_join(
+                BlockerChainIden::Table,
+                Expr::col((TaskDependencyIden::Table, TaskDependencyIden::TaskId))
+                    .equals((BlockerChainIden::Table, BlockerChainIden::Id)),
+            );
+        chain_query.union(UnionType::Distinct, next_query);
+        let chain_cte = CommonTableExpression::new()
+            .query(chain_query)
+            .column(BlockerChainIden::Id)
+            .table_name(BlockerChainIden::Table)
+            .to_owned();
+
+        let mut query = Query::select();
+        query
+            .expr(Expr::col(BlockerChainIden::Id).count())
+            .from(BlockerChainIden::Table)
+            .and_where(Expr::col(BlockerChainIden::Id).eq(task_id));
+        let query = query.with(WithClause::new().recursive(true).cte(chain_cte).to_owned());
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let (count,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+        if count > 0 {
+            Err(cycle_err)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Insert the dependency (no-op when already).
+    /// Returns the count of inserted rows.
+    async fn insert(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        blocker_task_id: i64,
+    ) -> Result<u64> {
+        // -- Build & Exec query
+        let mut query = Query::insert();
+        query
+            .into_table(TaskDependencyIden::Table)
+            .columns([
+                TaskDependencyIden::TaskId,
+                TaskDependencyIden::BlockerTaskId,
+                TaskDependencyIden::Cid,
+                TaskDependencyIden::Ctime,
+            ])
+            .values([
+                task_id.into(),
+                blocker_task_id.into(),
+                ctx.user_id().into(),
+                now_utc().into(),
+            ])?
+            .on_conflict(
+                OnConflict::columns([
+                    TaskDependencyIden::TaskId,
+                    TaskDependencyIden::BlockerTaskId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            );
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_with(&sql, values);
+        let count = mm.dbx().execute(sqlx_query).await?;
+
+        Ok(count)
+    }
+
+    /// Returns the change log snapshot of the task (see `ChangeLogBmc::snapshot`),
+    /// with its `blocker_task_ids`.
+    async fn task_snapshot(mm: &ModelManager, task_id: i64) -> Result<Option<Value>> {
+        let Some(mut snapshot) = ChangeLogBmc::snapshot::<TaskBmc>(mm, task_id).await? else {
+            return Ok(None);
+        };
+
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .column(TaskDependencyIden::BlockerTaskId)
+            .from(TaskDependencyIden::Table)
+            .and_where(Expr::col(TaskDependencyIden::TaskId).eq(task_id))
+            .order_by(TaskDependencyIden::BlockerTaskId, Order::Asc);
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let blocker_task_ids: Vec<i64> = mm
+            .dbx()
+            .fetch_all(sqlx_query)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect();
+        snapshot["blocker_task_ids"] = json!(blocker_task_ids);
+
+        Ok(Some(snapshot))
+    }
+
+    /// Record the dependency change (`count` rows affected) in the change log,
+    /// as a change of the task `blocker_task_ids` (nothing is recorded for a no-op).
+    ///
+    /// NOTE: Must be called in the mutation transaction (see `ModelManager::in_txn`).
+    async fn log_change(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        task_id: i64,
+        count: u64,
+        before: Option<Value>,
+    ) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        let after = Self::task_snapshot(mm, task_id).await?;
+        ChangeLogBmc::log_change::<TaskBmc>(ctx, mm, task_id, ChangeOp::Update, before, after).await
+    }
+}
+
+// endregion:   --- TaskDependencyBmc Privates
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::project::ProjectBmc;
+    use crate::model::task::{TaskForUpdate, TaskStatus};
+    use anyhow::Result;
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_dependencies_blocked_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_dependencies_blocked_ok project").await?;
+        let fx_tasks =
+            _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["build", "test", "release"]).await?;
+        let (build_id, test_id, release_id) = (fx_tasks[0].id, fx_tasks[1].id, fx_tasks[2].id);
+        let fx_done = |force: bool| TaskForUpdate {
+            status: Some(TaskStatus::Done),
+            force,
+            ..Default::default()
+        };
+
+        // -- Exec & Check (dependencies)
+        TaskDependencyBmc::add(&ctx, &mm, test_id, build_id).await?;
+        TaskDependencyBmc::add(&ctx, &mm, release_id, test_id).await?;
+        let res = TaskDependencyBmc::add(&ctx, &mm, build_id, release_id).await;
+        assert!(
+            matches!(res, Err(Error::TaskDependencyCycle { .. })),
+            "should reject the dependency cycle"
+        );
+        let blockers = TaskDependencyBmc::list_blockers(&ctx, &mm, release_id).await?;
+        assert_eq!(blockers.len(), 1);
+        assert_eq!(blockers[0].id, test_id);
+
+        // -- Exec & Check (blocked)
+        assert!(TaskBmc::get(&ctx, &mm, release_id).await?.blocked);
+        let res = TaskBmc::update(&ctx, &mm, test_id, fx_done(false)).await;
+        assert!(
+            matches!(res, Err(Error::TaskBlocked { id }) if id == test_id),
+            "should not mark a blocked task done"
+        );
+        TaskBmc::update(&ctx, &mm, build_id, fx_done(false)).await?;
+        assert!(!TaskBmc::get(&ctx, &mm, test_id).await?.blocked);
+        TaskBmc::update(&ctx, &mm, release_id, fx_done(true)).await?;
+        assert_eq!(
+            TaskBmc::get(&ctx, &mm, release_id).await?.status,
+            TaskStatus::Done
+        );
+
+        // -- Exec & Check (remove)
+        TaskDependencyBmc::remove(&ctx, &mm, release_id, test_id).await?;
+        assert!(TaskDependencyBmc::list_blockers(&ctx, &mm, release_id)
+            .await?
+            .is_empty());
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
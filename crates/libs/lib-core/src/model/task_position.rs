@@ -0,0 +1,684 @@
+//! Task board positions, the order of the tasks in their (project, status) board column.
+//!
+//! Design:
+//!
+//! - The `task.position` is a fractional index key: the base 36 digits (`0-9a-z`) of
+//!   a fraction between 0 and 1 (e.g., `i` is 0.5), without trailing zeros, so that
+//!   the keys order bytewise. There is always a key between two different keys.
+//! - Placing a task (see `TaskPositionBmc::place`) only updates the placed task row,
+//!   with a key between the keys of its new neighbors. New tasks are placed at the end of
+//!   their column, as are the tasks moved to another column by a status update
+//!   (see `TaskBmc::update`).
+//! - The keys get longer as the tasks are placed between the same neighbors. The columns with
+//!   keys longer than `POSITION_KEY_MAX_LEN` are rebalanced (evenly spaced short keys)
+//!   by a background job (see `TaskPositionBmc::rebalance_long_positions`).
+//! - The placements and rebalances of a project are serialized by a lock on the project row.
+//!
+//! Note: Concurrent creates can give the same key to two tasks (they are then ordered by id).
+//!       Placing a task between them rebalances their column first.
+
+// region:      --- Modules
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessLevel, CommonIden, DbBmc, SoftDeleteIden};
+use crate::model::project::ProjectBmc;
+use crate::model::task::{Task, TaskBmc, TaskStatus};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use modql::field::Fields;
+use sea_query::{Expr, Iden, IntoIden, LockType, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// endregion:   --- Modules
+
+// region:      --- Consts
+
+/// The keys longer than this get their column rebalanced
+/// (see the `task_position_long_idx` index of the `0016-task-position` migration).
+const POSITION_KEY_MAX_LEN: i32 = 16;
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE: u8 = 36;
+
+/// The digit of the step between a key and the key placed before or after it,
+/// at the start or end of a column (36^-6).
+const STEP_DIGIT_COUNT: usize = 6;
+
+// endregion:   --- Consts
+
+// region:      --- TaskPosition Types
+
+/// Where to place a task (see `TaskPositionBmc::place`).
+///
+/// With no `after_id` and `before_id`, the task is placed at the end of the column.
+#[derive(Debug, Default, Deserialize)]
+pub struct TaskPlacement {
+    /// The status column (the task status by default).
+    pub status: Option<TaskStatus>,
+    /// Place the task just after this task (of the column).
+    pub after_id: Option<i64>,
+    /// Place the task just before this task (of the column).
+    pub before_id: Option<i64>,
+    /// Move the task to the `done` column even if it is blocked
+    /// (see `TaskForUpdate::force`).
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Fields)]
+struct TaskForPlace {
+    status: TaskStatus,
+    position: String,
+}
+
+#[derive(Iden)]
+enum TaskPositionIden {
+    ProjectId,
+    Status,
+    Position,
+}
+
+// endregion:   --- TaskPosition Types
+
+// region:      --- TaskPositionBmc
+
+pub struct TaskPositionBmc;
+
+impl TaskPositionBmc {
+    /// Place the task in its status column, or in the `placement.status` column.
+    ///
+    /// Returns `Error::TaskPlacementInvalid` if a neighbor task is not a task of the column,
+    /// or `Error::TaskBlocked` when moving a blocked task to the `done` column (unless `force`).
+    pub async fn place(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        placement: TaskPlacement,
+    ) -> Result<()> {
+        let task: Task = TaskBmc::get(ctx, mm, id).await?;
+        base::ensure_access::<TaskBmc>(ctx, mm, AccessLevel::Write, id).await?;
+
+        let status = placement.status.unwrap_or(task.status);
+        if status == TaskStatus::Done && task.status != status && task.blocked && !placement.force {
+            return Err(Error::TaskBlocked { id });
+        }
+
+        // In a transaction
+        mm.in_txn(|mm| async move {
+            Self::lock_project(&mm, task.project_id).await?;
+
+            let column = Column {
+                project_id: task.project_id,
+                status,
+            };
+            let position = Self::position_between_neighbors(&mm, &column, id, &placement).await?;
+
+            let task_p = TaskForPlace { status, position };
+            base::update::<TaskBmc, _>(ctx, &mm, id, task_p).await
+        })
+        .await
+    }
+
+    /// Rebalance the columns with too long keys (evenly spaced short keys, in the same order).
+    /// Returns the number of rebalanced columns.
+    ///
+    /// Note: The rebalance does not change the task versions, nor is it in the change log.
+    pub async fn rebalance_long_positions(mm: &ModelManager) -> Result<usize> {
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .distinct()
+            .columns([TaskPositionIden::ProjectId, TaskPositionIden::Status])
+            .from(TaskBmc::table_ref())
+            // Note: The constant is inlined (not bound), so that the predicate matches
+            //       the `task_position_long_idx` partial index.
+            .and_where(Expr::cust(format!(
+                r#"length("position") > {POSITION_KEY_MAX_LEN}"#
+            )));
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64, String), _>(&sql, values);
+        let columns = mm.dbx().fetch_all(sqlx_query).await?;
+
+        // -- Rebalance each column
+        //    (in its own transaction)
+        for (project_id, status) in columns.iter() {
+            let column = Column {
+                project_id: *project_id,
+                status: TaskStatus::try_from(status.clone())?,
+            };
+            mm.in_txn(|mm| async move {
+                Self::lock_project(&mm, column.project_id).await?;
+                Self::rebalance_column(&mm, &column).await
+            })
+            .await?;
+        }
+
+        Ok(columns.len())
+    }
+}
+
+// endregion:   --- TaskPositionBmc
+
+// region:      --- TaskPositionBmc Privates
+
+/// A board column.
+struct Column {
+    project_id: i64,
+    status: TaskStatus,
+}
+
+impl TaskPositionBmc {
+    /// Lock the project row for the transaction, so that its placements and rebalances
+    /// are serialized.
+    async fn lock_project(mm: &ModelManager, project_id: i64) -> Result<()> {
+        let mut query = Query::select();
+        query
+            .column(CommonIden::Id)
+            .from(ProjectBmc::table_ref())
+            .and_where(Expr::col(CommonIden::Id).eq(project_id))
+            .lock(LockType::NoKeyUpdate);
+
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_with(&sql, values);
+        mm.dbx().execute(sqlx_query).await?;
+
+        Ok(())
+    }
+
+    /// Returns the key between the placement neighbors of the task `id`
+    /// (the column is rebalanced first when they have the same key).
+    async fn position_between_neighbors(
+        mm: &ModelManager,
+        column: &Column,
+        id: i64,
+        placement: &TaskPlacement,
+    ) -> Result<String> {
+        for _ in 0..2 {
+            let (prev, next) = Self::neighbors(mm, column, id, placement).await?;
+            let prev = prev.as_ref().map(|(_, position)| position.as_str());
+            let next = next.as_ref().map(|(_, position)| position.as_str());
+            if let Some(position) = position_between(prev, next) {
+                return Ok(position);
+            }
+            // The `after_id` task is after the `before_id` task.
+            if prev != next {
+                break;
+            }
+            Self::rebalance_column(mm, column).await?;
+        }
+
+        Err(Error::TaskPlacementInvalid {
+            id,
+            neighbor_id: placement.before_id.unwrap_or(id),
+        })
+    }
+
+    /// Returns the `(id, position)` of the tasks just before and after the placement
+    /// (None at the start or end of the column).
+    async fn neighbors(
+        mm: &ModelManager,
+        column: &Column,
+        id: i64,
+        placement: &TaskPlacement,
+    ) -> Result<(Option<(i64, String)>, Option<(i64, String)>)> {
+        let neighbors = match (placement.after_id, placement.before_id) {
+            (Some(after_id), Some(before_id)) => {
+                let prev = Self::column_task(mm, column, id, after_id).await?;
+                let next = Self::column_task(mm, column, id, before_id).await?;
+                (Some(prev), Some(next))
+            }
+            (Some(after_id), None) => {
+                let prev = Self::column_task(mm, column, id, after_id).await?;
+                let next = Self::adjacent_task(mm, column, id, Some(&prev), Order::Asc).await?;
+                (Some(prev), next)
+            }
+            (None, Some(before_id)) => {
+                let next = Self::column_task(mm, column, id, before_id).await?;
+                let prev = Self::adjacent_task(mm, column, id, Some(&next), Order::Desc).await?;
+                (prev, Some(next))
+            }
+            (None, None) => {
+                let prev = Self::adjacent_task(mm, column, id, None, Order::Desc).await?;
+                (prev, None)
+            }
+        };
+
+        Ok(neighbors)
+    }
+
+    /// Returns the `(id, position)` of the `neighbor_id` task, or
+    /// `Error::TaskPlacementInvalid` if it is not another (non deleted) task of the column.
+    async fn column_task(
+        mm: &ModelManager,
+        column: &Column,
+        id: i64,
+        neighbor_id: i64,
+    ) -> Result<(i64, String)> {
+        let mut query = Query::select();
+        query
+            .columns([
+                CommonIden::Id.into_iden(),
+                TaskPositionIden::Position.into_iden(),
+            ])
+            .from(TaskBmc::table_ref())
+            .and_where(Expr::col(CommonIden::Id).eq(neighbor_id))
+            .and_where(Expr::col(CommonIden::Id).ne(id))
+            .and_where(Expr::col(TaskPositionIden::ProjectId).eq(column.project_id))
+            .and_where(Expr::col(TaskPositionIden::Status).eq(column.status))
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64, String), _>(&sql, values);
+        mm.dbx()
+            .fetch_optional(sqlx_query)
+            .await?
+            .ok_or(Error::TaskPlacementInvalid { id, neighbor_id })
+    }
+
+    /// Returns the `(id, position)` of the (non deleted) task of the column just after
+    /// (`Order::Asc`) or before (`Order::Desc`) the `from` task, or the first/last task
+    /// of the column when no `from`. The task `id` is ignored.
+    async fn adjacent_task(
+        mm: &ModelManager,
+        column: &Column,
+        id: i64,
+        from: Option<&(i64, String)>,
+        order: Order,
+    ) -> Result<Option<(i64, String)>> {
+        let mut query = Query::select();
+        query
+            .columns([
+                CommonIden::Id.into_iden(),
+                TaskPositionIden::Position.into_iden(),
+            ])
+            .from(TaskBmc::table_ref())
+            .and_where(Expr::col(CommonIden::Id).ne(id))
+            .and_where(Expr::col(TaskPositionIden::ProjectId).eq(column.project_id))
+            .and_where(Expr::col(TaskPositionIden::Status).eq(column.status))
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null())
+            .order_by(TaskPositionIden::Position, order.clone())
+            .order_by(CommonIden::Id, order.clone())
+            .limit(1);
+        if let Some((from_id, from_position)) = from {
+            let key = Expr::tuple([
+                Expr::col(TaskPositionIden::Position).into(),
+                Expr::col(CommonIden::Id).into(),
+            ]);
+            let from_key = Expr::tuple([from_position.into(), (*from_id).into()]);
+            match order {
+                Order::Desc => query.and_where(key.lt(from_key)),
+                _ => query.and_where(key.gt(from_key)),
+            };
+        }
+
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64, String), _>(&sql, values);
+        let task = mm.dbx().fetch_optional(sqlx_query).await?;
+
+        Ok(task)
+    }
+
+    /// Give evenly spaced keys to all the tasks of the column (deleted ones included),
+    /// in the same order.
+    async fn rebalance_column(mm: &ModelManager, column: &Column) -> Result<()> {
+        // -- Column task ids
+        let mut query = Query::select();
+        query
+            .column(CommonIden::Id)
+            .from(TaskBmc::table_ref())
+            .and_where(Expr::col(TaskPositionIden::ProjectId).eq(column.project_id))
+            .and_where(Expr::col(TaskPositionIden::Status).eq(column.status))
+            .order_by(TaskPositionIden::Position, Order::Asc)
+            .order_by(CommonIden::Id, Order::Asc);
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        let ids: Vec<i64> = mm
+            .dbx()
+            .fetch_all(sqlx_query)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect();
+
+        // -- Update the keys
+        let positions = spread_positions(ids.len());
+        let sqlx_query = sqlx::query(
+            r#"UPDATE "task" SET "position" = "rebalanced"."position"
+               FROM unnest($1::BIGINT[], $2::TEXT[]) AS "rebalanced"("id", "position")
+               WHERE "task"."id" = "rebalanced"."id""#,
+        )
+        .bind(ids)
+        .bind(positions);
+        mm.dbx().execute(sqlx_query).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- TaskPositionBmc Privates
+
+// region:      --- Position Appender
+
+/// Gives the positions of the new tasks, at the end of their column
+/// (the last position of a column is cached, for the batch creates).
+#[derive(Default)]
+pub(crate) struct PositionAppender {
+    last_positions: HashMap<(i64, TaskStatus), String>,
+}
+
+impl PositionAppender {
+    pub(crate) async fn next(
+        &mut self,
+        mm: &ModelManager,
+        project_id: i64,
+        status: TaskStatus,
+    ) -> Result<String> {
+        let last_position = match self.last_positions.remove(&(project_id, status)) {
+            Some(last_position) => Some(last_position),
+            None => {
+                let column = Column { project_id, status };
+                TaskPositionBmc::adjacent_task(mm, &column, 0, None, Order::Desc)
+                    .await?
+                    .map(|(_, position)| position)
+            }
+        };
+        let position = position_between(last_position.as_deref(), None).unwrap_or_default();
+        self.last_positions
+            .insert((project_id, status), position.clone());
+
+        Ok(position)
+    }
+}
+
+// endregion:   --- Position Appender
+
+// region:      --- Position Keys
+
+/// Returns a key strictly between the `prev` and `next` keys (None for the start/end
+/// of the column), or None if `prev` is not before `next`.
+fn position_between(prev: Option<&str>, next: Option<&str>) -> Option<String> {
+    let digits = match (prev.map(to_digits), next.map(to_digits)) {
+        (None, None) => vec![BASE / 2],
+        (Some(prev), None) => step_up(&prev).unwrap_or_else(|| midpoint(&prev, None)),
+        (None, Some(next)) => {
+            if next.is_empty() {
+                return None;
+            }
+            step_down(&next).unwrap_or_else(|| midpoint(&[], Some(&next)))
+        }
+        (Some(prev), Some(next)) => {
+            if prev >= next {
+                return None;
+            }
+            midpoint(&prev, Some(&next))
+        }
+    };
+
+    Some(to_key(&digits))
+}
+
+/// Returns `count` evenly spaced keys, in order.
+fn spread_positions(count: usize) -> Vec<String> {
+    // One more digit than needed, to leave room between the keys.
+    let mut digit_count = 2;
+    while (BASE as u128).pow(digit_count - 1) <= count as u128 {
+        digit_count += 1;
+    }
+    let step = (BASE as u128).pow(digit_count) / (count as u128 + 1);
+
+    (1..=count as u128)
+        .map(|i| {
+            let mut value = step * i;
+            let mut digits = vec![0; digit_count as usize];
+            for digit in digits.iter_mut().rev() {
+                *digit = (value % BASE as u128) as u8;
+                value /= BASE as u128;
+            }
+            to_key(&digits)
+        })
+        .collect()
+}
+
+/// Returns the digits between `a` and `b` (1 when None).
+/// `a` must be before `b`, and `b` without trailing zeros.
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> Vec<u8> {
+    let mut digits = Vec::new();
+    let (mut a, mut b) = (a, b);
+    loop {
+        // -- Keep the common prefix
+        if let Some(b_digits) = b {
+            let prefix_len = b_digits
+                .iter()
+                .enumerate()
+                .take_while(|(i, digit)| a.get(*i).copied().unwrap_or(0) == **digit)
+                .count();
+            digits.extend_from_slice(&b_digits[..prefix_len]);
+            a = a.get(prefix_len..).unwrap_or_default();
+            b = Some(&b_digits[prefix_len..]);
+        }
+
+        // -- The middle digit (if the first digits are not consecutive)
+        let a_digit = a.first().copied().unwrap_or(0);
+        let b_digit = b.map(|b| b[0]).unwrap_or(BASE);
+        if b_digit - a_digit > 1 {
+            digits.push((a_digit + b_digit) / 2);
+            return digits;
+        }
+
+        // -- Otherwise, the first digit of `b` alone (if `b` is longer),
+        //    or the first digit of `a` followed by a key after the rest of `a`.
+        match b {
+            Some(b) if b.len() > 1 => {
+                digits.push(b[0]);
+                return digits;
+            }
+            _ => {
+                digits.push(a_digit);
+                a = a.get(1..).unwrap_or_default();
+                b = None;
+            }
+        }
+    }
+}
+
+/// Returns the digits plus one step (None if over 1).
+fn step_up(digits: &[u8]) -> Option<Vec<u8>> {
+    let mut digits = digits.to_vec();
+    if digits.len() < STEP_DIGIT_COUNT {
+        digits.resize(STEP_DIGIT_COUNT, 0);
+    }
+    for digit in digits[..STEP_DIGIT_COUNT].iter_mut().rev() {
+        if *digit + 1 < BASE {
+            *digit += 1;
+            return Some(digits);
+        }
+        *digit = 0;
+    }
+
+    None
+}
+
+/// Returns the digits minus one step (None if not over 0).
+fn step_down(digits: &[u8]) -> Option<Vec<u8>> {
+    let mut digits = digits.to_vec();
+    if digits.len() < STEP_DIGIT_COUNT {
+        digits.resize(STEP_DIGIT_COUNT, 0);
+    }
+    for digit in digits[..STEP_DIGIT_COUNT].iter_mut().rev() {
+        if *digit > 0 {
+            *digit -= 1;
+            return digits.iter().any(|digit| *digit > 0).then_some(digits);
+        }
+        *digit = BASE - 1;
+    }
+
+    None
+}
+
+/// Returns the digits of the key, without its trailing zeros (an unknown char is a zero).
+fn to_digits(key: &str) -> Vec<u8> {
+    let mut digits: Vec<u8> = key
+        .bytes()
+        .map(|c| DIGITS.iter().position(|d| *d == c).unwrap_or(0) as u8)
+        .collect();
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    digits
+}
+
+/// Returns the key of the digits, without its trailing zeros.
+fn to_key(digits: &[u8]) -> String {
+    let len = digits
+        .iter()
+        .rposition(|digit| *digit > 0)
+        .map_or(0, |i| i + 1);
+
+    digits[..len]
+        .iter()
+        .map(|digit| DIGITS[*digit as usize] as char)
+        .collect()
+}
+
+// endregion:   --- Position Keys
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_dev_utils;
+    use crate::model::task::TaskFilter;
+    use anyhow::Result;
+    use modql::filter::ListOptions;
+    use serial_test::serial;
+
+    #[test]
+    fn test_position_between_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_keys = [
+            "0000000001",
+            "000000001",
+            "1",
+            "i",
+            "i0001",
+            "i01",
+            "z",
+            "zzzzzz1",
+        ];
+
+        // -- Exec & Check
+        for pair in fx_keys.windows(2) {
+            let (prev, next) = (pair[0], pair[1]);
+            let key = position_between(Some(prev), Some(next)).unwrap();
+            assert!(
+                prev < key.as_str() && key.as_str() < next,
+                "{prev} < {key} < {next}"
+            );
+        }
+        for key in fx_keys {
+            let after = position_between(Some(key), None).unwrap();
+            assert!(key < after.as_str(), "{key} < {after}");
+            let before = position_between(None, Some(key)).unwrap();
+            assert!(
+                !before.is_empty() && before.as_str() < key,
+                "{before} < {key}"
+            );
+        }
+        assert_eq!(position_between(Some("i"), Some("i")), None);
+        assert_eq!(position_between(Some("z"), Some("i")), None);
+
+        // -- Check spread
+        let keys = spread_positions(100);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(keys.iter().all(|key| !key.is_empty() && key.len() <= 3));
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_place_and_rebalance_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_place_and_rebalance_ok project").await?;
+        let fx_tasks =
+            _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["t-a", "t-b", "t-c", "t-d"]).await?;
+        let ids: Vec<i64> = fx_tasks.iter().map(|task| task.id).collect();
+        let column_titles = |status: &'static str| {
+            let (ctx, mm) = (&ctx, &mm);
+            async move {
+                let filter = TaskFilter {
+                    project_id: Some(fx_project_id.into()),
+                    status: Some(status.into()),
+                    ..Default::default()
+                };
+                let list_options = ListOptions {
+                    order_bys: Some("position".into()),
+                    ..Default::default()
+                };
+                let tasks = TaskBmc::list(ctx, mm, Some(vec![filter]), Some(list_options)).await?;
+                Ok::<_, anyhow::Error>(tasks.into_iter().map(|task| task.title).collect::<Vec<_>>())
+            }
+        };
+
+        // -- Exec & Check - place after, before, at the end
+        let place = |after_id, before_id| TaskPlacement {
+            after_id,
+            before_id,
+            ..Default::default()
+        };
+        TaskPositionBmc::place(&ctx, &mm, ids[3], place(Some(ids[0]), None)).await?;
+        assert_eq!(column_titles("todo").await?, ["t-a", "t-d", "t-b", "t-c"]);
+        TaskPositionBmc::place(&ctx, &mm, ids[0], place(None, Some(ids[2]))).await?;
+        assert_eq!(column_titles("todo").await?, ["t-d", "t-b", "t-a", "t-c"]);
+        TaskPositionBmc::place(&ctx, &mm, ids[3], place(None, None)).await?;
+        assert_eq!(column_titles("todo").await?, ["t-b", "t-a", "t-c", "t-d"]);
+
+        // -- Exec & Check - another column
+        let placement = TaskPlacement {
+            status: Some(TaskStatus::InProgress),
+            ..Default::default()
+        };
+        TaskPositionBmc::place(&ctx, &mm, ids[1], placement).await?;
+        assert_eq!(column_titles("in_progress").await?, ["t-b"]);
+        let res = TaskPositionBmc::place(&ctx, &mm, ids[0], place(Some(ids[1]), None)).await;
+        assert!(
+            matches!(res, Err(Error::TaskPlacementInvalid { neighbor_id, .. }) if neighbor_id == ids[1]),
+            "neighbor should be in the column"
+        );
+        let res =
+            TaskPositionBmc::place(&ctx, &mm, ids[0], place(Some(ids[3]), Some(ids[2]))).await;
+        assert!(
+            matches!(res, Err(Error::TaskPlacementInvalid { .. })),
+            "after_id should be before before_id"
+        );
+
+        // -- Exec & Check - long keys rebalance
+        for _ in 0..40 {
+            TaskPositionBmc::place(&ctx, &mm, ids[2], place(Some(ids[0]), Some(ids[3]))).await?;
+            TaskPositionBmc::place(&ctx, &mm, ids[3], place(Some(ids[0]), Some(ids[2]))).await?;
+        }
+        let task: Task = TaskBmc::get(&ctx, &mm, ids[3]).await?;
+        assert!(task.position.len() > POSITION_KEY_MAX_LEN as usize);
+        let count = TaskPositionBmc::rebalance_long_positions(&mm).await?;
+        assert!(count >= 1);
+        let task: Task = TaskBmc::get(&ctx, &mm, ids[3]).await?;
+        assert!(task.position.len() <= POSITION_KEY_MAX_LEN as usize);
+        assert_eq!(column_titles("todo").await?, ["t-a", "t-d", "t-c"]);
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
@@ -1,28 +1,45 @@
 // region:      --- Modules
 
 use crate::ctx::Ctx;
-use crate::model::base::{self, prep_fields_for_update, DbBmc};
+use crate::model::base::{
+    self, log_change, prep_fields_for_soft_delete, prep_fields_for_update, AccessLevel, CommonIden,
+    DbBmc, SoftDeleteIden,
+};
+use crate::model::change_log::{ChangeLogBmc, ChangeOp};
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::project::{ProjectBmc, ProjectFilter, ProjectForUpdate};
+use crate::model::project_member::ProjectMemberBmc;
 use crate::model::ModelManager;
 use crate::model::{Error, Result};
 use lib_auth::pwd::{self, ContentToHash};
+use lib_utils::time::{now_utc, Rfc3339};
 use modql::field::{Field, Fields, HasFields};
 use modql::filter::{FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue};
-use sea_query::{Expr, Iden, PostgresQueryBuilder, Query};
+use sea_query::{Condition, Expr, Iden, Keyword, PostgresQueryBuilder, Query, SimpleExpr};
 use sea_query_binder::SqlxBinder;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use sqlx::postgres::PgRow;
+use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
+use std::time::Duration;
+use tracing::warn;
 use uuid::Uuid;
 
 // endregion:   --- Modules
 
 // region:     --- User Types
 
+#[serde_as]
 #[derive(Clone, Fields, FromRow, Debug, Serialize)]
 pub struct User {
     pub id: i64,
     pub username: String,
+
+    // -- Account deletion request
+    //    (the account is scrubbed once the grace period is over)
+    #[serde_as(as = "Option<Rfc3339>")]
+    pub deletion_requested_at: Option<OffsetDateTime>,
 }
 
 #[derive(Deserialize)]
@@ -56,6 +73,18 @@ pub struct UserForAuth {
     pub token_salt: Uuid,
 }
 
+/// What to do with the projects owned by a user being deleted.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnedProjectsAction {
+    /// Soft delete the projects (they can still be restored with `ProjectBmc::restore`).
+    #[default]
+    Archive,
+    /// Transfer the projects to another (non deleted) user,
+    /// who must be a member of each of them.
+    TransferTo(i64),
+}
+
 /// Marker trait
 pub trait UserBy: HasFields + for<'r> FromRow<'r, PgRow> + Unpin + Send {}
 
@@ -71,6 +100,11 @@ enum UserIden {
     Id,
     Username,
     Pwd,
+    PwdSalt,
+    TokenSalt,
+    IsAdmin,
+    DeletionRequestedAt,
+    DeletionTransferTo,
 }
 
 #[derive(FilterNodes, Deserialize, Default, Debug)]
@@ -95,6 +129,16 @@ pub struct UserBmc;
 
 impl DbBmc for UserBmc {
     const TABLE: &'static str = "user";
+
+    fn has_soft_delete() -> bool {
+        true
+    }
+
+    /// The `username` is personal data, so it cannot outlive the user anonymization
+    /// (see `UserBmc::delete`) in the change log.
+    fn secret_columns() -> &'static [&'static str] {
+        &["username", "pwd", "pwd_salt", "token_salt"]
+    }
 }
 
 impl UserBmc {
@@ -109,14 +153,10 @@ impl UserBmc {
             username: username.to_string(),
         };
 
-        // Start the transaction
-        let mm = mm.new_with_txn()?;
-
-        mm.dbx().begin_txn().await?;
-
-        let user_id = base::create::<Self, _>(ctx, &mm, user_fi)
-            .await
-            .map_err(|model_error| {
+        // In a transaction (no user without password when `update_pwd` fails)
+        mm.in_txn(|mm| async move {
+            let res = base::create::<Self, _>(ctx, &mm, user_fi).await;
+            let user_id = res.map_err(|model_error| {
                 Error::resolve_unique_violation(
                     model_error,
                     Some(|table: &str, constraint: &str| {
@@ -129,13 +169,12 @@ impl UserBmc {
                 )
             })?;
 
-        // -- Update the database
-        Self::update_pwd(ctx, &mm, user_id, &pwd_clear).await?;
-
-        // Commit the transaction
-        mm.dbx().commit_txn().await?;
+            // -- Update the database
+            Self::update_pwd(ctx, &mm, user_id, &pwd_clear).await?;
 
-        Ok(user_id)
+            Ok(user_id)
+        })
+        .await
     }
 
     pub async fn get<E>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
@@ -191,34 +230,298 @@ impl UserBmc {
         let mut fields = Fields::new(vec![Field::new(UserIden::Pwd, pwd.into())]);
         prep_fields_for_update::<Self>(&mut fields, ctx.user_id());
 
+        // -- Exec query
+        Self::exec_update(ctx, mm, id, fields, ChangeOp::Update).await
+    }
+
+    /// Returns `Error::UserNotAdmin` if the ctx user is not an admin.
+    /// (the root ctx is always allowed)
+    pub async fn ensure_admin(ctx: &Ctx, mm: &ModelManager) -> Result<()> {
+        if ctx.is_root() {
+            return Ok(());
+        }
+
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .from(Self::table_ref())
+            .column(UserIden::IsAdmin)
+            .and_where(Expr::col(UserIden::Id).eq(ctx.user_id()))
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (bool,), _>(&sql, values);
+        let is_admin = mm
+            .dbx()
+            .fetch_optional(sqlx_query)
+            .await?
+            .map(|(is_admin,)| is_admin)
+            .unwrap_or(false);
+
+        if is_admin {
+            Ok(())
+        } else {
+            Err(Error::UserNotAdmin {
+                user_id: ctx.user_id(),
+            })
+        }
+    }
+
+    /// Delete and anonymize the user:
+    /// - The owned projects are archived or transferred (see `OwnedProjectsAction`).
+    /// - The user is removed from the projects it is a member of.
+    /// - `username` is changed to "DELETED-_user_id_", and the password is cleared.
+    /// - `pwd_salt` and `token_salt` are regenerated, which invalidates all the user sessions.
+    /// - `deleted_at`/`deleted_by` (and `mid`/`mtime`) record who performed the deletion.
+    ///
+    pub async fn delete(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        owned_projects: OwnedProjectsAction,
+    ) -> Result<()> {
+        // In a transaction
+        mm.in_txn(|mm| async move {
+            // -- Make sure the user exists (and is not already deleted)
+            let _user: User = Self::get(ctx, &mm, id).await?;
+
+            // -- Archive or transfer the owned projects, and leave the other projects
+            Self::dispose_owned_projects(ctx, &mm, id, owned_projects).await?;
+            ProjectMemberBmc::remove_user(&Ctx::root_ctx(), &mm, id).await?;
+
+            // -- Prep the data
+            let null = SimpleExpr::Keyword(Keyword::Null);
+            let mut fields = Fields::new(vec![
+                Field::new(UserIden::Username, format!("DELETED-{id}").into()),
+                Field::new(UserIden::Pwd, null.clone()),
+                Field::new(UserIden::PwdSalt, Uuid::new_v4().into()),
+                Field::new(UserIden::TokenSalt, Uuid::new_v4().into()),
+                Field::new(UserIden::DeletionRequestedAt, null.clone()),
+                Field::new(UserIden::DeletionTransferTo, null),
+            ]);
+            prep_fields_for_soft_delete::<Self>(&mut fields, ctx.user_id());
+
+            // -- Exec query
+            Self::exec_update(ctx, &mm, id, fields, ChangeOp::Delete).await
+        })
+        .await
+    }
+
+    /// Self-service account deletion. The user is only scrubbed (see `UserBmc::delete`)
+    /// by `UserBmc::scrub_due_deletions` once the grace period is over,
+    /// and the request can be cancelled until then.
+    pub async fn request_deletion(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        owned_projects: OwnedProjectsAction,
+    ) -> Result<()> {
+        // -- Validate the transfer target now, rather than when scrubbing.
+        let transfer_to = match owned_projects {
+            OwnedProjectsAction::Archive => None,
+            OwnedProjectsAction::TransferTo(to_user_id) => {
+                Self::ensure_deletion_transfer_target(ctx, mm, id, to_user_id).await?;
+                Some(to_user_id)
+            }
+        };
+
+        // -- Prep the data
+        let mut fields = Fields::new(vec![
+            Field::new(UserIden::DeletionRequestedAt, now_utc().into()),
+            Field::new(UserIden::DeletionTransferTo, transfer_to.into()),
+        ]);
+        prep_fields_for_update::<Self>(&mut fields, ctx.user_id());
+
+        // -- Exec query
+        Self::exec_update(ctx, mm, id, fields, ChangeOp::Update).await
+    }
+
+    pub async fn cancel_deletion(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        // -- Prep the data
+        let null = SimpleExpr::Keyword(Keyword::Null);
+        let mut fields = Fields::new(vec![
+            Field::new(UserIden::DeletionRequestedAt, null.clone()),
+            Field::new(UserIden::DeletionTransferTo, null),
+        ]);
+        prep_fields_for_update::<Self>(&mut fields, ctx.user_id());
+
+        // -- Exec query
+        Self::exec_update(ctx, mm, id, fields, ChangeOp::Update).await
+    }
+
+    /// Delete (see `UserBmc::delete`) the users whose deletion request is older than `grace`.
+    /// Returns the ids of the deleted users.
+    ///
+    /// Note: A user that fails to be deleted (e.g., the transfer target has been deleted since,
+    ///       or is no longer a member of a project) is logged and skipped,
+    ///       so that it does not block the others.
+    pub async fn scrub_due_deletions(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        grace: Duration,
+    ) -> Result<Vec<i64>> {
+        // -- Build query
+        let mut query = Query::select();
+        query
+            .from(Self::table_ref())
+            .columns([UserIden::Id, UserIden::DeletionTransferTo])
+            .and_where(Expr::col(UserIden::DeletionRequestedAt).lte(now_utc() - grace))
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+
+        // -- Exec query
+        let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+        let sqlx_query = sqlx::query_as_with::<_, (i64, Option<i64>), _>(&sql, values);
+        let due_users = mm.dbx().fetch_all(sqlx_query).await?;
+
+        // -- Delete the users
+        let mut ids = Vec::new();
+        for (id, transfer_to) in due_users {
+            let owned_projects = transfer_to
+                .map(OwnedProjectsAction::TransferTo)
+                .unwrap_or_default();
+
+            match Self::delete(ctx, mm, id, owned_projects).await {
+                Ok(()) => ids.push(id),
+                Err(ex) => warn!("{:<12} - scrub_due_deletions - user {id}: {ex:?}", "MODEL"),
+            }
+        }
+
+        Ok(ids)
+    }
+}
+
+// region:     --- UserBmc Privates
+
+impl UserBmc {
+    /// Update the non deleted user row, and record the change in the change log.
+    async fn exec_update(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        fields: Fields,
+        op: ChangeOp,
+    ) -> Result<()> {
         // -- Build query
         let fields = fields.for_sea_update();
         let mut query = Query::update();
         query
             .table(Self::table_ref())
             .values(fields)
-            .and_where(Expr::col(UserIden::Id).eq(id));
+            .and_where(Expr::col(UserIden::Id).eq(id))
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null());
+
+        // In a transaction (the change log is written with the mutation)
+        mm.in_txn(|mm| async move {
+            let before = ChangeLogBmc::snapshot::<Self>(&mm, id).await?;
+
+            // -- Exec query
+            let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+            let sqlx_query = sqlx::query_with(&sql, values);
+            let count = mm.dbx().execute(sqlx_query).await?;
+
+            // -- Log change
+            log_change::<Self>(ctx, &mm, id, op, count, before).await
+        })
+        .await
+    }
+
+    /// Returns `Error::ProjectMemberNotFound` if the (existing) target user
+    /// is not a member of one of the (non deleted) projects owned by the user `id`.
+    async fn ensure_deletion_transfer_target(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        to_user_id: i64,
+    ) -> Result<()> {
+        if to_user_id == id {
+            return Err(Error::UserDeleteTransferToSelf { user_id: id });
+        }
+        let owned_cond = Condition::all().add(Expr::col(CommonIden::OwnerId).eq(id));
+        Self::ensure_transfer_target(ctx, mm, to_user_id, owned_cond).await
+    }
+
+    /// Returns `Error::EntityNotFound` if the target user does not exist (or is deleted),
+    /// and `Error::ProjectMemberNotFound` if it is not a member of one of the (non deleted)
+    /// projects matching `project_cond` and not already owned by it.
+    pub(crate) async fn ensure_transfer_target(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        to_user_id: i64,
+        project_cond: Condition,
+    ) -> Result<()> {
+        let _to_user: User = Self::get(ctx, mm, to_user_id).await?;
+
+        // -- Build query (a transferred project the target is not a member of)
+        let member_project_ids = ProjectMemberBmc::project_ids_query(to_user_id, AccessLevel::Read);
+        let mut query = Query::select();
+        query
+            .from(ProjectBmc::table_ref())
+            .column(CommonIden::Id)
+            .cond_where(project_cond)
+            .and_where(Expr::col(CommonIden::OwnerId).ne(to_user_id))
+            .and_where(Expr::col(SoftDeleteIden::DeletedAt).is_null())
+            .and_where(Expr::col(CommonIden::Id).not_in_subquery(member_project_ids))
+            .limit(1);
 
         // -- Exec query
         let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-        let sqlx_query = sqlx::query_with(&sql, values);
-        let _count = mm.dbx().execute(sqlx_query).await?;
-
-        Ok(())
+        let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+        match mm.dbx().fetch_optional(sqlx_query).await? {
+            Some((project_id,)) => Err(Error::ProjectMemberNotFound {
+                project_id,
+                user_id: to_user_id,
+            }),
+            None => Ok(()),
+        }
     }
 
-    /// TODO: For User, deletion will require a soft-delete approach:
-    ///       - Set `deleted: true`.
-    ///       - Change `username` to "DELETED-_user_id_".
-    ///       - Clear any other UUIDs or PII (Personally Identifiable Information).
-    ///       - The automatically set `mid`/`mtime` will record who performed the deletion.
-    ///       - It's likely necessary to record this action in a `um_change_log` (a user management change audit table).
-    ///       - Remove or clean up any user-specific assets (messages, etc.).
-    pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-        base::delete::<Self>(ctx, mm, id).await
+    async fn dispose_owned_projects(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        id: i64,
+        owned_projects: OwnedProjectsAction,
+    ) -> Result<()> {
+        if let OwnedProjectsAction::TransferTo(to_user_id) = owned_projects {
+            Self::ensure_deletion_transfer_target(ctx, mm, id, to_user_id).await?;
+        }
+
+        // Note: The deletion is already authorized (self or admin), but the projects
+        //       are not accessible to an admin, so they are processed as root.
+        let ctx = &Ctx::root_ctx();
+
+        // Note: Each processed project leaves the filtered set (archived or new owner),
+        //       so the first page is listed until empty.
+        loop {
+            let filter = ProjectFilter {
+                owner_id: Some(id.into()),
+                ..Default::default()
+            };
+            let projects = ProjectBmc::list(ctx, mm, Some(vec![filter]), None).await?;
+            if projects.is_empty() {
+                break;
+            }
+
+            for project in projects {
+                match owned_projects {
+                    OwnedProjectsAction::Archive => ProjectBmc::delete(ctx, mm, project.id).await?,
+                    OwnedProjectsAction::TransferTo(to_user_id) => {
+                        let project_u = ProjectForUpdate {
+                            name: None,
+                            owner_id: Some(to_user_id),
+                        };
+                        ProjectBmc::update(ctx, mm, project.id, project_u).await?
+                    }
+                }
+            }
+        }
+
+        Ok(())
     }
 }
 
+// endregion:  --- UserBmc Privates
+
 // endregion:  --- UserBmc
 
 // region:      --- Tests
@@ -227,6 +530,8 @@ impl UserBmc {
 mod tests {
     use super::*;
     use crate::_dev_utils;
+    use crate::model::change_log::ChangeLogFilter;
+    use crate::model::project_member::ProjectRole;
     use anyhow::{Context, Result};
     use serial_test::serial;
 
@@ -248,6 +553,143 @@ mod tests {
 
         Ok(())
     }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_delete_archive_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_username = "test_delete_archive_ok-user-01";
+        let user_id = UserBmc::create(
+            &ctx,
+            &mm,
+            UserForCreate {
+                username: fx_username.to_string(),
+                pwd_clear: "welcome".to_string(),
+            },
+        )
+        .await?;
+        let user_ctx = Ctx::new(user_id)?;
+        let project_id =
+            _dev_utils::seed_project(&user_ctx, &mm, "test_delete_archive_ok project").await?;
+        let other_project_id =
+            _dev_utils::seed_project(&ctx, &mm, "test_delete_archive_ok other project").await?;
+        ProjectMemberBmc::add(&ctx, &mm, other_project_id, user_id, ProjectRole::Editor).await?;
+        let user_before: UserForLogin = UserBmc::get(&ctx, &mm, user_id).await?;
+
+        // -- Exec
+        UserBmc::delete(&ctx, &mm, user_id, OwnedProjectsAction::Archive).await?;
+
+        // -- Check
+        let res = UserBmc::get::<User>(&ctx, &mm, user_id).await;
+        assert!(
+            matches!(res, Err(Error::EntityNotFound { .. })),
+            "user should be deleted"
+        );
+        let by_username: Option<User> = UserBmc::first_by_username(&ctx, &mm, fx_username).await?;
+        assert!(by_username.is_none(), "username should be scrubbed");
+        let user: UserForLogin =
+            UserBmc::first_by_username(&ctx, &mm, &format!("DELETED-{user_id}"))
+                .await?
+                .context("Should have the scrubbed user")?;
+        assert!(user.pwd.is_none());
+        assert_ne!(user.pwd_salt, user_before.pwd_salt);
+        assert_ne!(user.token_salt, user_before.token_salt);
+        let res = ProjectBmc::get(&ctx, &mm, project_id).await;
+        assert!(
+            matches!(res, Err(Error::EntityNotFound { .. })),
+            "project should be archived"
+        );
+        let res = ProjectMemberBmc::get(&ctx, &mm, other_project_id, user_id).await;
+        assert!(
+            matches!(res, Err(Error::ProjectMemberNotFound { .. })),
+            "user should be removed from the other project"
+        );
+        let filter = ChangeLogFilter {
+            entity: Some(UserBmc::TABLE.into()),
+            entity_id: Some(user_id.into()),
+            ..Default::default()
+        };
+        let change_logs = ChangeLogBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        assert!(!change_logs.is_empty(), "user changes should be logged");
+        for change_log in change_logs {
+            assert!(
+                !change_log.diff.to_string().contains(fx_username),
+                "username should not be in the change log"
+            );
+        }
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, project_id).await?;
+        ProjectBmc::purge(&ctx, &mm, other_project_id).await?;
+        base::purge::<UserBmc>(&ctx, &mm, user_id).await?;
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_scrub_due_deletions_transfer_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let demo1: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+            .await?
+            .context("Should have user 'demo1'")?;
+        let user_id = UserBmc::create(
+            &ctx,
+            &mm,
+            UserForCreate {
+                username: "test_scrub_due_deletions_transfer_ok-user-01".to_string(),
+                pwd_clear: "welcome".to_string(),
+            },
+        )
+        .await?;
+        let user_ctx = Ctx::new(user_id)?;
+        let project_id = _dev_utils::seed_project(
+            &user_ctx,
+            &mm,
+            "test_scrub_due_deletions_transfer_ok project",
+        )
+        .await?;
+        let res = UserBmc::request_deletion(
+            &user_ctx,
+            &mm,
+            user_id,
+            OwnedProjectsAction::TransferTo(demo1.id),
+        )
+        .await;
+        assert!(
+            matches!(res, Err(Error::ProjectMemberNotFound { project_id: id, .. }) if id == project_id),
+            "should reject a transfer target not member of the project"
+        );
+        ProjectMemberBmc::add(&user_ctx, &mm, project_id, demo1.id, ProjectRole::Editor).await?;
+        UserBmc::request_deletion(
+            &user_ctx,
+            &mm,
+            user_id,
+            OwnedProjectsAction::TransferTo(demo1.id),
+        )
+        .await?;
+
+        // -- Exec
+        let not_due_ids =
+            UserBmc::scrub_due_deletions(&ctx, &mm, Duration::from_secs(3600)).await?;
+        let due_ids = UserBmc::scrub_due_deletions(&ctx, &mm, Duration::ZERO).await?;
+
+        // -- Check
+        assert!(not_due_ids.is_empty(), "grace period should not be over");
+        assert_eq!(due_ids, vec![user_id]);
+        let project = ProjectBmc::get(&ctx, &mm, project_id).await?;
+        assert_eq!(project.owner_id, demo1.id);
+
+        // -- Clean
+        ProjectBmc::purge(&ctx, &mm, project_id).await?;
+        base::purge::<UserBmc>(&ctx, &mm, user_id).await?;
+
+        Ok(())
+    }
 }
 
 // endregion:   --- Tests
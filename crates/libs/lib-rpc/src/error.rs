@@ -14,6 +14,25 @@ pub enum Error {
     // -- RPC router.
     RpcMethodUnknown(String),
     RpcIntoParamsMissing,
+    RpcIntoParamsInvalid(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+
+    // -- RPC txn.
+    RpcTxnCallsOverMax {
+        max: usize,
+    },
+    RpcTxnRefInvalid {
+        reference: String,
+    },
+    RpcTxnCallFail {
+        index: usize,
+        method: String,
+        cause: Box<Error>,
+    },
+
+    // -- RPC dry run.
+    RpcDryRunNotMutating {
+        method: String,
+    },
 
     // -- Modules
     #[from]
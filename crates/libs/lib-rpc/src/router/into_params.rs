@@ -10,7 +10,7 @@ use serde_json::Value;
 pub trait IntoParams: DeserializeOwned + Send {
     fn into_params(value: Option<Value>) -> Result<Self> {
         match value {
-            Some(value) => Ok(serde_json::from_value(value)?),
+            Some(value) => serde_json::from_value(value).map_err(Error::RpcIntoParamsInvalid),
             None => Err(Error::RpcIntoParamsMissing),
         }
     }
@@ -26,7 +26,7 @@ where
 {
     fn into_params(value: Option<Value>) -> Result<Self> {
         match value {
-            Some(value) => Ok(serde_json::from_value(value)?),
+            Some(value) => serde_json::from_value(value).map_err(Error::RpcIntoParamsInvalid),
             None => Ok(Self::default()),
         }
     }
@@ -20,6 +20,12 @@
 //! impl IntoParams for ParamsIded {}
 //! ```
 //!
+//! - The mutating handlers are flagged when added (see `RpcRouter::add_dyn_mutating`
+//!   and the `mut` prefix of `rpc_router!`), so that the caller can run them in a transaction
+//!   (see `RpcRouter::is_mutating`).
+//! - The `txn` method calls a list of methods in one transaction (see `RpcRouter::call_txn`).
+//! - The mutating methods (and `txn`) can be dry run, with the `dry_run` flag of the request
+//!   (see `RpcRouter::call_dry_run`).
 //! - For custom `IntoParams` behavior, implement the `IntoParams::into_params` function.
 //! - Implementing `IntoDefaultParams` on a type that implements `Default` will auto-implement `IntoParams`
 //!   and call `T::default()` when the params `Option<Value>` is None.
@@ -29,13 +35,16 @@
 
 mod from_resources;
 mod into_params;
+mod rpc_dry_run;
 mod rpc_handler;
 mod rpc_handler_wrapper;
+mod rpc_txn;
 
 pub use from_resources::FromResources;
 pub use into_params::{IntoDefaultParams, IntoParams};
 pub use rpc_handler::RpcHandler;
 pub use rpc_handler_wrapper::{RpcHandlerWrapper, RpcHandlerWrapperTrait};
+pub use rpc_txn::{ParamsForTxn, RpcTxnCall, TXN_METHOD};
 
 use crate::RpcResources;
 use crate::{Error, Result};
@@ -53,6 +62,9 @@ pub struct RpcRequest {
     pub id: Option<Value>,
     pub method: String,
     pub params: Option<Value>,
+    /// Call the (mutating) method without committing its changes.
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 pub type PinFutureValue = Pin<Box<dyn Future<Output = Result<Value>> + Send>>;
@@ -61,7 +73,13 @@ pub type PinFutureValue = Pin<Box<dyn Future<Output = Result<Value>> + Send>>;
 ///
 /// RpcRouter can be extended with other RpcRouters for composability.
 pub struct RpcRouter {
-    route_by_name: HashMap<&'static str, Box<dyn RpcHandlerWrapperTrait>>,
+    route_by_name: HashMap<&'static str, RpcRoute>,
+}
+
+struct RpcRoute {
+    handler: Box<dyn RpcHandlerWrapperTrait>,
+    /// The handler writes data (see `RpcRouter::is_mutating`).
+    mutating: bool,
 }
 
 impl RpcRouter {
@@ -82,12 +100,31 @@ impl RpcRouter {
     ///         avoids monomorphization of the add function.
     ///         The RpcRouter also has a `.add()` as a convenience function to just pass the function.
     ///         See `RpcRouter::add` for more details.
-    pub fn add_dyn(
-        mut self,
+    pub fn add_dyn(self, name: &'static str, dyn_handler: Box<dyn RpcHandlerWrapperTrait>) -> Self {
+        self.add_route(name, dyn_handler, false)
+    }
+
+    /// Same as `add_dyn`, for a handler that writes data (e.g., `create_task`).
+    ///
+    /// ```
+    /// RpcRouter::new().add_dyn_mutating("create_task", create_task.into_dyn());
+    /// ```
+    pub fn add_dyn_mutating(
+        self,
         name: &'static str,
         dyn_handler: Box<dyn RpcHandlerWrapperTrait>,
     ) -> Self {
-        self.route_by_name.insert(name, dyn_handler);
+        self.add_route(name, dyn_handler, true)
+    }
+
+    fn add_route(
+        mut self,
+        name: &'static str,
+        handler: Box<dyn RpcHandlerWrapperTrait>,
+        mutating: bool,
+    ) -> Self {
+        self.route_by_name
+            .insert(name, RpcRoute { handler, mutating });
         self
     }
 
@@ -122,24 +159,32 @@ impl RpcRouter {
         params: Option<Value>,
     ) -> Result<Value> {
         if let Some(route) = self.route_by_name.get(method) {
-            route.call(rpc_resources, params).await
+            route.handler.call(rpc_resources, params).await
         } else {
             Err(Error::RpcMethodUnknown(method.to_string()))
         }
     }
+
+    /// Returns true if the method handler was added as mutating (false if unknown).
+    pub fn is_mutating(&self, method: &str) -> bool {
+        self.route_by_name
+            .get(method)
+            .is_some_and(|route| route.mutating)
+    }
 }
 
 /// A simple macro to create a new RpcRouter
 /// and add each rpc handler-compatible function along with their corresponding names.
+/// The mutating handlers are prefixed with `mut`.
 ///
 /// e.g.
 ///
 /// ```
 /// rpc_router!(
-///     create_project,
+///     mut create_project,
 ///     list_project,
-///     update_project,
-///     delete_project,
+///     mut update_project,
+///     mut delete_project,
 /// );
 /// ```
 ///
@@ -147,20 +192,27 @@ impl RpcRouter {
 ///
 /// ```
 /// RpcRouter::new()
-///     .add_dyn("create_project", create_project.into_dyn())
+///     .add_dyn_mutating("create_project", create_project.into_dyn())
 ///     .add_dyn("list_projects", list_projects.into_dyn())
-///     .add_dyn("update_project", update_project.into_dyn())
-///     .add_dyn("delete_project", delete_project.into_dyn())
+///     .add_dyn_mutating("update_project", update_project.into_dyn())
+///     .add_dyn_mutating("delete_project", delete_project.into_dyn())
 /// ```
 #[macro_export]
 macro_rules! rpc_router {
-    ($($fn_name:ident), + $(,)?) => {
+    (@add $router:ident;) => {};
+    (@add $router:ident; mut $fn_name:ident $(, $($rest:tt)*)?) => {
+        $router = $router.add_dyn_mutating(stringify!($fn_name), $fn_name.into_dyn());
+        $( $crate::rpc_router!(@add $router; $($rest)*); )?
+    };
+    (@add $router:ident; $fn_name:ident $(, $($rest:tt)*)?) => {
+        $router = $router.add_dyn(stringify!($fn_name), $fn_name.into_dyn());
+        $( $crate::rpc_router!(@add $router; $($rest)*); )?
+    };
+    ($($route:tt)+) => {
         {
             use $crate::router::{RpcHandler, RpcRouter};
             let mut router = RpcRouter::new();
-            $(
-                router = router.add_dyn(stringify!($fn_name), $fn_name.into_dyn());
-            )+
+            $crate::rpc_router!(@add router; $($route)+);
             router
         }
     };
@@ -0,0 +1,161 @@
+//! The dry run of a mutating json-rpc method, with the `dry_run` flag of the request.
+//!
+//! e.g.,
+//!
+//! ```json
+//! {
+//!   "method": "update_task",
+//!   "params": {"id": 123, "data": {"title": "Task A"}},
+//!   "dry_run": true
+//! }
+//! ```
+//!
+//! Design:
+//!
+//! - The method is called in a transaction that is always rolled back
+//!   (see `ModelManager::dry_run`).
+//! - The result is the would-be result of the method, with the change set of the call
+//!   (the `ChangeLog` rows of the created, updated, and deleted entity rows),
+//!   as `{"result": ..., "changes": [...]}`.
+//! - Only the mutating methods and `txn` can be dry run (see `Error::RpcDryRunNotMutating`).
+
+// region:      --- Modules
+
+use crate::router::{RpcRouter, TXN_METHOD};
+use crate::rpc_result::DryRunRpcResult;
+use crate::RpcResources;
+use crate::{Error, Result};
+use serde_json::Value;
+
+// endregion:   --- Modules
+
+impl RpcRouter {
+    /// Dry run the mutating `method` (see the module doc).
+    pub async fn call_dry_run(
+        &self,
+        method: &str,
+        rpc_resources: RpcResources,
+        params: Option<Value>,
+    ) -> Result<Value> {
+        let is_txn = method == TXN_METHOD;
+        if !is_txn && !self.is_mutating(method) {
+            return Err(if self.route_by_name.contains_key(method) {
+                Error::RpcDryRunNotMutating {
+                    method: method.to_string(),
+                }
+            } else {
+                Error::RpcMethodUnknown(method.to_string())
+            });
+        }
+
+        let RpcResources { mm, ctx } = rpc_resources;
+
+        let (result, changes) = mm
+            .dry_run(|mm| async move {
+                let rpc_resources = RpcResources { mm, ctx };
+                if is_txn {
+                    self.call_txn(rpc_resources, params).await
+                } else {
+                    self.call(method, rpc_resources, params).await
+                }
+            })
+            .await?;
+
+        Ok(serde_json::to_value(DryRunRpcResult { result, changes })?)
+    }
+}
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::all_rpc_router;
+    use anyhow::{Context, Result};
+    use lib_core::_dev_utils;
+    use lib_core::ctx::Ctx;
+    use lib_core::model::change_log::ChangeLogBmc;
+    use lib_core::model::project::{ProjectBmc, ProjectFilter};
+    use modql::filter::ListOptions;
+    use serde_json::json;
+    use serial_test::serial;
+
+    #[serial]
+    #[tokio::test]
+    async fn test_call_dry_run_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_name = "test_call_dry_run_ok project";
+        let fx_params = json!({"data": {"name": fx_name}});
+        let rpc_router = all_rpc_router();
+        let last_change_options = ListOptions {
+            order_bys: Some("!id".into()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let fx_last_changes =
+            ChangeLogBmc::list(&ctx, &mm, None, Some(last_change_options.clone())).await?;
+
+        // -- Exec
+        let rpc_resources = RpcResources {
+            mm: mm.clone(),
+            ctx: Some(ctx.clone()),
+        };
+        let res = rpc_router
+            .call_dry_run("create_project", rpc_resources, Some(fx_params))
+            .await?;
+
+        // -- Check
+        let project_id = res["result"]["data"]["id"]
+            .as_i64()
+            .context("Should have a project id")?;
+        let changes = res["changes"]
+            .as_array()
+            .context("Should have a changes array")?;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["entity"], "project");
+        assert_eq!(changes[0]["entity_id"], project_id);
+        assert_eq!(changes[0]["op"], "create");
+
+        let filter = ProjectFilter {
+            name: Some(fx_name.into()),
+            ..Default::default()
+        };
+        let projects = ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        assert!(projects.is_empty(), "the project should not be persisted");
+        let last_changes = ChangeLogBmc::list(&ctx, &mm, None, Some(last_change_options)).await?;
+        assert_eq!(
+            last_changes.first().map(|change| change.id),
+            fx_last_changes.first().map(|change| change.id),
+            "the change log should not be persisted"
+        );
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_call_dry_run_err_not_mutating() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let rpc_router = all_rpc_router();
+
+        // -- Exec
+        let rpc_resources = RpcResources { mm, ctx: Some(ctx) };
+        let res = rpc_router
+            .call_dry_run("list_projects", rpc_resources, None)
+            .await;
+
+        // -- Check
+        assert!(
+            matches!(&res, Err(Error::RpcDryRunNotMutating { method }) if method == "list_projects"),
+            "should reject the non mutating method"
+        );
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
@@ -0,0 +1,323 @@
+//! The `txn` json-rpc method, which calls a list of rpc methods in one transaction.
+//!
+//! e.g.,
+//!
+//! ```json
+//! {
+//!   "method": "txn",
+//!   "params": {
+//!     "calls": [
+//!       {"method": "create_project", "params": {"data": {"name": "Project A"}}},
+//!       {"method": "create_task", "params": {"data": {"project_id": {"$ref": "0.data.id"}, "title": "Task 1"}}}
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! Design:
+//!
+//! - The calls are executed in order, with a ModelManager sharing one transaction, which is
+//!   committed when all the calls succeed, and rolled back otherwise (see `Error::RpcTxnCallFail`,
+//!   with the index of the failing call). When the ModelManager is already with txn
+//!   (e.g., for a dry run), the calls are nested in its transaction.
+//! - The result `data` is the list of the call results.
+//! - A params `{"$ref": "i.path.to.value"}` object (with only the `$ref` property) is replaced
+//!   by the value at this path of the result of the call `i` (a previous call),
+//!   e.g., `{"$ref": "0.data.id"}`, `{"$ref": "1.data.0.id"}`. The string values are never
+//!   references (e.g., a `"$1"` title is kept as is).
+//! - A `txn` call cannot be nested in a `txn` call.
+
+// region:      --- Modules
+
+use crate::router::{IntoParams, RpcRouter};
+use crate::rpc_result::DataRpcResult;
+use crate::RpcResources;
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+// endregion:   --- Modules
+
+/// The json-rpc method name of the txn call.
+pub const TXN_METHOD: &str = "txn";
+
+/// Max number of calls of a txn call.
+const TXN_CALLS_MAX: usize = 100;
+
+// region:      --- Params
+
+/// Params for the `txn` call.
+#[derive(Deserialize)]
+pub struct ParamsForTxn {
+    pub calls: Vec<RpcTxnCall>,
+}
+
+impl IntoParams for ParamsForTxn {}
+
+#[derive(Deserialize)]
+pub struct RpcTxnCall {
+    pub method: String,
+    pub params: Option<Value>,
+}
+
+// endregion:   --- Params
+
+impl RpcRouter {
+    /// Call the `txn` method (see the module doc).
+    pub async fn call_txn(
+        &self,
+        rpc_resources: RpcResources,
+        params: Option<Value>,
+    ) -> Result<Value> {
+        let ParamsForTxn { calls } = ParamsForTxn::into_params(params)?;
+        if calls.len() > TXN_CALLS_MAX {
+            return Err(Error::RpcTxnCallsOverMax { max: TXN_CALLS_MAX });
+        }
+
+        let RpcResources { mm, ctx } = rpc_resources;
+        let mm = mm.ensure_txn()?;
+
+        let results = mm
+            .in_txn(|mm| async move {
+                let mut results: Vec<Value> = Vec::with_capacity(calls.len());
+                for (index, call) in calls.into_iter().enumerate() {
+                    let RpcTxnCall { method, params } = call;
+
+                    let params = params.map(|params| resolve_refs(params, &results));
+                    let res = match params.transpose() {
+                        Ok(params) => {
+                            let rpc_resources = RpcResources {
+                                mm: mm.clone(),
+                                ctx: ctx.clone(),
+                            };
+                            self.call(&method, rpc_resources, params).await
+                        }
+                        Err(ex) => Err(ex),
+                    };
+
+                    match res {
+                        Ok(result) => results.push(result),
+                        Err(cause) => {
+                            return Err(Error::RpcTxnCallFail {
+                                index,
+                                method,
+                                cause: Box::new(cause),
+                            })
+                        }
+                    }
+                }
+
+                Ok(results)
+            })
+            .await?;
+
+        Ok(serde_json::to_value(DataRpcResult::from(results))?)
+    }
+}
+
+// region:      --- Txn Refs
+
+/// The property name of a reference object (see the module doc).
+const REF_PROP: &str = "$ref";
+
+/// Returns the params with their `{"$ref": "i.path"}` objects replaced by the value
+/// of the `results` (see the module doc).
+fn resolve_refs(params: Value, results: &[Value]) -> Result<Value> {
+    let value = match params {
+        Value::Object(props) => match ref_of(&props) {
+            Some(reference) => parse_ref(reference)
+                .and_then(|(index, path)| lookup(results.get(index)?, path))
+                .cloned()
+                .ok_or_else(|| Error::RpcTxnRefInvalid {
+                    reference: reference.to_string(),
+                })?,
+            None => Value::Object(
+                props
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, resolve_refs(value, results)?)))
+                    .collect::<Result<_>>()?,
+            ),
+        },
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| resolve_refs(item, results))
+                .collect::<Result<_>>()?,
+        ),
+        value => value,
+    };
+
+    Ok(value)
+}
+
+/// Returns the reference of a `{"$ref": "i.path"}` object (None if not a reference object).
+fn ref_of(props: &Map<String, Value>) -> Option<&str> {
+    match props.get(REF_PROP) {
+        Some(Value::String(reference)) if props.len() == 1 => Some(reference),
+        _ => None,
+    }
+}
+
+/// Returns the `(index, path)` of a `index.path` (or `index`) reference.
+fn parse_ref(reference: &str) -> Option<(usize, &str)> {
+    let (index, path) = reference.split_once('.').unwrap_or((reference, ""));
+    if index.is_empty() || !index.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((index.parse().ok()?, path))
+}
+
+/// Returns the value at the dot separated `path` (property names or array indexes).
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+
+    path.split('.').try_fold(value, |value, name| match value {
+        Value::Object(props) => props.get(name),
+        Value::Array(items) => items.get(name.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+// endregion:   --- Txn Refs
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::all_rpc_router;
+    use anyhow::Result;
+    use lib_core::_dev_utils;
+    use lib_core::ctx::Ctx;
+    use lib_core::model::project::{ProjectBmc, ProjectFilter};
+    use serde_json::json;
+    use serial_test::serial;
+
+    #[test]
+    fn test_parse_ref_ok() -> Result<()> {
+        // -- Exec & Check
+        assert_eq!(parse_ref("0"), Some((0, "")));
+        assert_eq!(parse_ref("12.data.id"), Some((12, "data.id")));
+        assert_eq!(parse_ref("$0.data.id"), None);
+        assert_eq!(parse_ref("a.data"), None);
+        assert_eq!(parse_ref(".data"), None);
+        assert_eq!(parse_ref(""), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lookup_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_value = json!({"data": {"id": 7, "items": [{"id": 8}, {"id": 9}]}});
+
+        // -- Exec & Check
+        assert_eq!(lookup(&fx_value, ""), Some(&fx_value));
+        assert_eq!(lookup(&fx_value, "data.id"), Some(&json!(7)));
+        assert_eq!(lookup(&fx_value, "data.items.1.id"), Some(&json!(9)));
+        assert_eq!(lookup(&fx_value, "data.items.2.id"), None, "out of range");
+        assert_eq!(lookup(&fx_value, "data.items.x"), None, "non-numeric index");
+        assert_eq!(lookup(&fx_value, "data.missing"), None);
+        assert_eq!(lookup(&fx_value, "data.id.more"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_refs_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_results = [json!({"data": {"id": 7}}), json!({"data": [{"id": 8}]})];
+        let fx_params = json!({
+            "data": {
+                "project_id": {"$ref": "0.data.id"},
+                "ids": [{"$ref": "1.data.0.id"}, [{"$ref": "0"}]],
+                "title": "$1",
+                "description": "$0.data.id",
+                "custom": {"$ref": "0.data.id", "other": 1},
+            }
+        });
+
+        // -- Exec
+        let params = resolve_refs(fx_params, &fx_results)?;
+
+        // -- Check
+        assert_eq!(
+            params,
+            json!({
+                "data": {
+                    "project_id": 7,
+                    "ids": [8, [{"data": {"id": 7}}]],
+                    "title": "$1",
+                    "description": "$0.data.id",
+                    "custom": {"$ref": "0.data.id", "other": 1},
+                }
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_refs_err_invalid() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_results = [json!({"data": {"id": 7}})];
+
+        for fx_reference in ["1.data.id", "0.data.name", "x"] {
+            // -- Exec
+            let res = resolve_refs(json!({"$ref": fx_reference}), &fx_results);
+
+            // -- Check
+            assert!(
+                matches!(&res, Err(Error::RpcTxnRefInvalid { reference }) if reference == fx_reference),
+                "reference '{fx_reference}'"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_call_txn_err_rollback() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_name = "test_call_txn_err_rollback project";
+        let fx_params = json!({
+            "calls": [
+                {"method": "create_project", "params": {"data": {"name": fx_name}}},
+                {"method": "get_task", "params": {"id": -1}},
+            ]
+        });
+        let rpc_router = all_rpc_router();
+
+        // -- Exec
+        let rpc_resources = RpcResources {
+            mm: mm.clone(),
+            ctx: Some(ctx.clone()),
+        };
+        let res = rpc_router.call_txn(rpc_resources, Some(fx_params)).await;
+
+        // -- Check
+        assert!(
+            matches!(&res, Err(Error::RpcTxnCallFail { index: 1, .. })),
+            "should fail at the call 1"
+        );
+        let filter = ProjectFilter {
+            name: Some(fx_name.into()),
+            ..Default::default()
+        };
+        let projects = ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        assert!(
+            projects.is_empty(),
+            "the call 0 project should be rolled back"
+        );
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
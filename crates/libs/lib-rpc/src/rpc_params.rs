@@ -8,7 +8,7 @@
 //! `RpcRouter` (i.e., `rpc::router`) model.
 
 use crate::router::{IntoDefaultParams, IntoParams};
-use crate::Result;
+use crate::{Error, Result};
 use modql::filter::ListOptions;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
@@ -24,14 +24,65 @@ pub struct ParamsForCreate<D> {
 impl<D> IntoParams for ParamsForCreate<D> where D: DeserializeOwned + Send {}
 
 /// Params struct for any RPC Update Call.
+/// `data` is a JSON merge patch (RFC 7396) of the entity: an absent property is not updated,
+/// a `null` one clears a nullable column (it is rejected for the other columns), and
+/// an object value (e.g., task `custom`) is merged into the current one (`null` removes a key).
+/// When `expected_version` is given, the update fails with a version conflict
+/// if the entity has been updated since (for entities with a `version`).
 #[derive(Deserialize)]
 pub struct ParamsForUpdate<D> {
     pub id: i64,
     pub data: D,
+    pub expected_version: Option<i64>,
 }
 
 impl<D> IntoParams for ParamsForUpdate<D> where D: DeserializeOwned + Send {}
 
+/// Params struct for any RPC batch Create call.
+#[derive(Deserialize)]
+pub struct ParamsForCreateMany<D> {
+    pub data: Vec<D>,
+}
+
+impl<D> IntoParams for ParamsForCreateMany<D> where D: DeserializeOwned + Send {}
+
+/// Params struct for any RPC batch Update call.
+/// The updated entities are given by exactly one of `ids` or `filters`,
+/// and `data` is a JSON merge patch (as for `ParamsForUpdate`).
+#[serde_as]
+#[derive(Deserialize)]
+pub struct ParamsForUpdateMany<D, F>
+where
+    F: DeserializeOwned,
+{
+    pub ids: Option<Vec<i64>>,
+    #[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+    pub filters: Option<Vec<F>>,
+    pub data: D,
+}
+
+impl<D, F> IntoParams for ParamsForUpdateMany<D, F>
+where
+    D: DeserializeOwned + Send,
+    F: DeserializeOwned + Send + Default,
+{
+}
+
+/// Params struct for any RPC batch Delete call.
+/// The deleted entities are given by exactly one of `ids` or `filters`.
+#[serde_as]
+#[derive(Deserialize)]
+pub struct ParamsForDeleteMany<F>
+where
+    F: DeserializeOwned,
+{
+    pub ids: Option<Vec<i64>>,
+    #[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+    pub filters: Option<Vec<F>>,
+}
+
+impl<F> IntoParams for ParamsForDeleteMany<F> where F: DeserializeOwned + Send + Default {}
+
 /// Params structure for any RPC Update call.
 #[derive(Deserialize)]
 pub struct ParamsIded {
@@ -41,6 +92,8 @@ pub struct ParamsIded {
 impl IntoParams for ParamsIded {}
 
 /// Params structure for any RPC List call.
+/// `cursor` is the `next_cursor` of the previous list result (for keyset pagination).
+/// `with_meta` adds the `meta` (`total`, `limit`, and `offset`) to the list result.
 #[serde_as]
 #[derive(Deserialize, Default)]
 pub struct ParamsList<F>
@@ -50,6 +103,9 @@ where
     #[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
     pub filters: Option<Vec<F>>,
     pub list_options: Option<ListOptions>,
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub with_meta: bool,
 }
 
 impl<D> IntoDefaultParams for ParamsList<D> where D: DeserializeOwned + Send + Default {}
@@ -68,7 +124,10 @@ where
     D: IntoParams,
 {
     fn into_params(value: Option<Value>) -> Result<Self> {
-        let value = value.map(|v| serde_json::from_value(v)).transpose()?;
+        let value = value
+            .map(|v| serde_json::from_value(v))
+            .transpose()
+            .map_err(Error::RpcIntoParamsInvalid)?;
         Ok(value)
     }
 }
@@ -81,3 +140,54 @@ where
 impl IntoParams for Value {}
 
 // endregion:   --- General Implemetations
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use lib_core::model::label::LabelForUpdate;
+    use lib_core::model::project::ProjectForUpdate;
+    use lib_core::model::task::TaskForUpdate;
+    use lib_core::model::NullableValue;
+    use serde_json::json;
+
+    #[test]
+    fn test_params_for_update_null_ok() -> Result<()> {
+        // -- Exec
+        let params = ParamsForUpdate::<TaskForUpdate>::into_params(Some(json!({
+            "id": 1,
+            "data": {"description": null, "due_at": null}
+        })))?;
+
+        // -- Check
+        assert_eq!(params.data.description, Some(NullableValue(None)));
+        assert_eq!(params.data.due_at, Some(NullableValue(None)));
+        assert!(params.data.title.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_params_for_update_err_null_not_nullable() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_params = json!({"id": 1, "data": {"title": null}});
+        let fx_label_params = json!({"id": 1, "data": {"name": null}});
+        let fx_project_params = json!({"id": 1, "data": {"name": null}});
+
+        // -- Exec
+        let res = ParamsForUpdate::<TaskForUpdate>::into_params(Some(fx_params));
+        let label_res = ParamsForUpdate::<LabelForUpdate>::into_params(Some(fx_label_params));
+        let project_res = ParamsForUpdate::<ProjectForUpdate>::into_params(Some(fx_project_params));
+
+        // -- Check
+        assert!(matches!(res, Err(Error::RpcIntoParamsInvalid(_))));
+        assert!(matches!(label_res, Err(Error::RpcIntoParamsInvalid(_))));
+        assert!(matches!(project_res, Err(Error::RpcIntoParamsInvalid(_))));
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
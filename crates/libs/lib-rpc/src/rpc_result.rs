@@ -5,13 +5,19 @@
 //!
 //! Notes:
 //!
-//!     -   In the future, we may introduce types like `DataRpcResult` that includes metadata
-//!         about the returned list data (e.g., pagination information).
+//!     -   For list data, `next_cursor` is the cursor of the next page (when there is one),
+//!         to be given as the `cursor` of the next list call.
+//!     -   For list data, `meta` has the `total` count and the `limit`/`offset` of the page
+//!         (only when requested, see `ParamsList::with_meta`).
+//!     -   `DryRunRpcResult` is the `.result` of a dry run call (see `router::rpc_dry_run`),
+//!         with the would-be `result` of the method and its `changes`.
 //!     -   Although the struct is named with `Result`, it is not a typical Rust result. Instead,
 //!         it represents the `.result` property of a JSON-RPC response.
 //!
 
+use lib_core::model::change_log::ChangeLog;
 use serde::Serialize;
+use serde_json::Value;
 
 #[derive(Serialize)]
 pub struct DataRpcResult<T>
@@ -19,6 +25,35 @@ where
     T: Serialize,
 {
     data: T,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meta: Option<ListMeta>,
+}
+
+/// The metadata of a list result page.
+#[derive(Serialize)]
+pub struct ListMeta {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl<T> DataRpcResult<T>
+where
+    T: Serialize,
+{
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.next_cursor = next_cursor;
+        self
+    }
+
+    pub fn with_meta(mut self, meta: Option<ListMeta>) -> Self {
+        self.meta = meta;
+        self
+    }
 }
 
 // The `From` trait is used to create an instance of a type from another type.
@@ -30,6 +65,17 @@ where
     // This is assuming that the value has an implementation for `T`.
     // Usage: `DataRpcResult::from(value)` or `let result: DataRpcResult<_> = value.into()`
     fn from(value: T) -> Self {
-        Self { data: value }
+        Self {
+            data: value,
+            next_cursor: None,
+            meta: None,
+        }
     }
-}
\ No newline at end of file
+}
+
+/// The result of a dry run call.
+#[derive(Serialize)]
+pub struct DryRunRpcResult {
+    pub result: Value,
+    pub changes: Vec<ChangeLog>,
+}
@@ -0,0 +1,55 @@
+use crate::router::IntoParams;
+use crate::rpcs::prelude::*;
+use lib_core::model::attachment::{Attachment, AttachmentBmc};
+use modql::filter::ListOptions;
+use serde::Deserialize;
+
+// Note: The attachments are uploaded and downloaded with the web-server attachment routes.
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(list_attachments, mut delete_attachment)
+}
+
+// region:      --- Params
+
+/// Params for the `list_attachments` call.
+#[derive(Deserialize)]
+pub struct ParamsForAttachments {
+    pub task_id: i64,
+    pub list_options: Option<ListOptions>,
+}
+
+impl IntoParams for ParamsForAttachments {}
+
+// endregion:   --- Params
+
+/// List the attachments of the task (the oldest first by default).
+pub async fn list_attachments(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForAttachments,
+) -> Result<DataRpcResult<Vec<Attachment>>> {
+    let ParamsForAttachments {
+        task_id,
+        list_options,
+    } = params;
+
+    let attachments = AttachmentBmc::list_for_task(&ctx, &mm, task_id, list_options).await?;
+
+    Ok(attachments.into())
+}
+
+/// Delete the attachment and its content.
+/// Returns the deleted attachment.
+pub async fn delete_attachment(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsIded,
+) -> Result<DataRpcResult<Attachment>> {
+    let ParamsIded { id } = params;
+
+    let attachment = AttachmentBmc::get(&ctx, &mm, id).await?;
+    AttachmentBmc::delete(&ctx, &mm, id).await?;
+
+    Ok(attachment.into())
+}
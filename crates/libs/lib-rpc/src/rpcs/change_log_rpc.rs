@@ -0,0 +1,22 @@
+use crate::rpcs::prelude::*;
+use lib_core::model::change_log::{ChangeLog, ChangeLogBmc, ChangeLogFilter};
+use lib_core::model::user::UserBmc;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(list_changes)
+}
+
+/// List the change log (admin only).
+///
+/// e.g., `{"filters": {"entity": "task", "entity_id": 1000, "changed_at": {"$gte": "2024-01-01T00:00:00Z"}}}`
+pub async fn list_changes(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsList<ChangeLogFilter>,
+) -> Result<DataRpcResult<Vec<ChangeLog>>> {
+    UserBmc::ensure_admin(&ctx, &mm).await?;
+
+    let changes = ChangeLogBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+    Ok(changes.into())
+}
@@ -0,0 +1,86 @@
+use crate::router::IntoParams;
+use crate::rpcs::prelude::*;
+use lib_core::model::comment::{Comment, CommentBmc, CommentForCreate, CommentForUpdate};
+use modql::filter::ListOptions;
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(
+        mut create_comment,
+        list_comments,
+        mut update_comment,
+        mut delete_comment,
+    )
+}
+
+// region:      --- Params
+
+/// Params for the `list_comments` call.
+#[derive(Deserialize)]
+pub struct ParamsForComments {
+    pub task_id: i64,
+    pub list_options: Option<ListOptions>,
+}
+
+impl IntoParams for ParamsForComments {}
+
+// endregion:   --- Params
+
+/// Comment on a task (or reply to a comment with `parent_id`), as the ctx user.
+pub async fn create_comment(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForCreate<CommentForCreate>,
+) -> Result<DataRpcResult<Comment>> {
+    let ParamsForCreate { data } = params;
+
+    let id = CommentBmc::create(&ctx, &mm, data).await?;
+    let comment = CommentBmc::get(&ctx, &mm, id).await?;
+
+    Ok(comment.into())
+}
+
+/// List the comments of the task (the oldest first by default).
+pub async fn list_comments(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForComments,
+) -> Result<DataRpcResult<Vec<Comment>>> {
+    let ParamsForComments {
+        task_id,
+        list_options,
+    } = params;
+
+    let comments = CommentBmc::list_for_task(&ctx, &mm, task_id, list_options).await?;
+
+    Ok(comments.into())
+}
+
+/// Update the comment (author only).
+pub async fn update_comment(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForUpdate<CommentForUpdate>,
+) -> Result<DataRpcResult<Comment>> {
+    let ParamsForUpdate { id, data, .. } = params;
+
+    CommentBmc::update(&ctx, &mm, id, data).await?;
+    let comment = CommentBmc::get(&ctx, &mm, id).await?;
+
+    Ok(comment.into())
+}
+
+/// Delete the comment and its replies (author or project owners only).
+/// Returns the deleted comment.
+pub async fn delete_comment(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsIded,
+) -> Result<DataRpcResult<Comment>> {
+    let ParamsIded { id } = params;
+
+    let comment = CommentBmc::get(&ctx, &mm, id).await?;
+    CommentBmc::delete(&ctx, &mm, id).await?;
+
+    Ok(comment.into())
+}
@@ -0,0 +1,80 @@
+use crate::router::IntoParams;
+use crate::rpcs::prelude::*;
+use lib_core::model::custom_field::{
+    CustomFieldDef, CustomFieldDefBmc, CustomFieldDefForCreate, CustomFieldDefForUpdate,
+};
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(
+        mut create_custom_field_def,
+        list_custom_field_defs,
+        mut update_custom_field_def,
+        mut delete_custom_field_def,
+    )
+}
+
+// region:      --- Params
+
+/// Params for the `list_custom_field_defs` call.
+#[derive(Deserialize)]
+pub struct ParamsForCustomFieldDefs {
+    pub project_id: i64,
+}
+
+impl IntoParams for ParamsForCustomFieldDefs {}
+
+// endregion:   --- Params
+
+pub async fn create_custom_field_def(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForCreate<CustomFieldDefForCreate>,
+) -> Result<DataRpcResult<CustomFieldDef>> {
+    let ParamsForCreate { data } = params;
+
+    let id = CustomFieldDefBmc::create(&ctx, &mm, data).await?;
+    let field_def = CustomFieldDefBmc::get(&ctx, &mm, id).await?;
+
+    Ok(field_def.into())
+}
+
+/// List the custom field definitions of the project (by name).
+pub async fn list_custom_field_defs(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForCustomFieldDefs,
+) -> Result<DataRpcResult<Vec<CustomFieldDef>>> {
+    let field_defs = CustomFieldDefBmc::list(&ctx, &mm, params.project_id).await?;
+
+    Ok(field_defs.into())
+}
+
+/// Replace the options of a `select` custom field.
+pub async fn update_custom_field_def(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForUpdate<CustomFieldDefForUpdate>,
+) -> Result<DataRpcResult<CustomFieldDef>> {
+    let ParamsForUpdate { id, data, .. } = params;
+
+    CustomFieldDefBmc::update(&ctx, &mm, id, data).await?;
+    let field_def = CustomFieldDefBmc::get(&ctx, &mm, id).await?;
+
+    Ok(field_def.into())
+}
+
+/// Delete the custom field definition, and its values from the project tasks.
+/// Returns the deleted definition.
+pub async fn delete_custom_field_def(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsIded,
+) -> Result<DataRpcResult<CustomFieldDef>> {
+    let ParamsIded { id } = params;
+
+    let field_def = CustomFieldDefBmc::get(&ctx, &mm, id).await?;
+    CustomFieldDefBmc::delete(&ctx, &mm, id).await?;
+
+    Ok(field_def.into())
+}
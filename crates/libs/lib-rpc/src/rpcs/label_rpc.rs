@@ -0,0 +1,71 @@
+use crate::router::IntoParams;
+use crate::rpcs::prelude::*;
+use lib_core::model::label::{Label, LabelBmc, LabelFilter, LabelForCreate, LabelForUpdate};
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(
+        mut create_label,
+        get_label,
+        list_labels,
+        mut update_label,
+        mut delete_label,
+        mut create_labels,
+        mut update_labels,
+        mut delete_labels,
+        mut attach_task_label,
+        mut detach_task_label,
+    )
+}
+
+// region:      --- Params
+
+/// Params for the `attach_task_label` and `detach_task_label` calls.
+#[derive(Deserialize)]
+pub struct ParamsForTaskLabel {
+    pub task_id: i64,
+    pub label_id: i64,
+}
+
+impl IntoParams for ParamsForTaskLabel {}
+
+// endregion:   --- Params
+
+generate_common_rpc_fns!(
+    Bmc: LabelBmc,
+    Entity: Label,
+    ForCreate: LabelForCreate,
+    ForUpdate: LabelForUpdate,
+    Filter: LabelFilter,
+    Suffix: label
+);
+
+/// Attach the label to the task.
+/// Returns the task labels.
+pub async fn attach_task_label(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForTaskLabel,
+) -> Result<DataRpcResult<Vec<Label>>> {
+    let ParamsForTaskLabel { task_id, label_id } = params;
+
+    LabelBmc::attach(&ctx, &mm, task_id, label_id).await?;
+    let labels = LabelBmc::list_for_task(&ctx, &mm, task_id).await?;
+
+    Ok(labels.into())
+}
+
+/// Detach the label from the task.
+/// Returns the task labels.
+pub async fn detach_task_label(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForTaskLabel,
+) -> Result<DataRpcResult<Vec<Label>>> {
+    let ParamsForTaskLabel { task_id, label_id } = params;
+
+    LabelBmc::detach(&ctx, &mm, task_id, label_id).await?;
+    let labels = LabelBmc::list_for_task(&ctx, &mm, task_id).await?;
+
+    Ok(labels.into())
+}
@@ -1,6 +1,9 @@
 /// Create the base crud rpc functions following the common pattern.
 /// - `create_...`
 /// - `get_...`
+/// - `list_...s`, `update_...`, `delete_...`
+/// - `create_...s`, `update_...s`, `delete_...s` (batches, with per item results)
+/// - `list_deleted_...s`, `restore_...`, `purge_...` (for soft delete entities)
 ///
 /// NOTE: Make sure to import the Ctx, ModelManager, ... in the model that uses this macro.
 #[macro_export]
@@ -40,8 +43,22 @@ macro_rules! generate_common_rpc_fns {
                 mm: ModelManager,
                 params: ParamsList<$filter>,
             ) -> Result<DataRpcResult<Vec<$entity>>> {
-                let entities = $bmc::list(&ctx, &mm, params.filters, params.list_options).await?;
-                Ok(entities.into())
+                let ParamsList {
+                    filters,
+                    list_options,
+                    cursor,
+                    with_meta,
+                } = params;
+                let page =
+                    $bmc::list_page(&ctx, &mm, filters, list_options, cursor, with_meta).await?;
+                let meta = page.total.map(|total| ListMeta {
+                    total,
+                    limit: page.limit,
+                    offset: page.offset,
+                });
+                Ok(DataRpcResult::from(page.entities)
+                    .with_next_cursor(page.next_cursor)
+                    .with_meta(meta))
             }
 
             pub async fn [<update_ $suffix>](
@@ -49,8 +66,12 @@ macro_rules! generate_common_rpc_fns {
                 mm: ModelManager,
                 params: ParamsForUpdate<$for_update>,
             ) -> Result<DataRpcResult<$entity>> {
-                let ParamsForUpdate { id, data } = params;
-                $bmc::update(&ctx, &mm, id, data).await?;
+                let ParamsForUpdate {
+                    id,
+                    data,
+                    expected_version,
+                } = params;
+                $bmc::update_with_version(&ctx, &mm, id, data, expected_version).await?;
                 let entity = $bmc::get(&ctx, &mm, id).await?;
                 Ok(entity.into())
             }
@@ -65,6 +86,70 @@ macro_rules! generate_common_rpc_fns {
                 $bmc::delete(&ctx, &mm, id).await?;
                 Ok(entity.into())
             }
+
+            pub async fn [<create_ $suffix s>](
+                ctx: Ctx,
+                mm: ModelManager,
+                params: ParamsForCreateMany<$for_create>,
+            ) -> Result<DataRpcResult<Vec<BatchItemResult>>> {
+                let ParamsForCreateMany { data } = params;
+                let results = $bmc::create_many(&ctx, &mm, data).await?;
+                Ok(results.into())
+            }
+
+            pub async fn [<update_ $suffix s>](
+                ctx: Ctx,
+                mm: ModelManager,
+                params: ParamsForUpdateMany<$for_update, $filter>,
+            ) -> Result<DataRpcResult<Vec<BatchItemResult>>> {
+                let ParamsForUpdateMany { ids, filters, data } = params;
+                let target = BatchTarget::from_ids_or_filter(ids, filters)?;
+                let results = $bmc::update_many(&ctx, &mm, target, data).await?;
+                Ok(results.into())
+            }
+
+            pub async fn [<delete_ $suffix s>](
+                ctx: Ctx,
+                mm: ModelManager,
+                params: ParamsForDeleteMany<$filter>,
+            ) -> Result<DataRpcResult<Vec<BatchItemResult>>> {
+                let ParamsForDeleteMany { ids, filters } = params;
+                let target = BatchTarget::from_ids_or_filter(ids, filters)?;
+                let results = $bmc::delete_many(&ctx, &mm, target).await?;
+                Ok(results.into())
+            }
+
+            pub async fn [<list_deleted_ $suffix s>](
+                ctx: Ctx,
+                mm: ModelManager,
+                params: ParamsList<$filter>,
+            ) -> Result<DataRpcResult<Vec<$entity>>> {
+                let entities =
+                    $bmc::list_deleted(&ctx, &mm, params.filters, params.list_options).await?;
+                Ok(entities.into())
+            }
+
+            pub async fn [<restore_ $suffix>](
+                ctx: Ctx,
+                mm: ModelManager,
+                params: ParamsIded,
+            ) -> Result<DataRpcResult<$entity>> {
+                let ParamsIded { id } = params;
+                $bmc::restore(&ctx, &mm, id).await?;
+                let entity = $bmc::get(&ctx, &mm, id).await?;
+                Ok(entity.into())
+            }
+
+            // Note: The entity might already be soft deleted, so, only the id is returned.
+            pub async fn [<purge_ $suffix>](
+                ctx: Ctx,
+                mm: ModelManager,
+                params: ParamsIded,
+            ) -> Result<DataRpcResult<i64>> {
+                let ParamsIded { id } = params;
+                $bmc::purge(&ctx, &mm, id).await?;
+                Ok(id.into())
+            }
         }
     };
 }
\ No newline at end of file
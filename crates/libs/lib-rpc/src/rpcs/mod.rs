@@ -1,7 +1,16 @@
 use crate::router::RpcRouter;
 
 pub mod task_rpc;
+pub mod task_dependency_rpc;
 pub mod project_rpc;
+pub mod project_member_rpc;
+pub mod label_rpc;
+pub mod custom_field_rpc;
+pub mod comment_rpc;
+pub mod attachment_rpc;
+pub mod search_rpc;
+pub mod user_rpc;
+pub mod change_log_rpc;
 
 mod macro_utils;
 mod prelude;
@@ -9,5 +18,14 @@ mod prelude;
 pub fn all_rpc_router() -> RpcRouter {
     RpcRouter::new()
         .extends(task_rpc::rpc_router())
+        .extends(task_dependency_rpc::rpc_router())
         .extends(project_rpc::rpc_router())
+        .extends(project_member_rpc::rpc_router())
+        .extends(label_rpc::rpc_router())
+        .extends(custom_field_rpc::rpc_router())
+        .extends(comment_rpc::rpc_router())
+        .extends(attachment_rpc::rpc_router())
+        .extends(search_rpc::rpc_router())
+        .extends(user_rpc::rpc_router())
+        .extends(change_log_rpc::rpc_router())
 }
@@ -3,10 +3,13 @@
 
 pub use crate::generate_common_rpc_fns;
 pub use crate::router::RpcRouter;
-pub use crate::rpc_params::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
-pub use crate::rpc_result::DataRpcResult;
+pub use crate::rpc_params::{
+    ParamsForCreate, ParamsForCreateMany, ParamsForDeleteMany, ParamsForUpdate,
+    ParamsForUpdateMany, ParamsIded, ParamsList,
+};
+pub use crate::rpc_result::{DataRpcResult, ListMeta};
 pub use crate::rpc_router;
 pub use crate::Result;
 pub use lib_core::ctx::Ctx;
-pub use lib_core::model::ModelManager;
+pub use lib_core::model::{BatchItemResult, BatchTarget, ModelManager};
 pub use paste::paste;
@@ -0,0 +1,108 @@
+use crate::router::IntoParams;
+use crate::rpcs::prelude::*;
+use lib_core::model::project_member::{ProjectMember, ProjectMemberBmc, ProjectRole};
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(
+        mut invite_project_member,
+        list_project_members,
+        mut update_project_member_role,
+        mut remove_project_member,
+    )
+}
+
+// region:      --- Params
+
+/// Params for the `invite_project_member` and `update_project_member_role` calls.
+#[derive(Deserialize)]
+pub struct ParamsForProjectMember {
+    pub project_id: i64,
+    pub user_id: i64,
+    pub role: ProjectRole,
+}
+
+impl IntoParams for ParamsForProjectMember {}
+
+/// Params for the `remove_project_member` call.
+#[derive(Deserialize)]
+pub struct ParamsForProjectMemberRemove {
+    pub project_id: i64,
+    pub user_id: i64,
+}
+
+impl IntoParams for ParamsForProjectMemberRemove {}
+
+/// Params for the `list_project_members` call.
+#[derive(Deserialize)]
+pub struct ParamsForProjectMembers {
+    pub project_id: i64,
+}
+
+impl IntoParams for ParamsForProjectMembers {}
+
+// endregion:   --- Params
+
+/// Add a user to the project (project owners only).
+pub async fn invite_project_member(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForProjectMember,
+) -> Result<DataRpcResult<ProjectMember>> {
+    let ParamsForProjectMember {
+        project_id,
+        user_id,
+        role,
+    } = params;
+
+    ProjectMemberBmc::add(&ctx, &mm, project_id, user_id, role).await?;
+    let member = ProjectMemberBmc::get(&ctx, &mm, project_id, user_id).await?;
+
+    Ok(member.into())
+}
+
+pub async fn list_project_members(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForProjectMembers,
+) -> Result<DataRpcResult<Vec<ProjectMember>>> {
+    let members = ProjectMemberBmc::list(&ctx, &mm, params.project_id).await?;
+
+    Ok(members.into())
+}
+
+/// Change the role of a project member (project owners only).
+pub async fn update_project_member_role(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForProjectMember,
+) -> Result<DataRpcResult<ProjectMember>> {
+    let ParamsForProjectMember {
+        project_id,
+        user_id,
+        role,
+    } = params;
+
+    ProjectMemberBmc::update_role(&ctx, &mm, project_id, user_id, role).await?;
+    let member = ProjectMemberBmc::get(&ctx, &mm, project_id, user_id).await?;
+
+    Ok(member.into())
+}
+
+/// Remove a member from the project (project owners only).
+/// Returns the removed member.
+pub async fn remove_project_member(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForProjectMemberRemove,
+) -> Result<DataRpcResult<ProjectMember>> {
+    let ParamsForProjectMemberRemove {
+        project_id,
+        user_id,
+    } = params;
+
+    let member = ProjectMemberBmc::get(&ctx, &mm, project_id, user_id).await?;
+    ProjectMemberBmc::remove(&ctx, &mm, project_id, user_id).await?;
+
+    Ok(member.into())
+}
@@ -4,7 +4,19 @@ use lib_core::model::project::{
 };
 
 pub fn rpc_router() -> RpcRouter {
-    rpc_router!(create_project, get_project, list_projects, update_project, delete_project,)
+    rpc_router!(
+        mut create_project,
+        get_project,
+        list_projects,
+        mut update_project,
+        mut delete_project,
+        mut create_projects,
+        mut update_projects,
+        mut delete_projects,
+        list_deleted_projects,
+        mut restore_project,
+        mut purge_project,
+    )
 }
 
 generate_common_rpc_fns!(
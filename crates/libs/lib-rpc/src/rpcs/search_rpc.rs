@@ -0,0 +1,35 @@
+use crate::router::IntoParams;
+use crate::rpcs::prelude::*;
+use lib_core::model::search::{SearchBmc, SearchHit, SearchOptions};
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(search)
+}
+
+// region:      --- Params
+
+/// Params for the `search` call.
+#[derive(Deserialize)]
+pub struct ParamsForSearch {
+    /// The web search syntax text (e.g., `login "time out" -mobile`).
+    pub text: String,
+    pub options: Option<SearchOptions>,
+}
+
+impl IntoParams for ParamsForSearch {}
+
+// endregion:   --- Params
+
+/// Search the tasks, comments, and projects (the most relevant first).
+pub async fn search(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForSearch,
+) -> Result<DataRpcResult<Vec<SearchHit>>> {
+    let ParamsForSearch { text, options } = params;
+
+    let hits = SearchBmc::search(&ctx, &mm, &text, options).await?;
+
+    Ok(hits.into())
+}
@@ -0,0 +1,58 @@
+use crate::router::IntoParams;
+use crate::rpcs::prelude::*;
+use lib_core::model::task::Task;
+use lib_core::model::task_dependency::TaskDependencyBmc;
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(mut add_task_dependency, mut remove_task_dependency)
+}
+
+// region:      --- Params
+
+/// Params for the `add_task_dependency` and `remove_task_dependency` calls.
+#[derive(Deserialize)]
+pub struct ParamsForTaskDependency {
+    pub task_id: i64,
+    pub blocker_task_id: i64,
+}
+
+impl IntoParams for ParamsForTaskDependency {}
+
+// endregion:   --- Params
+
+/// Add the `blocker_task_id` task as a blocker of the `task_id` task.
+/// Returns the task blockers.
+pub async fn add_task_dependency(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForTaskDependency,
+) -> Result<DataRpcResult<Vec<Task>>> {
+    let ParamsForTaskDependency {
+        task_id,
+        blocker_task_id,
+    } = params;
+
+    TaskDependencyBmc::add(&ctx, &mm, task_id, blocker_task_id).await?;
+    let blockers = TaskDependencyBmc::list_blockers(&ctx, &mm, task_id).await?;
+
+    Ok(blockers.into())
+}
+
+/// Remove the `blocker_task_id` task from the blockers of the `task_id` task.
+/// Returns the task blockers.
+pub async fn remove_task_dependency(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForTaskDependency,
+) -> Result<DataRpcResult<Vec<Task>>> {
+    let ParamsForTaskDependency {
+        task_id,
+        blocker_task_id,
+    } = params;
+
+    TaskDependencyBmc::remove(&ctx, &mm, task_id, blocker_task_id).await?;
+    let blockers = TaskDependencyBmc::list_blockers(&ctx, &mm, task_id).await?;
+
+    Ok(blockers.into())
+}
@@ -1,10 +1,82 @@
+use crate::router::{IntoDefaultParams, IntoParams};
 use crate::rpcs::prelude::*;
-use lib_core::model::task::{Task, TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate};
+use lib_core::model::task::{Task, TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate, TaskTree};
+use lib_core::model::task_position::{TaskPlacement, TaskPositionBmc};
+use modql::filter::ListOptions;
+use serde::Deserialize;
+use serde_with::{serde_as, OneOrMany};
 
 pub fn rpc_router() -> RpcRouter {
-    rpc_router!(create_task, get_task, list_tasks, update_task, delete_task,)
+    rpc_router!(
+        mut create_task,
+        get_task,
+        list_tasks,
+        mut update_task,
+        mut delete_task,
+        mut create_tasks,
+        mut update_tasks,
+        mut delete_tasks,
+        list_deleted_tasks,
+        mut restore_task,
+        mut purge_task,
+        list_overdue_tasks,
+        list_task_tree,
+        mut move_task,
+        mut assign_task,
+        mut unassign_task,
+        list_my_tasks,
+    )
 }
 
+// region:      --- Params
+
+/// Params for the `list_overdue_tasks` call (all the accessible projects by default).
+#[derive(Deserialize, Default)]
+pub struct ParamsForOverdueTasks {
+    pub project_id: Option<i64>,
+    pub list_options: Option<ListOptions>,
+}
+
+impl IntoDefaultParams for ParamsForOverdueTasks {}
+
+/// Params for the `move_task` call.
+///
+/// - `parent_task_id`: The new parent task, or `null` for the top level.
+///   When absent, the task is moved to the top level, unless it is a board placement.
+/// - `status`, `after_id`, `before_id`, `force`: The board placement (see `TaskPlacement`).
+#[derive(Deserialize)]
+pub struct ParamsForTaskMove {
+    pub id: i64,
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub parent_task_id: Option<Option<i64>>,
+    #[serde(flatten)]
+    pub placement: TaskPlacement,
+}
+
+impl IntoParams for ParamsForTaskMove {}
+
+/// Params for the `assign_task` call.
+#[derive(Deserialize)]
+pub struct ParamsForTaskAssign {
+    pub id: i64,
+    pub assignee_id: i64,
+}
+
+impl IntoParams for ParamsForTaskAssign {}
+
+/// Params for the `list_my_tasks` call (all the assigned tasks by default).
+#[serde_as]
+#[derive(Deserialize, Default)]
+pub struct ParamsForMyTasks {
+    #[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+    pub filters: Option<Vec<TaskFilter>>,
+    pub list_options: Option<ListOptions>,
+}
+
+impl IntoDefaultParams for ParamsForMyTasks {}
+
+// endregion:   --- Params
+
 generate_common_rpc_fns!(
     Bmc: TaskBmc,
     Entity: Task,
@@ -13,3 +85,100 @@ generate_common_rpc_fns!(
     Filter: TaskFilter,
     Suffix: task
 );
+
+/// List the tasks past their due date and not done (the most overdue first).
+pub async fn list_overdue_tasks(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForOverdueTasks,
+) -> Result<DataRpcResult<Vec<Task>>> {
+    let ParamsForOverdueTasks {
+        project_id,
+        list_options,
+    } = params;
+    let tasks = TaskBmc::list_overdue(&ctx, &mm, project_id, list_options).await?;
+
+    Ok(tasks.into())
+}
+
+/// Returns the task with all its subtasks (at any depth), and their done/total roll-up.
+pub async fn list_task_tree(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsIded,
+) -> Result<DataRpcResult<TaskTree>> {
+    let tree = TaskBmc::list_tree(&ctx, &mm, params.id).await?;
+
+    Ok(tree.into())
+}
+
+/// Move the task under another task of its project (or to the top level),
+/// and/or to another place of the board (between two tasks, or another status column).
+pub async fn move_task(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForTaskMove,
+) -> Result<DataRpcResult<Task>> {
+    let ParamsForTaskMove {
+        id,
+        parent_task_id,
+        placement,
+    } = params;
+    let is_placement =
+        placement.status.is_some() || placement.after_id.is_some() || placement.before_id.is_some();
+
+    match parent_task_id {
+        Some(parent_task_id) => TaskBmc::move_to_parent(&ctx, &mm, id, parent_task_id).await?,
+        None if !is_placement => TaskBmc::move_to_parent(&ctx, &mm, id, None).await?,
+        None => (),
+    }
+    if is_placement {
+        TaskPositionBmc::place(&ctx, &mm, id, placement).await?;
+    }
+    let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+    Ok(task.into())
+}
+
+/// Assign the task to a user with access to its project.
+pub async fn assign_task(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForTaskAssign,
+) -> Result<DataRpcResult<Task>> {
+    let ParamsForTaskAssign { id, assignee_id } = params;
+
+    TaskBmc::assign(&ctx, &mm, id, Some(assignee_id)).await?;
+    let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+    Ok(task.into())
+}
+
+/// Remove the assignee of the task.
+pub async fn unassign_task(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsIded,
+) -> Result<DataRpcResult<Task>> {
+    let ParamsIded { id } = params;
+
+    TaskBmc::assign(&ctx, &mm, id, None).await?;
+    let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+    Ok(task.into())
+}
+
+/// List the tasks assigned to the ctx user (the soonest due first).
+pub async fn list_my_tasks(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForMyTasks,
+) -> Result<DataRpcResult<Vec<Task>>> {
+    let ParamsForMyTasks {
+        filters,
+        list_options,
+    } = params;
+    let tasks = TaskBmc::list_mine(&ctx, &mm, filters, list_options).await?;
+
+    Ok(tasks.into())
+}
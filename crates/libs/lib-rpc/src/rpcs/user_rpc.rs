@@ -0,0 +1,84 @@
+use crate::router::{IntoDefaultParams, IntoParams};
+use crate::rpcs::prelude::*;
+use lib_core::model::user::{OwnedProjectsAction, User, UserBmc};
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+    rpc_router!(
+        // -- Admin
+        mut delete_user,
+        // -- Self-service
+        mut delete_my_account,
+        mut cancel_delete_my_account,
+    )
+}
+
+// region:      --- Params
+
+/// Params for the admin `delete_user` call.
+#[derive(Deserialize)]
+pub struct ParamsForUserDelete {
+    pub id: i64,
+    #[serde(default)]
+    pub owned_projects: OwnedProjectsAction,
+}
+
+impl IntoParams for ParamsForUserDelete {}
+
+/// Params for the self-service `delete_my_account` call.
+#[derive(Deserialize, Default)]
+pub struct ParamsForAccountDelete {
+    #[serde(default)]
+    pub owned_projects: OwnedProjectsAction,
+}
+
+impl IntoDefaultParams for ParamsForAccountDelete {}
+
+// endregion:   --- Params
+
+// region:      --- Admin
+
+/// Immediately delete and anonymize a user (admin only).
+pub async fn delete_user(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForUserDelete,
+) -> Result<DataRpcResult<i64>> {
+    let ParamsForUserDelete { id, owned_projects } = params;
+
+    UserBmc::ensure_admin(&ctx, &mm).await?;
+    UserBmc::delete(&ctx, &mm, id, owned_projects).await?;
+
+    Ok(id.into())
+}
+
+// endregion:   --- Admin
+
+// region:      --- Self-service
+
+/// Request the deletion of the ctx user account.
+/// The account is scrubbed once the grace period is over (see `UserBmc::scrub_due_deletions`).
+pub async fn delete_my_account(
+    ctx: Ctx,
+    mm: ModelManager,
+    params: ParamsForAccountDelete,
+) -> Result<DataRpcResult<User>> {
+    let ParamsForAccountDelete { owned_projects } = params;
+    let id = ctx.user_id();
+
+    UserBmc::request_deletion(&ctx, &mm, id, owned_projects).await?;
+    let user: User = UserBmc::get(&ctx, &mm, id).await?;
+
+    Ok(user.into())
+}
+
+pub async fn cancel_delete_my_account(ctx: Ctx, mm: ModelManager) -> Result<DataRpcResult<User>> {
+    let id = ctx.user_id();
+
+    UserBmc::cancel_deletion(&ctx, &mm, id).await?;
+    let user: User = UserBmc::get(&ctx, &mm, id).await?;
+
+    Ok(user.into())
+}
+
+// endregion:   --- Self-service
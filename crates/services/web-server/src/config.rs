@@ -1,4 +1,4 @@
-use lib_utils::envs::get_env;
+use lib_utils::envs::{get_env, get_env_parse};
 use std::sync::OnceLock;
 
 pub fn web_config() -> &'static WebConfig {
@@ -13,12 +13,21 @@ pub fn web_config() -> &'static WebConfig {
 #[allow(non_snake_case)]
 pub struct WebConfig {
     pub WEB_FOLDER: String,
+
+    // -- Rpc
+    /// Run each mutating rpc call in its own transaction (see `routes_rpc`).
+    pub RPC_MUTATION_TXN: bool,
+
+    // -- Jobs
+    pub USER_DELETION_GRACE_SEC: u64,
 }
 
 impl WebConfig {
     fn load_from_env() -> lib_utils::envs::Result<WebConfig> {
         Ok(WebConfig {
             WEB_FOLDER: get_env("SERVICE_WEB_FOLDER")?,
+            RPC_MUTATION_TXN: get_env_parse("SERVICE_RPC_MUTATION_TXN")?,
+            USER_DELETION_GRACE_SEC: get_env_parse("SERVICE_USER_DELETION_GRACE_SEC")?,
         })
     }
 }
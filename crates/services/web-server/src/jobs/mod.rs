@@ -0,0 +1,53 @@
+//! Background jobs of the web-server, spawned at startup.
+
+use crate::config::web_config;
+use lib_core::ctx::Ctx;
+use lib_core::model::task_position::TaskPositionBmc;
+use lib_core::model::user::UserBmc;
+use lib_core::model::ModelManager;
+use std::time::Duration;
+use tracing::{error, info};
+
+const USER_DELETION_SCRUB_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const TASK_POSITION_REBALANCE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Periodically scrub the accounts whose self-service deletion grace period is over.
+pub fn spawn_user_deletion_scrub(mm: ModelManager) {
+    let grace = Duration::from_secs(web_config().USER_DELETION_GRACE_SEC);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(USER_DELETION_SCRUB_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            match UserBmc::scrub_due_deletions(&Ctx::root_ctx(), &mm, grace).await {
+                Ok(ids) if !ids.is_empty() => {
+                    info!("{:<12} - user_deletion_scrub - deleted: {ids:?}", "JOBS")
+                }
+                Ok(_) => (),
+                Err(ex) => error!("{:<12} - user_deletion_scrub - {ex:?}", "JOBS"),
+            }
+        }
+    });
+}
+
+/// Periodically rebalance the task board columns with too long position keys.
+pub fn spawn_task_position_rebalance(mm: ModelManager) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TASK_POSITION_REBALANCE_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            match TaskPositionBmc::rebalance_long_positions(&mm).await {
+                Ok(count) if count > 0 => {
+                    info!(
+                        "{:<12} - task_position_rebalance - columns: {count}",
+                        "JOBS"
+                    )
+                }
+                Ok(_) => (),
+                Err(ex) => error!("{:<12} - task_position_rebalance - {ex:?}", "JOBS"),
+            }
+        }
+    });
+}
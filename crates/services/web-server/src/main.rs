@@ -2,6 +2,7 @@
 
 mod config;
 mod error;
+mod jobs;
 mod log;
 mod web;
 
@@ -11,7 +12,7 @@ use config::web_config;
 use crate::web::mw_auth::{mw_ctx_require, mw_ctx_resolve};
 use crate::web::mw_res_map::mw_response_map;
 use crate::web::mw_stamp;
-use crate::web::{routes_login, routes_rpc, routes_static};
+use crate::web::{routes_attachment, routes_login, routes_rpc, routes_static};
 use axum::{middleware, Router};
 use lib_core::_dev_utils;
 use lib_core::model::ModelManager;
@@ -35,13 +36,19 @@ async fn main() -> Result<()> {
     // Initilize ModelManager
     let mm = ModelManager::new().await?;
 
+    // -- Start background jobs
+    jobs::spawn_user_deletion_scrub(mm.clone());
+    jobs::spawn_task_position_rebalance(mm.clone());
+
     // -- Define routes
     let routes_rpc =
         routes_rpc::routes(mm.clone()).route_layer(middleware::from_fn(mw_ctx_require));
+    let routes_attachment =
+        routes_attachment::routes(mm.clone()).route_layer(middleware::from_fn(mw_ctx_require));
 
     let routes_all = Router::new()
         .merge(routes_login::routes(mm.clone()))
-        .nest("/api", routes_rpc)
+        .nest("/api", routes_rpc.merge(routes_attachment))
         .layer(middleware::map_response(mw_response_map))
         .layer(middleware::from_fn_with_state(mm.clone(), mw_ctx_resolve))
         .layer(CookieManagerLayer::new())
@@ -33,6 +33,20 @@ pub enum Error {
     // -- ReqStamp
     ReqStampNotInResponseExt,
 
+    // -- Attachment
+    AttachmentFileMissing,
+    AttachmentUploadFail {
+        cause: String,
+    },
+
+    // -- Rpc txn
+    /// The failing call of a `txn` rpc call (with its cause as web error).
+    RpcTxnCallFail {
+        index: usize,
+        method: String,
+        cause: Box<Error>,
+    },
+
     // -- Modules
     #[from]
     Model(model::Error),
@@ -95,12 +109,162 @@ impl Error {
             // -- Auth
             Self::CtxExt(_) => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
 
-            // -- Model
-            Self::Model(model::Error::EntityNotFound { entity, id }) => (
+            // -- Attachment
+            Self::AttachmentFileMissing | Self::AttachmentUploadFail { .. } => {
+                (StatusCode::BAD_REQUEST, ClientError::INVALID_ATTACHMENT_UPLOAD)
+            }
+
+            // -- Rpc params
+            Self::Rpc(lib_rpc::Error::RpcIntoParamsMissing)
+            | Self::Rpc(lib_rpc::Error::RpcIntoParamsInvalid(_)) => {
+                (StatusCode::BAD_REQUEST, ClientError::INVALID_PARAMS)
+            }
+
+            // -- Rpc txn
+            Self::RpcTxnCallFail { index, cause, .. } => {
+                let (status_code, client_error) = cause.client_status_and_error();
+                (
+                    status_code,
+                    ClientError::TXN_CALL_FAIL {
+                        index: *index,
+                        error: Box::new(client_error),
+                    },
+                )
+            }
+            Self::Rpc(lib_rpc::Error::RpcTxnCallsOverMax { .. })
+            | Self::Rpc(lib_rpc::Error::RpcTxnRefInvalid { .. }) => {
+                (StatusCode::BAD_REQUEST, ClientError::INVALID_TXN_PARAMS)
+            }
+
+            // -- Rpc dry run
+            Self::Rpc(lib_rpc::Error::RpcDryRunNotMutating { method }) => (
                 StatusCode::BAD_REQUEST,
-                ClientError::ENTITY_NOT_FOUND { entity, id: *id },
+                ClientError::DRY_RUN_NOT_MUTATING {
+                    method: method.clone(),
+                },
             ),
 
+            // -- Model (directly or through the rpc layer)
+            Self::Model(model_error) | Self::Rpc(lib_rpc::Error::Model(model_error)) => {
+                match model_error {
+                    model::Error::EntityNotFound { entity, id } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::ENTITY_NOT_FOUND { entity, id: *id },
+                    ),
+                    model::Error::ListLimitOverMax { .. }
+                    | model::Error::ListOrderByUnknown { .. }
+                    | model::Error::ListCursorInvalid { .. }
+                    | model::Error::ListCursorWithMultiOrderBys
+                    | model::Error::ListCursorWithOffset => {
+                        (StatusCode::BAD_REQUEST, ClientError::INVALID_LIST_PARAMS)
+                    }
+                    model::Error::BatchSizeOverMax { .. }
+                    | model::Error::BatchTargetInvalid
+                    | model::Error::BatchFilterEmpty { .. } => {
+                        (StatusCode::BAD_REQUEST, ClientError::INVALID_BATCH_PARAMS)
+                    }
+                    model::Error::UserNotAdmin { .. } | model::Error::AccessDenied { .. } => {
+                        (StatusCode::FORBIDDEN, ClientError::ACCESS_DENIED)
+                    }
+                    model::Error::ProjectMemberNotFound {
+                        project_id,
+                        user_id,
+                    } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::PROJECT_MEMBER_NOT_FOUND {
+                            project_id: *project_id,
+                            user_id: *user_id,
+                        },
+                    ),
+                    model::Error::ProjectMemberAlreadyExists {
+                        project_id,
+                        user_id,
+                    } => (
+                        StatusCode::CONFLICT,
+                        ClientError::PROJECT_MEMBER_ALREADY_EXISTS {
+                            project_id: *project_id,
+                            user_id: *user_id,
+                        },
+                    ),
+                    model::Error::LabelProjectMismatch { label_id, task_id } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::LABEL_PROJECT_MISMATCH {
+                            label_id: *label_id,
+                            task_id: *task_id,
+                        },
+                    ),
+                    model::Error::CommentParentMismatch { parent_id, task_id } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::COMMENT_PARENT_MISMATCH {
+                            parent_id: *parent_id,
+                            task_id: *task_id,
+                        },
+                    ),
+                    model::Error::TaskParentProjectMismatch { id, parent_task_id }
+                    | model::Error::TaskParentCycle { id, parent_task_id } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::TASK_PARENT_INVALID {
+                            id: *id,
+                            parent_task_id: *parent_task_id,
+                        },
+                    ),
+                    model::Error::TaskBlocked { id } => {
+                        (StatusCode::CONFLICT, ClientError::TASK_BLOCKED { id: *id })
+                    }
+                    model::Error::TaskAssigneeNoAccess { assignee_id, .. } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::TASK_ASSIGNEE_INVALID {
+                            assignee_id: *assignee_id,
+                        },
+                    ),
+                    model::Error::TaskPlacementInvalid { neighbor_id, .. } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::TASK_PLACEMENT_INVALID {
+                            neighbor_id: *neighbor_id,
+                        },
+                    ),
+                    model::Error::TaskDependencyCycle {
+                        task_id,
+                        blocker_task_id,
+                    } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::TASK_DEPENDENCY_CYCLE {
+                            task_id: *task_id,
+                            blocker_task_id: *blocker_task_id,
+                        },
+                    ),
+                    model::Error::CustomFieldOptionsInvalid { name }
+                    | model::Error::CustomFieldUnknown { name, .. }
+                    | model::Error::CustomFieldValueInvalid { name, .. } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::CUSTOM_FIELD_INVALID {
+                            name: Some(name.to_string()),
+                        },
+                    ),
+                    model::Error::TaskCustomNotObject
+                    | model::Error::CustomFilterPathInvalid { .. } => (
+                        StatusCode::BAD_REQUEST,
+                        ClientError::CUSTOM_FIELD_INVALID { name: None },
+                    ),
+                    model::Error::VersionConflict {
+                        entity,
+                        id,
+                        current,
+                    } => (
+                        StatusCode::CONFLICT,
+                        ClientError::VERSION_CONFLICT {
+                            entity,
+                            id: *id,
+                            current: *current,
+                        },
+                    ),
+                    _ => (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        ClientError::SERVICE_ERROR,
+                    ),
+                }
+            }
+
             // -- Fallback
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -116,7 +280,26 @@ impl Error {
 pub enum ClientError {
     LOGIN_FAIL,
     NO_AUTH,
+    ACCESS_DENIED,
     ENTITY_NOT_FOUND { entity: &'static str, id: i64 },
+    VERSION_CONFLICT { entity: &'static str, id: i64, current: i64 },
+    PROJECT_MEMBER_NOT_FOUND { project_id: i64, user_id: i64 },
+    PROJECT_MEMBER_ALREADY_EXISTS { project_id: i64, user_id: i64 },
+    LABEL_PROJECT_MISMATCH { label_id: i64, task_id: i64 },
+    COMMENT_PARENT_MISMATCH { parent_id: i64, task_id: i64 },
+    TASK_PARENT_INVALID { id: i64, parent_task_id: i64 },
+    TASK_BLOCKED { id: i64 },
+    TASK_ASSIGNEE_INVALID { assignee_id: i64 },
+    TASK_PLACEMENT_INVALID { neighbor_id: i64 },
+    TASK_DEPENDENCY_CYCLE { task_id: i64, blocker_task_id: i64 },
+    CUSTOM_FIELD_INVALID { name: Option<String> },
+    INVALID_PARAMS,
+    INVALID_LIST_PARAMS,
+    INVALID_BATCH_PARAMS,
+    INVALID_ATTACHMENT_UPLOAD,
+    INVALID_TXN_PARAMS,
+    TXN_CALL_FAIL { index: usize, error: Box<ClientError> },
+    DRY_RUN_NOT_MUTATING { method: String },
     SERVICE_ERROR,
 }
 
@@ -0,0 +1,153 @@
+use crate::web::mw_auth::CtxW;
+use crate::web::{Error, Result};
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Multipart, Path, State};
+use axum::http::header::{
+    CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS,
+};
+use axum::http::HeaderValue;
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::TryStreamExt;
+use lib_core::model::attachment::{AttachmentBmc, AttachmentForCreate};
+use lib_core::model::{self, storage, ModelManager};
+use serde_json::{json, Value};
+use std::io;
+use tokio_util::io::{ReaderStream, StreamReader};
+use tracing::debug;
+
+/// Max size of an upload request (the file and its multipart envelope).
+const UPLOAD_MAX_SIZE: usize = 25 * 1024 * 1024;
+
+/// The multipart field of the uploaded file.
+const UPLOAD_FILE_FIELD: &str = "file";
+
+// Axum router for the '/api' attachment routes
+// (the listing and delete are in the rpc `attachment_rpc`)
+pub fn routes(mm: ModelManager) -> Router {
+    Router::new()
+        .route(
+            "/tasks/:task_id/attachments",
+            post(upload_handler).layer(DefaultBodyLimit::max(UPLOAD_MAX_SIZE)),
+        )
+        .route("/attachments/:id", get(download_handler))
+        .with_state(mm)
+}
+
+// region:     Upload
+
+/// Attach the multipart `file` field to the task.
+async fn upload_handler(
+    State(mm): State<ModelManager>,
+    ctx: CtxW,
+    Path(task_id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<Json<Value>> {
+    debug!("{:<12} - upload_handler", "HANDLER");
+    let ctx = ctx.0;
+
+    // -- Find the file field
+    let field = loop {
+        let field = multipart
+            .next_field()
+            .await
+            .map_err(|ex| Error::AttachmentUploadFail {
+                cause: ex.to_string(),
+            })?
+            .ok_or(Error::AttachmentFileMissing)?;
+        if field.name() == Some(UPLOAD_FILE_FIELD) {
+            break field;
+        }
+    };
+
+    let attachment_c = AttachmentForCreate {
+        task_id,
+        filename: field.file_name().unwrap_or_default().to_string(),
+        content_type: field.content_type().map(str::to_string),
+    };
+
+    // -- Stream the file to the storage
+    //    (the multipart errors, e.g., body limit reached, are mapped to InvalidData)
+    let stream = field.map_err(|ex| io::Error::new(io::ErrorKind::InvalidData, ex));
+    let mut reader = StreamReader::new(stream);
+    let id = AttachmentBmc::create(&ctx, &mm, attachment_c, &mut reader)
+        .await
+        .map_err(|model_error| match model_error {
+            model::Error::Storage(storage::Error::Io(ex))
+                if ex.kind() == io::ErrorKind::InvalidData =>
+            {
+                Error::AttachmentUploadFail {
+                    cause: ex.to_string(),
+                }
+            }
+            model_error => Error::Model(model_error),
+        })?;
+    let attachment = AttachmentBmc::get(&ctx, &mm, id).await?;
+
+    // -- Create the success body
+    let body = Json(json!({
+        "result": {
+            "data": attachment
+        }
+    }));
+
+    Ok(body)
+}
+
+// endregion:  Upload
+
+// region:     Download
+
+/// Stream the attachment content.
+async fn download_handler(
+    State(mm): State<ModelManager>,
+    ctx: CtxW,
+    Path(id): Path<i64>,
+) -> Result<Response> {
+    debug!("{:<12} - download_handler", "HANDLER");
+    let ctx = ctx.0;
+
+    let (attachment, reader) = AttachmentBmc::open(&ctx, &mm, id).await?;
+
+    let mut res = Response::new(Body::from_stream(ReaderStream::new(reader)));
+    let headers = res.headers_mut();
+    let content_type = HeaderValue::from_str(&attachment.content_type)
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+    headers.insert(CONTENT_TYPE, content_type);
+    headers.insert(CONTENT_LENGTH, HeaderValue::from(attachment.size));
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    if let Ok(disposition) = HeaderValue::from_str(&content_disposition(&attachment.filename)) {
+        headers.insert(CONTENT_DISPOSITION, disposition);
+    }
+
+    Ok(res)
+}
+
+/// Returns the `attachment` content disposition, with the ascii `filename`
+/// and the utf-8 `filename*` (RFC 6266).
+fn content_disposition(filename: &str) -> String {
+    let ascii_filename: String = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut encoded_filename = String::new();
+    for byte in filename.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded_filename.push(byte as char);
+        } else {
+            encoded_filename.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    format!("attachment; filename=\"{ascii_filename}\"; filename*=UTF-8''{encoded_filename}")
+}
+
+// endregion:  Download
@@ -1,10 +1,12 @@
+use crate::config::web_config;
 use crate::web::mw_auth::CtxW;
 use axum::extract::State;
 use axum::response::{IntoResponse, Response};
 use axum::routing::post;
 use axum::{Json, Router};
+use lib_core::ctx::Ctx;
 use lib_core::model::ModelManager;
-use lib_rpc::router::RpcRouter;
+use lib_rpc::router::{RpcRouter, TXN_METHOD};
 use lib_rpc::{all_rpc_router, RpcRequest, RpcResources};
 use serde_json::{json, Value};
 use std::sync::Arc;
@@ -52,10 +54,23 @@ async fn rpc_axum_handler(
     };
     let rpc_method = &rpc_info.method;
     let rpc_params = rpc_req.params;
-    let rpc_resources = RpcResources { ctx: Some(ctx), mm };
 
     // -- Exec Rpc Route
-    let res = rpc_router.call(rpc_method, rpc_resources, rpc_params).await;
+    //    (the mutating ones in a request transaction, when `RPC_MUTATION_TXN`)
+    let res = if rpc_req.dry_run {
+        let rpc_resources = RpcResources { ctx: Some(ctx), mm };
+        rpc_router
+            .call_dry_run(rpc_method, rpc_resources, rpc_params)
+            .await
+    } else if rpc_method == TXN_METHOD {
+        let rpc_resources = RpcResources { ctx: Some(ctx), mm };
+        rpc_router.call_txn(rpc_resources, rpc_params).await
+    } else if web_config().RPC_MUTATION_TXN && rpc_router.is_mutating(rpc_method) {
+        call_in_txn(&rpc_router, rpc_method, ctx, mm, rpc_params).await
+    } else {
+        let rpc_resources = RpcResources { ctx: Some(ctx), mm };
+        rpc_router.call(rpc_method, rpc_resources, rpc_params).await
+    };
 
     // -- Build Rpc Success Response
     let res = res.map(|v| {
@@ -67,9 +82,115 @@ async fn rpc_axum_handler(
     });
 
     // -- Create and Update Axum Response
-    let res: crate::web::Result<_> = res.map_err(crate::web::Error::from);
+    let res: crate::web::Result<_> = res.map_err(|rpc_error| match rpc_error {
+        lib_rpc::Error::RpcTxnCallFail {
+            index,
+            method,
+            cause,
+        } => crate::web::Error::RpcTxnCallFail {
+            index,
+            method,
+            cause: Box::new(crate::web::Error::from(*cause)),
+        },
+        rpc_error => crate::web::Error::from(rpc_error),
+    });
     let mut res = res.into_response();
     res.extensions_mut().insert(Arc::new(rpc_info));
 
     res
 }
+
+/// Call the rpc method with a new transaction,
+/// committed only when the handler returns Ok (rolled back otherwise).
+async fn call_in_txn(
+    rpc_router: &RpcRouter,
+    rpc_method: &str,
+    ctx: Ctx,
+    mm: ModelManager,
+    rpc_params: Option<Value>,
+) -> lib_rpc::Result<Value> {
+    let mm = mm.new_with_txn()?;
+
+    mm.in_txn(|mm| async move {
+        let rpc_resources = RpcResources { ctx: Some(ctx), mm };
+        rpc_router.call(rpc_method, rpc_resources, rpc_params).await
+    })
+    .await
+}
+
+// region:      --- Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use lib_core::_dev_utils;
+    use lib_core::model::change_log::ChangeLogBmc;
+    use lib_core::model::project::{ProjectBmc, ProjectFilter, ProjectForCreate};
+    use lib_core::model::task::{Task, TaskBmc};
+    use lib_rpc::{rpc_router, ParamsForCreate};
+    use modql::filter::ListOptions;
+    use serial_test::serial;
+
+    /// A mutating handler with a write before its failure.
+    async fn create_project_then_fail(
+        ctx: Ctx,
+        mm: ModelManager,
+        params: ParamsForCreate<ProjectForCreate>,
+    ) -> lib_rpc::Result<i64> {
+        let id = ProjectBmc::create(&ctx, &mm, params.data).await?;
+        let _task: Task = TaskBmc::get(&ctx, &mm, -1).await?;
+
+        Ok(id)
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn test_call_in_txn_err_rollback() -> Result<()> {
+        // -- Setup & Fixtures
+        let mm = _dev_utils::init_test().await;
+        let ctx = Ctx::root_ctx();
+        let fx_name = "test_call_in_txn_err_rollback project";
+        let fx_params = json!({"data": {"name": fx_name}});
+        let rpc_router = rpc_router!(mut create_project_then_fail);
+        let last_change_options = ListOptions {
+            order_bys: Some("!id".into()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let fx_last_changes =
+            ChangeLogBmc::list(&ctx, &mm, None, Some(last_change_options.clone())).await?;
+
+        // -- Exec
+        let res = call_in_txn(
+            &rpc_router,
+            "create_project_then_fail",
+            ctx.clone(),
+            mm.clone(),
+            Some(fx_params),
+        )
+        .await;
+
+        // -- Check
+        assert!(
+            matches!(res, Err(lib_rpc::Error::Model(_))),
+            "the handler should fail"
+        );
+        let filter = ProjectFilter {
+            name: Some(fx_name.into()),
+            ..Default::default()
+        };
+        let projects = ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+        assert!(projects.is_empty(), "the project should be rolled back");
+        let last_changes = ChangeLogBmc::list(&ctx, &mm, None, Some(last_change_options)).await?;
+        assert_eq!(
+            last_changes.first().map(|change| change.id),
+            fx_last_changes.first().map(|change| change.id),
+            "the change log should be rolled back"
+        );
+
+        Ok(())
+    }
+}
+
+// endregion:   --- Tests
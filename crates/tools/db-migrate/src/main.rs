@@ -0,0 +1,47 @@
+//! Apply or revert the `sql/migrations` on the `SERVICE_DB_URL` database.
+//!
+//! Usage:
+//!
+//! - `cargo run -p db-migrate -- up`               Apply all pending migrations.
+//! - `cargo run -p db-migrate -- down <version>`   Revert the migrations above `<version>` (0 for all).
+//! - `cargo run -p db-migrate -- status`           List the migrations and when they were applied.
+
+use anyhow::{bail, Context, Result};
+use lib_core::model::ModelManager;
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .without_time()
+        .with_target(false)
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mm = ModelManager::new_without_migrate().await?;
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["up"] => {
+            let versions = mm.migrate_up().await?;
+            println!("Applied migrations: {versions:?}");
+        }
+        ["down", version] => {
+            let version: i64 = version.parse().context("version must be a number")?;
+            let versions = mm.migrate_down(version).await?;
+            println!("Reverted migrations: {versions:?}");
+        }
+        ["status"] => {
+            for status in mm.migration_status().await? {
+                let applied_on = status
+                    .applied_on
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "pending".to_string());
+                println!("{:>4} {:<32} {applied_on}", status.version, status.name);
+            }
+        }
+        _ => bail!("usage: db-migrate (up | down <version> | status)"),
+    }
+
+    Ok(())
+}
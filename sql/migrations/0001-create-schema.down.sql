@@ -0,0 +1,5 @@
+---- Base app schema (down)
+
+DROP TABLE IF EXISTS "task";
+DROP TABLE IF EXISTS project;
+DROP TABLE IF EXISTS "user";
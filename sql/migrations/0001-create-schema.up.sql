@@ -1,10 +1,12 @@
 ---- Base app schema
+-- NOTE: Written with `IF NOT EXISTS` so that databases created before the migration
+--       system (from the old `sql/dev_initial/01-create-schema.sql`) can adopt it as-is.
 
 -- User
-CREATE TABLE "user" (
+CREATE TABLE IF NOT EXISTS "user" (
   id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
   username VARCHAR(128) NOT NULL UNIQUE,
-  
+
   -- auth
   pwd VARCHAR(256),
   pwd_salt UUID NOT NULL DEFAULT gen_random_uuid(),
@@ -14,11 +16,11 @@ CREATE TABLE "user" (
   cid BIGINT NOT NULL,
   ctime TIMESTAMP WITH TIME ZONE NOT NULL,
   mid BIGINT NOT NULL,
-  mtime TIMESTAMP WITH TIME ZONE NOT NULL  
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL
 );
 
 -- Project
-CREATE TABLE project (
+CREATE TABLE IF NOT EXISTS project (
   -- PK
   id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
 
@@ -30,11 +32,11 @@ CREATE TABLE project (
   cid BIGINT NOT NULL,
   ctime TIMESTAMP WITH TIME ZONE NOT NULL,
   mid BIGINT NOT NULL,
-  mtime TIMESTAMP WITH TIME ZONE NOT NULL  
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL
 );
 
 -- Task
-CREATE TABLE "task" (
+CREATE TABLE IF NOT EXISTS "task" (
   -- PK
   id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
 
@@ -49,7 +51,13 @@ CREATE TABLE "task" (
   cid BIGINT NOT NULL,
   ctime TIMESTAMP WITH TIME ZONE NOT NULL,
   mid BIGINT NOT NULL,
-  mtime TIMESTAMP WITH TIME ZONE NOT NULL  
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL
 );
 
-ALTER TABLE task ADD CONSTRAINT fk_project FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE;
\ No newline at end of file
+DO $$
+BEGIN
+  IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'fk_project') THEN
+    ALTER TABLE task ADD CONSTRAINT fk_project FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE;
+  END IF;
+END
+$$;
@@ -0,0 +1,9 @@
+---- Soft delete (down)
+
+ALTER TABLE "task"
+  DROP COLUMN deleted_at,
+  DROP COLUMN deleted_by;
+
+ALTER TABLE project
+  DROP COLUMN deleted_at,
+  DROP COLUMN deleted_by;
@@ -0,0 +1,9 @@
+---- Soft delete (deleted_at, deleted_by) for project and task
+
+ALTER TABLE project
+  ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE,
+  ADD COLUMN deleted_by BIGINT;
+
+ALTER TABLE "task"
+  ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE,
+  ADD COLUMN deleted_by BIGINT;
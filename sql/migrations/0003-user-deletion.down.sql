@@ -0,0 +1,8 @@
+---- User admin flag and account deletion (down)
+
+ALTER TABLE "user"
+  DROP COLUMN is_admin,
+  DROP COLUMN deletion_requested_at,
+  DROP COLUMN deletion_transfer_to,
+  DROP COLUMN deleted_at,
+  DROP COLUMN deleted_by;
@@ -0,0 +1,10 @@
+---- User admin flag and account deletion (request, soft delete)
+
+ALTER TABLE "user"
+  ADD COLUMN is_admin BOOL NOT NULL DEFAULT FALSE,
+  -- Self-service deletion request (scrubbed after a grace period)
+  ADD COLUMN deletion_requested_at TIMESTAMP WITH TIME ZONE,
+  ADD COLUMN deletion_transfer_to BIGINT,
+  -- Soft delete
+  ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE,
+  ADD COLUMN deleted_by BIGINT;
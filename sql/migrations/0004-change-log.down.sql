@@ -0,0 +1,3 @@
+---- Change log (down)
+
+DROP TABLE IF EXISTS change_log;
@@ -0,0 +1,20 @@
+---- Change log (audit of every model mutation)
+
+CREATE TABLE change_log (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Changed entity
+  entity VARCHAR(128) NOT NULL,
+  entity_id BIGINT NOT NULL,
+
+  -- Change
+  user_id BIGINT NOT NULL,
+  op VARCHAR(32) NOT NULL,
+  changed_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  diff JSONB NOT NULL
+);
+
+CREATE INDEX change_log_entity_idx ON change_log (entity, entity_id);
+CREATE INDEX change_log_user_id_idx ON change_log (user_id);
+CREATE INDEX change_log_changed_at_idx ON change_log (changed_at);
@@ -0,0 +1,7 @@
+---- Row version (down)
+
+ALTER TABLE project
+  DROP COLUMN version;
+
+ALTER TABLE "task"
+  DROP COLUMN version;
@@ -0,0 +1,7 @@
+---- Row version (optimistic concurrency) for project and task
+
+ALTER TABLE project
+  ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
+
+ALTER TABLE "task"
+  ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
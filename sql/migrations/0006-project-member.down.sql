@@ -0,0 +1,3 @@
+---- Project members (down)
+
+DROP TABLE IF EXISTS project_member;
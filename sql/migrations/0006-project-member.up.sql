@@ -0,0 +1,24 @@
+---- Project members (users sharing a project, with their role)
+-- NOTE: The project `owner_id` user is always an owner, without a member row.
+
+CREATE TABLE project_member (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  project_id BIGINT NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+  user_id BIGINT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+
+  -- Properties
+  role VARCHAR(32) NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP WITH TIME ZONE NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+  CONSTRAINT project_member_project_user_key UNIQUE (project_id, user_id)
+);
+
+CREATE INDEX project_member_user_id_idx ON project_member (user_id);
@@ -0,0 +1,10 @@
+---- Task details (down)
+
+DROP TRIGGER IF EXISTS task_sync_done_status ON "task";
+DROP FUNCTION IF EXISTS task_sync_done_status();
+
+ALTER TABLE "task"
+  DROP COLUMN description,
+  DROP COLUMN status,
+  DROP COLUMN priority,
+  DROP COLUMN due_at;
@@ -0,0 +1,36 @@
+---- Task details (description, status, priority, due date)
+-- NOTE: `status` is the task state, `done` is kept (in sync) for the existing clients.
+
+ALTER TABLE "task"
+  ADD COLUMN description TEXT,
+  ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'todo'
+    CHECK (status IN ('todo', 'in_progress', 'blocked', 'done')),
+  -- 0 (none) and up, the higher the more urgent
+  ADD COLUMN priority SMALLINT NOT NULL DEFAULT 0,
+  ADD COLUMN due_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE "task" SET status = 'done' WHERE done;
+
+CREATE INDEX task_due_at_idx ON "task" (due_at) WHERE status <> 'done';
+
+-- Keep `done` and `status` in sync
+-- (a `status` change sets `done`, otherwise a `done` change sets `status`)
+CREATE FUNCTION task_sync_done_status() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'INSERT' THEN
+    IF NEW.status = 'done' OR NEW.done THEN
+      NEW.status := 'done';
+      NEW.done := TRUE;
+    END IF;
+  ELSIF NEW.status IS DISTINCT FROM OLD.status THEN
+    NEW.done := NEW.status = 'done';
+  ELSIF NEW.done IS DISTINCT FROM OLD.done THEN
+    NEW.status := CASE WHEN NEW.done THEN 'done' ELSE 'todo' END;
+  END IF;
+  RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER task_sync_done_status
+  BEFORE INSERT OR UPDATE ON "task"
+  FOR EACH ROW EXECUTE FUNCTION task_sync_done_status();
@@ -0,0 +1,4 @@
+---- Labels (down)
+
+DROP TABLE IF EXISTS task_label;
+DROP TABLE IF EXISTS label;
@@ -0,0 +1,31 @@
+---- Labels (project scoped tags, assigned to the tasks of the project)
+
+CREATE TABLE label (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  project_id BIGINT NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+
+  -- Properties
+  name VARCHAR(128) NOT NULL,
+  color VARCHAR(32),
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP WITH TIME ZONE NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+  CONSTRAINT label_project_name_key UNIQUE (project_id, name)
+);
+
+-- Task/Label assignments (many-to-many)
+CREATE TABLE task_label (
+  task_id BIGINT NOT NULL REFERENCES "task"(id) ON DELETE CASCADE,
+  label_id BIGINT NOT NULL REFERENCES label(id) ON DELETE CASCADE,
+
+  PRIMARY KEY (task_id, label_id)
+);
+
+CREATE INDEX task_label_label_id_idx ON task_label (label_id);
@@ -0,0 +1,3 @@
+---- Task comments (down)
+
+DROP TABLE IF EXISTS comment;
@@ -0,0 +1,24 @@
+---- Task comments (threaded with the optional parent comment)
+
+CREATE TABLE comment (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  task_id BIGINT NOT NULL REFERENCES "task"(id) ON DELETE CASCADE,
+  -- The replies are deleted with their parent comment.
+  parent_id BIGINT REFERENCES comment(id) ON DELETE CASCADE,
+
+  -- Properties
+  author_id BIGINT NOT NULL,
+  body TEXT NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP WITH TIME ZONE NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX comment_task_id_idx ON comment (task_id);
+CREATE INDEX comment_parent_id_idx ON comment (parent_id);
@@ -0,0 +1,6 @@
+---- Subtasks (down)
+
+ALTER TABLE "task"
+  DROP COLUMN parent_task_id;
+
+ALTER TABLE "task" DROP CONSTRAINT IF EXISTS task_id_project_id_key;
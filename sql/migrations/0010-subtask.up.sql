@@ -0,0 +1,11 @@
+---- Subtasks (task hierarchy, within a project)
+
+-- Target of the parent foreign key, so that a parent task is always of the same project.
+ALTER TABLE "task" ADD CONSTRAINT task_id_project_id_key UNIQUE (id, project_id);
+
+ALTER TABLE "task"
+  ADD COLUMN parent_task_id BIGINT,
+  ADD CONSTRAINT task_parent_task_fkey FOREIGN KEY (parent_task_id, project_id)
+    REFERENCES "task" (id, project_id) ON DELETE CASCADE;
+
+CREATE INDEX task_parent_task_id_idx ON "task" (parent_task_id);
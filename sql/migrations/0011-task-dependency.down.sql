@@ -0,0 +1,10 @@
+---- Task dependencies (down)
+
+DROP TRIGGER IF EXISTS task_refresh_dependents_blocked ON "task";
+DROP FUNCTION IF EXISTS task_refresh_dependents_blocked();
+
+DROP TABLE IF EXISTS task_dependency;
+DROP FUNCTION IF EXISTS task_dependency_refresh_blocked();
+DROP FUNCTION IF EXISTS task_refresh_blocked(BIGINT[]);
+
+ALTER TABLE "task" DROP COLUMN blocked;
@@ -0,0 +1,65 @@
+---- Task dependencies (a blocker task blocks a task until done)
+-- NOTE: The cycles are rejected by the model layer (`TaskDependencyBmc::add`).
+
+CREATE TABLE task_dependency (
+  -- FK
+  task_id BIGINT NOT NULL REFERENCES "task"(id) ON DELETE CASCADE,
+  blocker_task_id BIGINT NOT NULL REFERENCES "task"(id) ON DELETE CASCADE,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+  PRIMARY KEY (task_id, blocker_task_id),
+  CONSTRAINT task_dependency_not_self_check CHECK (task_id <> blocker_task_id)
+);
+
+CREATE INDEX task_dependency_blocker_task_id_idx ON task_dependency (blocker_task_id);
+
+-- Computed: the task has open blockers (not done, and not deleted)
+ALTER TABLE "task" ADD COLUMN blocked BOOL NOT NULL DEFAULT FALSE;
+
+-- (only the stale rows are updated)
+CREATE FUNCTION task_refresh_blocked(task_ids BIGINT[]) RETURNS void AS $$
+  UPDATE "task" SET blocked = NOT blocked
+  WHERE id = ANY(task_ids)
+    AND blocked <> EXISTS (
+      SELECT 1 FROM task_dependency
+      JOIN "task" blocker ON blocker.id = task_dependency.blocker_task_id
+      WHERE task_dependency.task_id = "task".id
+        AND blocker.status <> 'done'
+        AND blocker.deleted_at IS NULL
+    );
+$$ LANGUAGE sql;
+
+-- Refresh the task on a dependency change
+CREATE FUNCTION task_dependency_refresh_blocked() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    PERFORM task_refresh_blocked(ARRAY[OLD.task_id]);
+  ELSE
+    PERFORM task_refresh_blocked(ARRAY[NEW.task_id]);
+  END IF;
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER task_dependency_refresh_blocked
+  AFTER INSERT OR DELETE ON task_dependency
+  FOR EACH ROW EXECUTE FUNCTION task_dependency_refresh_blocked();
+
+-- Refresh the dependent tasks on a blocker status (or soft delete) change
+CREATE FUNCTION task_refresh_dependents_blocked() RETURNS trigger AS $$
+BEGIN
+  PERFORM task_refresh_blocked(
+    ARRAY(SELECT task_id FROM task_dependency WHERE blocker_task_id = NEW.id)
+  );
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER task_refresh_dependents_blocked
+  AFTER UPDATE ON "task"
+  FOR EACH ROW
+  WHEN (OLD.status IS DISTINCT FROM NEW.status OR OLD.deleted_at IS DISTINCT FROM NEW.deleted_at)
+  EXECUTE FUNCTION task_refresh_dependents_blocked();
@@ -0,0 +1,3 @@
+---- Task attachments (down)
+
+DROP TABLE IF EXISTS attachment;
@@ -0,0 +1,29 @@
+---- Task attachments (the file metadata, the content is in the attachment storage)
+
+CREATE TABLE attachment (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  task_id BIGINT NOT NULL REFERENCES "task"(id) ON DELETE CASCADE,
+
+  -- Properties
+  uploader_id BIGINT NOT NULL,
+  filename varchar(256) NOT NULL,
+  content_type varchar(256) NOT NULL,
+  size BIGINT NOT NULL,
+  -- Hex encoded sha256 of the content.
+  sha256 CHAR(64) NOT NULL,
+  -- The key of the content in the attachment storage.
+  storage_key varchar(128) NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP WITH TIME ZONE NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+  CONSTRAINT attachment_storage_key_key UNIQUE (storage_key)
+);
+
+CREATE INDEX attachment_task_id_idx ON attachment (task_id);
@@ -0,0 +1,6 @@
+---- Project custom fields (down)
+
+DROP INDEX IF EXISTS task_custom_idx;
+ALTER TABLE "task" DROP COLUMN IF EXISTS custom;
+
+DROP TABLE IF EXISTS custom_field_def;
@@ -0,0 +1,29 @@
+---- Project custom fields (the definitions), and their task values (`task.custom`)
+
+CREATE TABLE custom_field_def (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  project_id BIGINT NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+
+  -- Properties
+  name varchar(128) NOT NULL,
+  -- 'text', 'number', 'bool', 'date', or 'select' (see `CustomFieldType`).
+  field_type varchar(16) NOT NULL,
+  -- The allowed values of a 'select' field (JSON array of strings).
+  options JSONB,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP WITH TIME ZONE NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+  CONSTRAINT custom_field_def_project_name_key UNIQUE (project_id, name)
+);
+
+-- The custom field values by field name (validated by the model layer).
+ALTER TABLE "task" ADD COLUMN custom JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX task_custom_idx ON "task" USING GIN (custom jsonb_path_ops);
@@ -0,0 +1,10 @@
+---- Full-text search (down)
+
+DROP INDEX IF EXISTS comment_search_tsv_idx;
+ALTER TABLE comment DROP COLUMN IF EXISTS search_tsv;
+
+DROP INDEX IF EXISTS project_search_tsv_idx;
+ALTER TABLE project DROP COLUMN IF EXISTS search_tsv;
+
+DROP INDEX IF EXISTS task_search_tsv_idx;
+ALTER TABLE "task" DROP COLUMN IF EXISTS search_tsv;
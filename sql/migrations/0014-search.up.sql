@@ -0,0 +1,24 @@
+---- Full-text search (see `model::search`)
+-- NOTE: The `english` text search configuration must match the `SEARCH_CONFIG` of the model layer.
+
+-- Task title (weight A) and description (weight B)
+ALTER TABLE "task" ADD COLUMN search_tsv tsvector GENERATED ALWAYS AS (
+  setweight(to_tsvector('english', title), 'A') ||
+  setweight(to_tsvector('english', coalesce(description, '')), 'B')
+) STORED;
+
+CREATE INDEX task_search_tsv_idx ON "task" USING GIN (search_tsv);
+
+-- Project name
+ALTER TABLE project ADD COLUMN search_tsv tsvector GENERATED ALWAYS AS (
+  setweight(to_tsvector('english', name), 'A')
+) STORED;
+
+CREATE INDEX project_search_tsv_idx ON project USING GIN (search_tsv);
+
+-- Comment body
+ALTER TABLE comment ADD COLUMN search_tsv tsvector GENERATED ALWAYS AS (
+  setweight(to_tsvector('english', body), 'B')
+) STORED;
+
+CREATE INDEX comment_search_tsv_idx ON comment USING GIN (search_tsv);
@@ -0,0 +1,4 @@
+---- Task assignee (down)
+
+DROP INDEX IF EXISTS task_assignee_id_idx;
+ALTER TABLE "task" DROP COLUMN IF EXISTS assignee_id;
@@ -0,0 +1,7 @@
+---- Task assignee (the user who should do the task)
+-- NOTE: The assignee access to the task project is checked by the model layer (`TaskBmc`).
+
+ALTER TABLE "task"
+  ADD COLUMN assignee_id BIGINT REFERENCES "user"(id) ON DELETE SET NULL;
+
+CREATE INDEX task_assignee_id_idx ON "task" (assignee_id);
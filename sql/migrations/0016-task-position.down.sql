@@ -0,0 +1,5 @@
+---- Task board positions (down)
+
+DROP INDEX IF EXISTS task_position_long_idx;
+DROP INDEX IF EXISTS task_board_position_idx;
+ALTER TABLE "task" DROP COLUMN IF EXISTS position;
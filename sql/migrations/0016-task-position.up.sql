@@ -0,0 +1,23 @@
+---- Task board positions (the order of the tasks in their project status column)
+-- NOTE: The position keys are generated by the model layer (see `model::task_position`),
+--       and compared bytewise (`COLLATE "C"`).
+
+ALTER TABLE "task" ADD COLUMN position TEXT COLLATE "C";
+
+-- The existing tasks in id order (as the keys `0000000001`, `0000000002`, ...,
+-- without their trailing zeros)
+UPDATE "task" SET position = ranked.position
+  FROM (
+    SELECT id,
+      rtrim(lpad((row_number() OVER (PARTITION BY project_id, status ORDER BY id))::text, 10, '0'), '0')
+        AS position
+    FROM "task"
+  ) AS ranked
+  WHERE "task".id = ranked.id;
+
+ALTER TABLE "task" ALTER COLUMN position SET NOT NULL;
+
+CREATE INDEX task_board_position_idx ON "task" (project_id, status, position, id);
+
+-- The columns to rebalance (the `16` must match the `POSITION_KEY_MAX_LEN` of the model layer)
+CREATE INDEX task_position_long_idx ON "task" (project_id, status) WHERE length(position) > 16;
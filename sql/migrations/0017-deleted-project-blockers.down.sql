@@ -0,0 +1,18 @@
+---- Deleted project blockers (down)
+
+DROP TRIGGER IF EXISTS project_refresh_dependents_blocked ON project;
+DROP FUNCTION IF EXISTS project_refresh_dependents_blocked();
+
+CREATE OR REPLACE FUNCTION task_refresh_blocked(task_ids BIGINT[]) RETURNS void AS $$
+  UPDATE "task" SET blocked = NOT blocked
+  WHERE id = ANY(task_ids)
+    AND blocked <> EXISTS (
+      SELECT 1 FROM task_dependency
+      JOIN "task" blocker ON blocker.id = task_dependency.blocker_task_id
+      WHERE task_dependency.task_id = "task".id
+        AND blocker.status <> 'done'
+        AND blocker.deleted_at IS NULL
+    );
+$$ LANGUAGE sql;
+
+SELECT task_refresh_blocked(ARRAY(SELECT DISTINCT task_id FROM task_dependency));
@@ -0,0 +1,39 @@
+---- Deleted project blockers (the tasks of a soft deleted project do not block)
+-- NOTE: The tasks of a deleted project are hidden by the model layer (`TaskBmc::not_deleted_parent_cond`).
+
+CREATE OR REPLACE FUNCTION task_refresh_blocked(task_ids BIGINT[]) RETURNS void AS $$
+  UPDATE "task" SET blocked = NOT blocked
+  WHERE id = ANY(task_ids)
+    AND blocked <> EXISTS (
+      SELECT 1 FROM task_dependency
+      JOIN "task" blocker ON blocker.id = task_dependency.blocker_task_id
+      JOIN project blocker_project ON blocker_project.id = blocker.project_id
+      WHERE task_dependency.task_id = "task".id
+        AND blocker.status <> 'done'
+        AND blocker.deleted_at IS NULL
+        AND blocker_project.deleted_at IS NULL
+    );
+$$ LANGUAGE sql;
+
+-- Refresh the tasks blocked by the project tasks on a project soft delete change
+CREATE FUNCTION project_refresh_dependents_blocked() RETURNS trigger AS $$
+BEGIN
+  PERFORM task_refresh_blocked(
+    ARRAY(
+      SELECT task_dependency.task_id FROM task_dependency
+      JOIN "task" blocker ON blocker.id = task_dependency.blocker_task_id
+      WHERE blocker.project_id = NEW.id
+    )
+  );
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER project_refresh_dependents_blocked
+  AFTER UPDATE ON project
+  FOR EACH ROW
+  WHEN (OLD.deleted_at IS DISTINCT FROM NEW.deleted_at)
+  EXECUTE FUNCTION project_refresh_dependents_blocked();
+
+-- The blockers of the already deleted projects
+SELECT task_refresh_blocked(ARRAY(SELECT DISTINCT task_id FROM task_dependency));